@@ -0,0 +1,202 @@
+use crate::{peer_table, BoxValue, CellPos, HouseId, SudokuBoard, SudokuError};
+
+/// Candidates of every square as bit masks, bit `d` for digit `d`, and
+/// which squares are placed.
+#[derive(Clone)]
+struct Singles<'a> {
+    candidates: [u16; 81],
+    placed: [bool; 81],
+    peers: &'a [Vec<usize>],
+    houses: &'a [[usize; 9]],
+}
+
+impl Singles<'_> {
+    /// Place the digit and remove it from the peers.  False when a peer is
+    /// left without candidates.
+    fn place(&mut self, i: usize, digit: u8) -> bool {
+        self.candidates[i] = 1 << digit;
+        self.placed[i] = true;
+        for &j in &self.peers[i] {
+            if !self.placed[j] {
+                self.candidates[j] &= !(1 << digit);
+                if self.candidates[j] == 0 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Place naked and hidden singles until there are none.  False on a
+    /// contradiction.
+    fn propagate(&mut self) -> bool {
+        loop {
+            let mut progress = false;
+            for i in 0..81 {
+                if !self.placed[i] && self.candidates[i].count_ones() == 1 {
+                    let digit = self.candidates[i].trailing_zeros() as u8;
+                    if !self.place(i, digit) {
+                        return false;
+                    }
+                    progress = true;
+                }
+            }
+            for house in self.houses {
+                for digit in 1..=9u8 {
+                    let mut places = house
+                        .iter()
+                        .filter(|&&i| self.candidates[i] & (1 << digit) != 0);
+                    match (places.next(), places.next()) {
+                        (None, _) => return false,
+                        (Some(&i), None) if !self.placed[i] => {
+                            if !self.place(i, digit) {
+                                return false;
+                            }
+                            progress = true;
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            if !progress {
+                return true;
+            }
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.placed.iter().all(|p| *p)
+    }
+
+    /// Look for `left` more squares, from `open[from..]`, whose digits from
+    /// the solution let singles finish the board, adding them to `chosen`.
+    fn search(
+        &self,
+        solution: &[u8; 81],
+        open: &[usize],
+        from: usize,
+        left: usize,
+        chosen: &mut Vec<usize>,
+    ) -> bool {
+        if self.is_solved() {
+            return true;
+        }
+        if left == 0 {
+            return false;
+        }
+        for (k, &i) in open.iter().enumerate().skip(from) {
+            // a square singles place anyway is no help
+            if self.placed[i] {
+                continue;
+            }
+            let mut next = self.clone();
+            chosen.push(i);
+            if next.place(i, solution[i])
+                && next.propagate()
+                && next.search(solution, open, k + 1, left - 1, chosen)
+            {
+                return true;
+            }
+            chosen.pop();
+        }
+        false
+    }
+}
+
+impl SudokuBoard {
+    /// Find a smallest backdoor of the board with respect to singles: squares
+    /// whose digits from the solution, once placed, let naked and hidden
+    /// singles alone finish the board.  Its size is a common measure of how
+    /// hard a puzzle is, most puzzles have one of 0, 1, or 2 squares.
+    ///
+    /// The solution is the one `solve_outcome_first` finds.  Backdoors of
+    /// up to `max_size` squares are tried, None means all of those are too
+    /// small.  Fails with `NotSolvable` when the board has no solution.
+    pub fn singles_backdoor(&self, max_size: usize) -> Result<Option<Vec<CellPos>>, SudokuError> {
+        let mut solved = self.clone();
+        solved.solve_first()?;
+        let solution = solved.as_bytes().map(|b| b - b'0');
+
+        let peers = peer_table();
+        let houses: Vec<[usize; 9]> = HouseId::all().map(|h| h.cells()).collect();
+        let mut singles = Singles {
+            candidates: [0; 81],
+            placed: [false; 81],
+            peers: &peers,
+            houses: &houses,
+        };
+        for (i, node) in self.board.iter().flatten().enumerate() {
+            match &node.value {
+                BoxValue::Known(v) => {
+                    singles.candidates[i] = 1 << v;
+                    singles.placed[i] = true;
+                }
+                BoxValue::Unknown(set) => {
+                    singles.candidates[i] = set.iter().fold(0, |mask, d| mask | 1 << d);
+                }
+            }
+        }
+        if !singles.propagate() {
+            return Err(SudokuError::NotSolvable(None));
+        }
+
+        let open: Vec<usize> = (0..81).filter(|&i| !singles.placed[i]).collect();
+        let mut chosen = Vec::new();
+        for size in 0..=max_size {
+            if singles.search(&solution, &open, 0, size, &mut chosen) {
+                let cells = chosen.iter().map(|&i| CellPos::from_index(i).unwrap());
+                return Ok(Some(cells.collect()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::{SudokuBoard, Technique};
+
+    /// Whether placing the solution's digits in the squares lets singles
+    /// finish the board.
+    fn opens(board: &SudokuBoard, backdoor: &[crate::CellPos]) -> bool {
+        let mut solution = board.clone();
+        solution.solve_first().unwrap();
+        let mut board = board.clone();
+        for pos in backdoor {
+            let (row, col) = (pos.row.get(), pos.col.get());
+            let digit = solution.get(at(row, col)).unwrap();
+            board.set(at(row, col), digit).unwrap();
+        }
+        let singles = [Technique::NakedSingle, Technique::HiddenSingle];
+        board.solve_with_techniques(&singles).unwrap().0.solved
+    }
+
+    #[test]
+    fn test_singles_backdoor() {
+        let easy = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        assert_eq!(easy.singles_backdoor(3).unwrap(), Some(vec![]));
+
+        // singles alone get stuck on the locked candidates
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let busy = SudokuBoard::fill_board(s).unwrap();
+        let backdoor = busy.singles_backdoor(3).unwrap().unwrap();
+        assert!(!backdoor.is_empty());
+        assert!(opens(&busy, &backdoor));
+        // nothing smaller does
+        assert_eq!(busy.singles_backdoor(backdoor.len() - 1).unwrap(), None);
+
+        assert_eq!(SudokuBoard::new().singles_backdoor(1).unwrap(), None);
+    }
+}
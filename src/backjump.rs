@@ -0,0 +1,200 @@
+use crate::{peer_table, BoxValue, SolveStats, SudokuBoard, SudokuError};
+
+/// A set of guesses, as bits by how deeply they are nested, that a
+/// deduction depends on.  A board has at most 81 unknown squares, so at
+/// most 81 nested guesses.
+type Levels = u128;
+
+/// A board during the search, which remembers why each alternative was
+/// removed.
+#[derive(Clone)]
+struct State {
+    // the known digit of each square, 0 when unknown
+    grid: Vec<u8>,
+    // the alternatives of each square, as bits 1 to 9
+    masks: Vec<u16>,
+    // the guesses each removed alternative of each square depends on
+    reasons: Vec<[Levels; 10]>,
+}
+
+impl State {
+    /// The guesses the removal of every alternative of the square at `i`,
+    /// other than `keep`, depends on.
+    fn removed_because(&self, i: usize, keep: u8) -> Levels {
+        (1..=9)
+            .filter(|d| *d != keep && self.masks[i] & (1 << d) == 0)
+            .fold(0, |why, d| why | self.reasons[i][d as usize])
+    }
+
+    /// Place the digit in the square at `i`, then any singles this leaves,
+    /// where `why` is the guesses the digit depends on.  On a dead end,
+    /// fails with the guesses that led to it.
+    fn place(
+        &mut self,
+        peers: &[Vec<usize>],
+        i: usize,
+        digit: u8,
+        why: Levels,
+    ) -> Result<(), Levels> {
+        let mut queue = vec![(i, digit, why)];
+        while let Some((i, digit, why)) = queue.pop() {
+            self.grid[i] = digit;
+            self.masks[i] = 1 << digit;
+            for &j in &peers[i] {
+                if self.grid[j] != 0 || self.masks[j] & (1 << digit) == 0 {
+                    continue;
+                }
+                self.masks[j] &= !(1 << digit);
+                self.reasons[j][digit as usize] = why;
+                match self.masks[j].count_ones() {
+                    0 => return Err(self.removed_because(j, 0)),
+                    1 => {
+                        let last = self.masks[j].trailing_zeros() as u8;
+                        queue.push((j, last, self.removed_because(j, last)));
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Guess the alternatives of the square with the fewest, nested `level`
+    /// deep.  When a guess fails for reasons that do not include this one,
+    /// trying the other alternatives cannot help, so the search jumps
+    /// straight back to the guess that did.
+    fn search(
+        &mut self,
+        peers: &[Vec<usize>],
+        level: usize,
+        stats: &mut SolveStats,
+    ) -> Result<(), Levels> {
+        let square = (0..81)
+            .filter(|i| self.grid[*i] == 0)
+            .min_by_key(|i| self.masks[*i].count_ones());
+        let i = match square {
+            Some(i) => i,
+            None => return Ok(()),
+        };
+        let this = 1 << level;
+        let mut conflict = self.removed_because(i, 0);
+        for digit in (1..=9).filter(|d| self.masks[i] & (1 << d) != 0) {
+            stats.guesses += 1;
+            let mut next = self.clone();
+            let result = next
+                .place(peers, i, digit, this)
+                .and_then(|_| next.search(peers, level + 1, stats));
+            match result {
+                Ok(()) => {
+                    *self = next;
+                    return Ok(());
+                }
+                Err(why) => {
+                    stats.backtracks += 1;
+                    if why & this == 0 {
+                        return Err(why);
+                    }
+                    conflict |= why & !this;
+                }
+            }
+        }
+        Err(conflict)
+    }
+}
+
+impl SudokuBoard {
+    /// Fill in the solution `solve_outcome_first` finds, but when a line of
+    /// guesses ends in a contradiction, jump back to the latest guess the
+    /// contradiction depends on instead of the latest guess made.  Every
+    /// removed alternative remembers the guesses that removed it, so the
+    /// cause of a dead end is known.
+    ///
+    /// Only guesses that cannot lead to a solution are skipped, so the
+    /// solution found is the same as with `solve_outcome_first`, usually
+    /// after fewer guesses on the hardest puzzles.  Alternatives removed by hand
+    /// are kept.
+    pub fn solve_backjumping(&mut self) -> Result<SolveStats, SudokuError> {
+        let peers = peer_table();
+        let mut state = State {
+            grid: vec![0; 81],
+            masks: vec![0; 81],
+            reasons: vec![[0; 10]; 81],
+        };
+        for (i, node) in self.board.iter().flatten().enumerate() {
+            match &node.value {
+                BoxValue::Known(v) => {
+                    state.grid[i] = *v as u8;
+                    state.masks[i] = 1 << v;
+                }
+                BoxValue::Unknown(set) => {
+                    state.masks[i] = set.iter().fold(0, |mask, d| mask | 1 << d);
+                }
+            }
+        }
+        let mut stats = SolveStats::default();
+        let singles: Vec<usize> = (0..81)
+            .filter(|i| state.grid[*i] == 0 && state.masks[*i].count_ones() <= 1)
+            .collect();
+        for i in singles {
+            if state.grid[i] != 0 {
+                continue;
+            }
+            let digit = state.masks[i].trailing_zeros() as u8;
+            if state.masks[i] == 0 || state.place(&peers, i, digit, 0).is_err() {
+                return Err(SudokuError::NotSolvable(None));
+            }
+        }
+        state
+            .search(&peers, 0, &mut stats)
+            .map_err(|_| SudokuError::NotSolvable(None))?;
+        for (node, digit) in self.board.iter_mut().flatten().zip(state.grid) {
+            node.value = BoxValue::Known(i32::from(digit));
+        }
+        self.unknown_values = 0;
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SudokuBoard, SudokuError};
+
+    #[test]
+    fn test_solve_backjumping() {
+        let s = concat!(
+            "---------",
+            "-----3-85",
+            "--1-2----",
+            "---5-7---",
+            "--4---1--",
+            "-9-------",
+            "5------73",
+            "--2-1----",
+            "----4---9"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        let mut expected = puzzle.clone();
+        let chronological = expected.solve_first().unwrap();
+        let mut sboard = puzzle.clone();
+        let stats = sboard.solve_backjumping().unwrap();
+        assert!(sboard == expected && sboard.is_solved());
+        assert!(stats.guesses < chronological.guesses);
+
+        // puzzles with several solutions end up with the same one
+        let mut expected = SudokuBoard::new();
+        expected.solve_first().unwrap();
+        let mut empty = SudokuBoard::new();
+        empty.solve_backjumping().unwrap();
+        assert!(empty == expected);
+
+        // 9 cannot go anywhere in row 1
+        let s = "---123456--9".to_string() + &"-".repeat(69);
+        let mut unsolvable = SudokuBoard::fill_board(&s).unwrap();
+        let before = unsolvable.clone();
+        assert!(matches!(
+            unsolvable.solve_backjumping(),
+            Err(SudokuError::NotSolvable(None))
+        ));
+        assert!(unsolvable == before);
+    }
+}
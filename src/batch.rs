@@ -0,0 +1,40 @@
+use rayon::prelude::*;
+
+use crate::{SolveOutcome, SudokuBoard};
+
+/// Solve many boards at once on the rayon thread pool, each like
+/// `solve_outcome`.  The outcomes come back in the order of the boards.
+pub fn solve_batch(boards: &[SudokuBoard]) -> Vec<SolveOutcome> {
+    boards.par_iter().map(SudokuBoard::solve_outcome).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::solve_batch;
+    use crate::{SolveOutcome, SudokuBoard};
+
+    #[test]
+    fn test_solve_batch() {
+        let puzzle = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        let boards = vec![puzzle.clone(), SudokuBoard::new(), puzzle];
+        let outcomes = solve_batch(&boards);
+        assert_eq!(outcomes.len(), 3);
+        assert!(matches!(outcomes[0], SolveOutcome::Unique(_)));
+        assert!(matches!(outcomes[1], SolveOutcome::Multiple(_, _)));
+        match (&outcomes[0], &outcomes[2]) {
+            (SolveOutcome::Unique(a), SolveOutcome::Unique(b)) => assert_eq!(a, b),
+            _ => panic!("{:?}", outcomes),
+        }
+    }
+}
@@ -1,29 +1,48 @@
 use std::env;
-use std::fs;
+use std::panic;
+use std::process;
 
-pub fn main() {
-    let file_name = "test_sudoku.txt";
+mod cli;
 
-    let file_contents = fs::read_to_string(file_name).unwrap();
+use cli::{Command, Failure};
 
-    let mut collected_lines = 0;
-    let mut grid: String = String::new();
-    for line in file_contents.split("\n") {
-        if line.contains("Grid") {
-            println!("{}", line);
-        } else {
-            collected_lines += 1;
-            grid += line;
-            if collected_lines == 9 {
-                let mut solver = sudoku::SudokuBoard::fill_board(&grid).unwrap();
-                solver.solve().unwrap();
-                let to_print = solver.print_board();
-                for r in 0..9 {
-                    println!("{}", &to_print.as_str()[r * 9..(r + 1) * 9]);
-                }
-                collected_lines = 0;
-                grid = String::new();
-            }
+pub fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    // a panic is a bug in the program, report it as an internal error
+    let result = panic::catch_unwind(|| match command {
+        Command::Help => {
+            print!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Version => {
+            println!("sudoku_bin {}", env!("CARGO_PKG_VERSION"));
+            Ok(())
         }
+        Command::Solve(options) => cli::solve::run(&options),
+        Command::Generate(options) => cli::generate::run(&options),
+        Command::Book(options) => cli::book::run(&options),
+        Command::Rate(options) => cli::rate::run(&options),
+        Command::Validate(options) => cli::validate::run(&options),
+        Command::Verify(options) => cli::verify::run(&options),
+        Command::Count(options) => cli::count::run(&options),
+        Command::Hint(options) => cli::hint::run(&options),
+        Command::Compare(options) => cli::compare::run(&options),
+        Command::Tui(options) => cli::tui::run(&options),
+        Command::Watch(options) => cli::watch::run(&options),
+        Command::Bench(options) => cli::bench::run(&options),
+        Command::ServeStdio => cli::serve::run(),
+    })
+    .unwrap_or_else(|_| Err(Failure::Internal("the program crashed".to_string())));
+    if let Err(failure) = result {
+        eprintln!("error: {}", failure.message());
+        process::exit(failure.exit_code());
     }
 }
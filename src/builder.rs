@@ -0,0 +1,245 @@
+#[cfg(feature = "variants")]
+use crate::Variant;
+use crate::{CellPos, Solver, SolverOptions, SudokuBoard, SudokuError};
+
+/// Collects the givens of a board, alternatives to remove from it, the
+/// rules of a variant, and the options of the solver, checking all of them
+/// together when the board is built.
+#[derive(Debug, Clone, Default)]
+pub struct SudokuBoardBuilder {
+    // givens read from a grid, or the number of squares when it is not 81
+    grid: Option<Result<Vec<Option<u8>>, usize>>,
+    givens: Vec<(CellPos, u8)>,
+    eliminations: Vec<(CellPos, u8)>,
+    #[cfg(feature = "variants")]
+    variant: Option<Variant>,
+    solver_options: SolverOptions,
+}
+
+impl SudokuBoardBuilder {
+    pub fn new() -> SudokuBoardBuilder {
+        SudokuBoardBuilder::default()
+    }
+
+    /// Take givens from a string read the way `fill_board` reads it.  The
+    /// string has to describe all 81 squares.
+    pub fn grid(mut self, s: &str) -> SudokuBoardBuilder {
+        let cells: Vec<Option<u8>> = s
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '-')
+            .map(|c| c.to_digit(10).filter(|d| *d != 0).map(|d| d as u8))
+            .collect();
+        self.grid = Some(match cells.len() {
+            81 => Ok(cells),
+            n => Err(n),
+        });
+        self
+    }
+
+    /// Give the digit of a square.
+    pub fn given(mut self, pos: CellPos, digit: u8) -> SudokuBoardBuilder {
+        self.givens.push((pos, digit));
+        self
+    }
+
+    /// Remove a digit from the alternatives of a square once the givens are
+    /// placed.
+    pub fn eliminate(mut self, pos: CellPos, digit: u8) -> SudokuBoardBuilder {
+        self.eliminations.push((pos, digit));
+        self
+    }
+
+    /// The givens have to follow the rules of a variant as well.  The
+    /// variant only checks the givens and is not kept with the board, so
+    /// pass it to `solve_variant` again to solve under its rules.
+    #[cfg(feature = "variants")]
+    pub fn variant(mut self, variant: Variant) -> SudokuBoardBuilder {
+        self.variant = Some(variant);
+        self
+    }
+
+    /// Options of the `Solver` made by `build_with_solver`.
+    pub fn solver_options(mut self, options: SolverOptions) -> SudokuBoardBuilder {
+        self.solver_options = options;
+        self
+    }
+
+    /// Check everything collected and make the board.
+    ///
+    /// Fails with `InvalidCellCount` when the grid is not 81 squares,
+    /// `InvalidRange` for digits out of range, a variant region
+    /// that is not 9 different squares, or a solver that remembers states
+    /// in a table of size 0, `AlreadyKnown` when a square is given two
+    /// different digits, `ConflictingGivens` when a digit is given twice in
+    /// a house or against the rules of the variant, and `NotSolvable` when
+    /// the eliminations leave a square without alternatives.
+    pub fn build(self) -> Result<SudokuBoard, SudokuError> {
+        if self.solver_options.transpositions && self.solver_options.table_size == 0 {
+            return Err(SudokuError::InvalidRange);
+        }
+        let mut cells = match self.grid {
+            Some(Ok(cells)) => cells,
+            Some(Err(n)) => return Err(SudokuError::InvalidCellCount(n)),
+            None => vec![None; 81],
+        };
+        for (pos, digit) in self.givens {
+            if !(1..=9).contains(&digit) {
+                return Err(SudokuError::InvalidRange);
+            }
+            let cell = &mut cells[pos.index()];
+            match cell {
+                Some(known) if *known != digit => {
+                    return Err(SudokuError::AlreadyKnown {
+                        row: pos.row.get(),
+                        col: pos.col.get(),
+                    })
+                }
+                _ => *cell = Some(digit),
+            }
+        }
+
+        #[cfg(feature = "variants")]
+        if let Some(variant) = &self.variant {
+            variant.check_givens(&cells)?;
+        }
+
+        let mut board = SudokuBoard::new();
+        for (i, cell) in cells.iter().enumerate() {
+            if let Some(digit) = cell {
+                board.mark_as_given(i / 9 + 1, i % 9 + 1, *digit as i32)?;
+            }
+        }
+        for (pos, digit) in self.eliminations {
+            board.eliminate(pos, digit)?;
+        }
+        Ok(board)
+    }
+
+    /// Make the board like `build`, with a `Solver` using the options
+    /// given.
+    pub fn build_with_solver(self) -> Result<(SudokuBoard, Solver), SudokuError> {
+        let options = self.solver_options.clone();
+        Ok((self.build()?, Solver::new(options)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SudokuBoardBuilder;
+    use crate::pos::at;
+    use crate::{SolverOptions, SudokuError};
+
+    #[test]
+    fn test_builder() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let board = SudokuBoardBuilder::new()
+            .grid(&s)
+            .given(at(9, 9), 3)
+            .given(at(1, 1), 4)
+            .eliminate(at(9, 8), 1)
+            .build()
+            .unwrap();
+        assert_eq!(board.get(at(9, 9)), Some(3));
+        assert_eq!(board.print_given_mask().matches('G').count(), 3);
+        assert_eq!(board.candidates(at(9, 8)).next(), Some(2));
+
+        let err = |builder: SudokuBoardBuilder| builder.build().err().unwrap();
+        assert!(matches!(
+            err(SudokuBoardBuilder::new().grid("123")),
+            SudokuError::InvalidCellCount(3)
+        ));
+        assert!(matches!(
+            err(SudokuBoardBuilder::new().given(at(1, 1), 0)),
+            SudokuError::InvalidRange
+        ));
+        assert!(matches!(
+            err(SudokuBoardBuilder::new().grid(&s).given(at(1, 1), 5)),
+            SudokuError::AlreadyKnown { row: 1, col: 1 }
+        ));
+        assert!(matches!(
+            err(SudokuBoardBuilder::new().grid(&s).given(at(3, 4), 8)),
+            SudokuError::ConflictingGivens {
+                digit: 8,
+                first: (1, 6),
+                second: (3, 4)
+            }
+        ));
+        assert!(matches!(
+            err(SudokuBoardBuilder::new()
+                .given(at(2, 2), 1)
+                .eliminate(at(2, 2), 1)),
+            SudokuError::AlreadyKnown { row: 2, col: 2 }
+        ));
+    }
+
+    #[test]
+    fn test_builder_solver_options() {
+        let options = SolverOptions {
+            transpositions: false,
+            table_size: 0,
+        };
+        let (mut board, mut solver) = SudokuBoardBuilder::new()
+            .given(at(1, 1), 4)
+            .solver_options(options)
+            .build_with_solver()
+            .unwrap();
+        solver.solve(&mut board).unwrap();
+        assert!(board.is_solved());
+        assert_eq!(solver.skipped(), 0);
+
+        let options = SolverOptions {
+            table_size: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            SudokuBoardBuilder::new().solver_options(options).build(),
+            Err(SudokuError::InvalidRange)
+        ));
+    }
+
+    #[cfg(feature = "variants")]
+    #[test]
+    fn test_builder_variant() {
+        use crate::Variant;
+
+        let mut board = SudokuBoardBuilder::new()
+            .given(at(1, 1), 1)
+            .given(at(2, 3), 2)
+            .variant(Variant::diagonals())
+            .build()
+            .unwrap();
+        board.solve_variant(&Variant::diagonals()).unwrap();
+
+        let err = |builder: SudokuBoardBuilder| builder.build().err().unwrap();
+        assert!(matches!(
+            err(SudokuBoardBuilder::new()
+                .given(at(1, 1), 5)
+                .given(at(9, 9), 5)
+                .variant(Variant::diagonals())),
+            SudokuError::ConflictingGivens {
+                digit: 5,
+                first: (1, 1),
+                second: (9, 9)
+            }
+        ));
+        let anti_knight = Variant {
+            anti_knight: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            err(SudokuBoardBuilder::new()
+                .given(at(1, 1), 3)
+                .given(at(2, 3), 3)
+                .variant(anti_knight)),
+            SudokuError::ConflictingGivens { digit: 3, .. }
+        ));
+        let short = Variant {
+            regions: vec![vec![(1, 1), (1, 2)]],
+            anti_knight: false,
+        };
+        assert!(matches!(
+            err(SudokuBoardBuilder::new().variant(short)),
+            SudokuError::InvalidRange
+        ));
+    }
+}
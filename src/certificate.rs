@@ -0,0 +1,228 @@
+use crate::{peer_table, BoxValue, CellPos, Node, SudokuBoard, SudokuError};
+
+/// A proof that a board has no solution, which `check` can verify without
+/// trusting the solver.  Squares are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Certificate {
+    // Two squares of one house already hold the same digit
+    Clash {
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+    // The unknown square has every digit somewhere in its row, column, or box
+    NoCandidates {
+        row: usize,
+        col: usize,
+    },
+    // The unknown square can only hold the digit.  The rest of the proof is
+    // for the board with the digit placed
+    Single {
+        row: usize,
+        col: usize,
+        digit: u8,
+        then: Box<Certificate>,
+    },
+    // Each digit the unknown square can hold, with a proof that placing it
+    // leads nowhere
+    Split {
+        row: usize,
+        col: usize,
+        cases: Vec<(u8, Certificate)>,
+    },
+}
+
+/// The known digits of a board, 0 for an unknown square.
+type Grid = [u8; 81];
+
+/// The digits not known in the houses of the square at `i`.
+fn candidates(grid: &Grid, peers: &[Vec<usize>], i: usize) -> Vec<u8> {
+    (1..=9)
+        .filter(|d| peers[i].iter().all(|&j| grid[j] != *d))
+        .collect()
+}
+
+/// The index of a square, or None when it is off the board.
+fn index((row, col): (usize, usize)) -> Option<usize> {
+    (row.wrapping_sub(1) < 9 && col.wrapping_sub(1) < 9).then(|| (row - 1) * 9 + col - 1)
+}
+
+/// The index of an unknown square, or None when it is off the board or
+/// known.
+fn unknown(grid: &Grid, row: usize, col: usize) -> Option<usize> {
+    index((row, col)).filter(|i| grid[*i] == 0)
+}
+
+fn holds(grid: &Grid, peers: &[Vec<usize>], certificate: &Certificate) -> bool {
+    match certificate {
+        Certificate::Clash { first, second } => match (index(*first), index(*second)) {
+            (Some(a), Some(b)) => grid[a] != 0 && grid[a] == grid[b] && peers[a].contains(&b),
+            _ => false,
+        },
+        Certificate::NoCandidates { row, col } => match unknown(grid, *row, *col) {
+            Some(i) => candidates(grid, peers, i).is_empty(),
+            None => false,
+        },
+        Certificate::Single {
+            row,
+            col,
+            digit,
+            then,
+        } => match unknown(grid, *row, *col) {
+            Some(i) if candidates(grid, peers, i) == [*digit] => {
+                let mut next = *grid;
+                next[i] = *digit;
+                holds(&next, peers, then)
+            }
+            _ => false,
+        },
+        Certificate::Split { row, col, cases } => match unknown(grid, *row, *col) {
+            Some(i) => candidates(grid, peers, i).into_iter().all(|d| {
+                cases.iter().any(|(digit, case)| {
+                    let mut next = *grid;
+                    next[i] = d;
+                    *digit == d && holds(&next, peers, case)
+                })
+            }),
+            None => false,
+        },
+    }
+}
+
+impl Certificate {
+    /// Whether the certificate proves that `puzzle` has no solution.  Only
+    /// the known digits of the puzzle are looked at, and the check follows
+    /// the rules of the game alone, so it does not depend on the solver.
+    pub fn check(&self, puzzle: &SudokuBoard) -> bool {
+        let mut grid = [0; 81];
+        for (cell, node) in grid.iter_mut().zip(puzzle.board.iter().flatten()) {
+            if let BoxValue::Known(v) = node.value {
+                *cell = v as u8;
+            }
+        }
+        holds(&grid, &peer_table(), self)
+    }
+}
+
+impl SudokuBoard {
+    /// A proof that the known digits of the board leave no solution, or
+    /// None when there is one.  Alternatives removed by hand are not relied
+    /// on.
+    pub fn unsolvability_certificate(&self) -> Option<Certificate> {
+        let known = |node: &Node| match node.value {
+            BoxValue::Known(v) => Some(v),
+            BoxValue::Unknown(_) => None,
+        };
+        for a in self.board.iter().flatten().filter(|a| known(a).is_some()) {
+            let clash = self.peers(a.row, a.col).into_iter().find(|&(r, c)| {
+                (r, c) > (a.row, a.col) && known(&self.board[r - 1][c - 1]) == known(a)
+            });
+            if let Some(second) = clash {
+                return Some(Certificate::Clash {
+                    first: (a.row, a.col),
+                    second,
+                });
+            }
+        }
+        // work out the alternatives from the known digits alone
+        let mut board = self.clone();
+        for (row, col, _) in self.unsolved_cells() {
+            let known = board.peer_digits(row, col);
+            board.board[row - 1][col - 1].value =
+                BoxValue::Unknown((1..=9).filter(|d| !known.contains(d)).collect());
+        }
+        board.refute()
+    }
+
+    /// Prove there is no solution by placing singles, and trying each
+    /// alternative of a square when there are none.  The alternatives of
+    /// the board are those the known digits leave.
+    fn refute(&self) -> Option<Certificate> {
+        if let Some((row, col, _)) = self.unsolved_cells().find(|(_, _, n)| *n == 0) {
+            return Some(Certificate::NoCandidates { row, col });
+        }
+        let (row, col, alternatives) = match self.unsolved_cells().find(|(_, _, n)| *n == 1) {
+            Some((row, col, _)) => (
+                row,
+                col,
+                self.candidates(CellPos::new(row, col).ok()?)
+                    .map(i32::from)
+                    .collect(),
+            ),
+            None => self.guess_square()?,
+        };
+        let mut cases = Vec::new();
+        for digit in alternatives {
+            let mut next = self.clone();
+            let case = match next.mark_as_known(row, col, digit) {
+                Ok(()) => next.refute()?,
+                Err(SudokuError::NotSolvable(Some(w))) => Certificate::NoCandidates {
+                    row: w.row,
+                    col: w.col,
+                },
+                Err(_) => return None,
+            };
+            cases.push((digit as u8, case));
+        }
+        if cases.len() == 1 {
+            let (digit, then) = cases.pop()?;
+            return Some(Certificate::Single {
+                row,
+                col,
+                digit,
+                then: Box::new(then),
+            });
+        }
+        Some(Certificate::Split { row, col, cases })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Certificate;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_certificate() {
+        // 9 cannot go anywhere in row 1, though no square is empty yet
+        let s = "---123456--9".to_string() + &"-".repeat(69);
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(sboard.count_solutions(1), 0);
+        let certificate = sboard.unsolvability_certificate().unwrap();
+        assert!(matches!(
+            certificate,
+            Certificate::Split { row: 1, col: 1, ref cases } if cases.len() == 2
+        ));
+        assert!(certificate.check(&sboard));
+
+        // a proof of something else does not check
+        let wrong = Certificate::NoCandidates { row: 1, col: 1 };
+        assert!(!wrong.check(&sboard));
+        if let Certificate::Split {
+            row,
+            col,
+            mut cases,
+        } = certificate
+        {
+            cases.pop();
+            assert!(!Certificate::Split { row, col, cases }.check(&sboard));
+        }
+        let off_board = Certificate::NoCandidates { row: 0, col: 10 };
+        assert!(!off_board.check(&sboard));
+
+        let mut clash = SudokuBoard::new();
+        clash.mark_as_known(5, 5, 4).unwrap();
+        clash.mark_as_known(4, 6, 4).unwrap();
+        let certificate = clash.unsolvability_certificate().unwrap();
+        assert_eq!(
+            certificate,
+            Certificate::Clash {
+                first: (4, 6),
+                second: (5, 5)
+            }
+        );
+        assert!(certificate.check(&clash));
+
+        let solvable = SudokuBoard::fill_board(&s[..9]).unwrap();
+        assert!(solvable.unsolvability_certificate().is_none());
+    }
+}
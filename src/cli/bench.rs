@@ -0,0 +1,92 @@
+use super::solve::{read_input, read_puzzles};
+use super::{BenchOptions, Failure};
+use std::time::{Duration, Instant};
+use sudoku::SudokuBoard;
+
+/// The time below which `p` percent of the sorted times fall, using the
+/// nearest rank.
+pub fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Solve every board `runs` times, returning the time of each solve and the
+/// time of the whole benchmark.
+pub fn bench(boards: &[SudokuBoard], runs: usize) -> (Vec<Duration>, Duration) {
+    let mut times = Vec::with_capacity(boards.len() * runs);
+    let start = Instant::now();
+    for _ in 0..runs {
+        for board in boards {
+            let solve_start = Instant::now();
+            // unsolvable puzzles are timed all the same
+            let _ = board.solve_outcome_first();
+            times.push(solve_start.elapsed());
+        }
+    }
+    (times, start.elapsed())
+}
+
+pub fn run(options: &BenchOptions) -> Result<(), Failure> {
+    let contents = read_input(options.input.as_deref())?;
+    let mut boards = Vec::new();
+    for (i, puzzle) in read_puzzles(&contents).iter().enumerate() {
+        let board = puzzle.board().map_err(|e| {
+            Failure::InvalidInput(format!("{} is invalid: {}", puzzle.describe(i + 1), e))
+        })?;
+        boards.push(board);
+    }
+    if boards.is_empty() {
+        return Err(Failure::InvalidInput("no puzzles to solve".to_string()));
+    }
+
+    let (mut times, total) = bench(&boards, options.runs);
+    times.sort();
+    println!(
+        "{} puzzles x {} runs = {} solves in {:?}",
+        boards.len(),
+        options.runs,
+        times.len(),
+        total
+    );
+    println!("  min     {:?}", times[0]);
+    println!("  median  {:?}", percentile(&times, 50));
+    println!("  p99     {:?}", percentile(&times, 99));
+    println!("  max     {:?}", times[times.len() - 1]);
+    println!(
+        "  throughput {:.0} puzzles/s",
+        times.len() as f64 / total.as_secs_f64()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bench, percentile};
+    use crate::cli::solve::read_puzzles;
+    use std::time::Duration;
+    use sudoku::SudokuBoard;
+
+    #[test]
+    fn test_percentile() {
+        let times: Vec<Duration> = (1..=200).map(Duration::from_micros).collect();
+        assert_eq!(percentile(&times, 50), Duration::from_micros(100));
+        assert_eq!(percentile(&times, 99), Duration::from_micros(198));
+        assert_eq!(percentile(&times, 100), Duration::from_micros(200));
+        assert_eq!(percentile(&times[..1], 99), Duration::from_micros(1));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_bench() {
+        let contents = std::fs::read_to_string("test_sudoku.txt").unwrap();
+        let boards: Vec<SudokuBoard> = read_puzzles(&contents)
+            .iter()
+            .map(|p| p.board().unwrap())
+            .collect();
+        let (times, _) = bench(&boards[..2], 3);
+        assert_eq!(times.len(), 6);
+    }
+}
@@ -0,0 +1,281 @@
+use super::generate::{no_puzzle, seed_or_clock};
+use super::progress::Progress;
+use super::solve::fill_in;
+use super::{BookFormat, BookOptions, Failure};
+use std::collections::BTreeMap;
+use std::fs;
+use sudoku::{BoxValue, Difficulty, Generator, GeneratorOptions, SudokuBoard, HTML_STYLE};
+
+/// A4, in PDF points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const PAGE_MARGIN: f32 = 40.0;
+/// Room above each grid for its title.
+const TITLE_HEIGHT: f32 = 24.0;
+
+/// A titled board on a page of the book.
+type Entry<'a> = (String, &'a SudokuBoard);
+
+pub fn run(options: &BookOptions) -> Result<(), Failure> {
+    let seed = seed_or_clock(options.seed);
+    let mut generators: BTreeMap<Difficulty, Generator> = BTreeMap::new();
+    let progress = Progress::new(options.count, None);
+    let mut puzzles = Vec::with_capacity(options.count);
+    for i in 0..options.count {
+        // a mixed book gets easier puzzles first
+        let difficulty = options
+            .difficulty
+            .unwrap_or(Difficulty::ALL[i * Difficulty::ALL.len() / options.count]);
+        let generator = generators.entry(difficulty).or_insert_with(|| {
+            Generator::new(GeneratorOptions {
+                seed: seed.wrapping_add(difficulty as u64),
+                difficulty: Some(difficulty),
+                ..Default::default()
+            })
+        });
+        let puzzle = generator
+            .generate()
+            .ok_or_else(|| no_puzzle(Some(difficulty), None))?;
+        puzzles.push((difficulty, puzzle));
+        progress.tick();
+    }
+    progress.finish();
+
+    let solutions: Vec<SudokuBoard> = puzzles
+        .iter()
+        .map(|(_, puzzle)| {
+            fill_in(puzzle).map_or_else(|_| puzzle.clone(), |(solution, _)| solution)
+        })
+        .collect();
+    let puzzle_entries: Vec<Entry> = puzzles
+        .iter()
+        .enumerate()
+        .map(|(i, (difficulty, puzzle))| {
+            (format!("Puzzle {} ({})", i + 1, difficulty.name()), puzzle)
+        })
+        .collect();
+    let solution_entries: Vec<Entry> = solutions
+        .iter()
+        .enumerate()
+        .map(|(i, solution)| (format!("Solution {}", i + 1), solution))
+        .collect();
+
+    let book = match options.format {
+        BookFormat::Html => {
+            html_book(&puzzle_entries, &solution_entries, options.per_page).into_bytes()
+        }
+        BookFormat::Pdf => pdf_book(&puzzle_entries, &solution_entries, options.per_page),
+    };
+    fs::write(&options.out, book)
+        .map_err(|e| Failure::Internal(format!("could not write `{}`: {}", options.out, e)))
+}
+
+/// The number of boards side by side on a page of `per_page`.
+fn columns(per_page: usize) -> usize {
+    if per_page == 1 {
+        1
+    } else {
+        2
+    }
+}
+
+/// A whole HTML page with the puzzles `per_page` to a printed page, then
+/// the solutions.
+fn html_book(puzzles: &[Entry], solutions: &[Entry], per_page: usize) -> String {
+    let mut html = vec![
+        "<!DOCTYPE html>".to_string(),
+        "<html>".to_string(),
+        "<head>".to_string(),
+        "<meta charset=\"utf-8\">".to_string(),
+        "<title>Sudoku</title>".to_string(),
+        HTML_STYLE.to_string(),
+        format!(
+            "<style>\n.page {{ display: grid; grid-template-columns: repeat({}, 1fr); gap: 2em; break-after: page; }}\n</style>",
+            columns(per_page)
+        ),
+        "</head>".to_string(),
+        "<body>".to_string(),
+    ];
+    for (heading, entries) in [("Puzzles", puzzles), ("Solutions", solutions)] {
+        html.push(format!("<h1>{}</h1>", heading));
+        for page in entries.chunks(per_page) {
+            html.push("<section class=\"page\">".to_string());
+            for (title, board) in page {
+                html.push(format!(
+                    "<div>\n<h2>{}</h2>\n{}\n</div>",
+                    title,
+                    board.to_html_table(false)
+                ));
+            }
+            html.push("</section>".to_string());
+        }
+    }
+    html.push("</body>".to_string());
+    html.push("</html>".to_string());
+    html.join("\n") + "\n"
+}
+
+/// Escape text for a PDF string.
+fn pdf_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('(', "\\(")
+        .replace(')', "\\)")
+}
+
+/// The drawing commands of one page of the PDF, with its boards laid out
+/// in a grid of `per_page` slots.  Givens are bold.
+fn pdf_page(entries: &[Entry], per_page: usize) -> String {
+    let cols = columns(per_page);
+    let rows = per_page.div_ceil(cols);
+    let slot_width = (PAGE_WIDTH - 2.0 * PAGE_MARGIN) / cols as f32;
+    let slot_height = (PAGE_HEIGHT - 2.0 * PAGE_MARGIN) / rows as f32;
+    let side = (slot_width.min(slot_height - TITLE_HEIGHT) * 0.9).max(9.0);
+    let cell = side / 9.0;
+    let mut ops = Vec::new();
+    for (k, (title, board)) in entries.iter().enumerate() {
+        let left = PAGE_MARGIN + (k % cols) as f32 * slot_width + (slot_width - side) / 2.0;
+        let top = PAGE_HEIGHT - PAGE_MARGIN - (k / cols) as f32 * slot_height;
+        ops.push(format!(
+            "BT /F2 12 Tf {:.1} {:.1} Td ({}) Tj ET",
+            left,
+            top - 14.0,
+            pdf_text(title)
+        ));
+        let top = top - TITLE_HEIGHT;
+        for i in 0..=9 {
+            let width = if i % 3 == 0 { 2.0 } else { 0.5 };
+            let at = i as f32 * cell;
+            ops.push(format!(
+                "{:.1} w {:.1} {:.1} m {:.1} {:.1} l S {:.1} {:.1} m {:.1} {:.1} l S",
+                width,
+                left + at,
+                top,
+                left + at,
+                top - side,
+                left,
+                top - at,
+                left + side,
+                top - at
+            ));
+        }
+        let size = cell * 0.6;
+        for node in board.rows().flatten() {
+            if let BoxValue::Known(v) = node.value {
+                let font = if node.is_given() { "F2" } else { "F1" };
+                // Helvetica digits are 0.556 of the font size wide
+                let x = left + (node.col as f32 - 0.5) * cell - 0.278 * size;
+                let y = top - (node.row as f32 - 0.5) * cell - 0.35 * size;
+                ops.push(format!(
+                    "BT /{} {:.1} Tf {:.1} {:.1} Td ({}) Tj ET",
+                    font, size, x, y, v
+                ));
+            }
+        }
+    }
+    ops.join("\n")
+}
+
+/// A PDF with the puzzles `per_page` to a page, then the solutions, using
+/// the Helvetica fonts every PDF reader has.
+fn pdf_book(puzzles: &[Entry], solutions: &[Entry], per_page: usize) -> Vec<u8> {
+    let pages: Vec<String> = puzzles
+        .chunks(per_page)
+        .chain(solutions.chunks(per_page))
+        .map(|page| pdf_page(page, per_page))
+        .collect();
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 5 + 2 * i))
+        .collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>".to_string(),
+    ];
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            6 + 2 * i
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).into_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .into_bytes(),
+    );
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_in, html_book, pdf_book, pdf_text, Entry};
+    use sudoku::SudokuBoard;
+
+    fn entries(boards: &[SudokuBoard], name: &str) -> Vec<(String, SudokuBoard)> {
+        boards
+            .iter()
+            .enumerate()
+            .map(|(i, board)| (format!("{} {}", name, i + 1), board.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_books() {
+        let puzzle = SudokuBoard::fill_board("53--7----6--195----98----6-").unwrap();
+        let (solution, _) = fill_in(&puzzle).unwrap();
+        let puzzles = entries(&vec![puzzle; 5], "Puzzle");
+        let solutions = entries(&vec![solution; 5], "Solution");
+        let puzzles: Vec<Entry> = puzzles.iter().map(|(t, b)| (t.clone(), b)).collect();
+        let solutions: Vec<Entry> = solutions.iter().map(|(t, b)| (t.clone(), b)).collect();
+
+        let html = html_book(&puzzles, &solutions, 4);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("<table").count(), 10);
+        assert_eq!(html.matches("<section class=\"page\">").count(), 4);
+        assert_eq!(html.matches("<style>").count(), 2);
+        assert!(html.contains("<h2>Solution 5</h2>"));
+
+        let pdf = pdf_book(&puzzles, &solutions, 4);
+        let text = String::from_utf8(pdf).unwrap();
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Count 4"));
+        assert!(text.contains("(Puzzle 5) Tj"));
+        // the cross reference table points at each object
+        let xref: usize = text.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(text[xref..].starts_with("xref\n0 13\n"));
+        for (i, line) in text[xref..].lines().skip(3).take(12).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+
+        assert_eq!(pdf_text("a (b) \\"), "a \\(b\\) \\\\");
+    }
+}
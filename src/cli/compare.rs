@@ -0,0 +1,124 @@
+use super::solve::{read_input, read_puzzles};
+use super::{CompareOptions, Failure};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use sudoku::{SolveStats, SudokuBoard, SudokuError, Technique, TechniqueReport};
+
+/// How one set of techniques did on a puzzle.
+pub struct Run {
+    pub elapsed: Duration,
+    pub stats: SolveStats,
+    pub report: TechniqueReport,
+}
+
+/// Solve a copy of the board with only `techniques`.
+pub fn run_with(board: &SudokuBoard, techniques: &[Technique]) -> Result<Run, SudokuError> {
+    let mut board = board.clone();
+    let start = Instant::now();
+    let (report, stats) = board.solve_with_techniques(techniques)?;
+    Ok(Run {
+        elapsed: start.elapsed(),
+        stats,
+        report,
+    })
+}
+
+/// Describe how the two runs differ: the time and guesses of both, then
+/// each technique used a different number of times.
+pub fn differences(first: &Run, second: &Run) -> String {
+    let mut parts = vec![
+        format!("{:?} vs {:?}", first.elapsed, second.elapsed),
+        format!(
+            "guesses {} vs {}",
+            first.stats.guesses, second.stats.guesses
+        ),
+    ];
+    for technique in Technique::ALL {
+        let count = |run: &Run| run.report.counts.get(&technique).copied().unwrap_or(0);
+        if count(first) != count(second) {
+            parts.push(format!(
+                "{} {} vs {}",
+                technique.name(),
+                count(first),
+                count(second)
+            ));
+        }
+    }
+    parts.join(", ")
+}
+
+pub fn run(options: &CompareOptions) -> Result<(), Failure> {
+    let contents = read_input(options.input.as_deref())?;
+    let output_error =
+        |e: io::Error| Failure::Internal(format!("could not write standard output: {}", e));
+    let mut out = io::stdout().lock();
+
+    let mut totals = [(Duration::ZERO, 0); 2];
+    let mut differing = 0;
+    let puzzles = read_puzzles(&contents);
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        let label = match &puzzle.name {
+            Some(name) => name.trim().to_string(),
+            None => format!("puzzle {}", i + 1),
+        };
+        let runs = puzzle.board().and_then(|board| {
+            Ok((
+                run_with(&board, &options.first)?,
+                run_with(&board, &options.second)?,
+            ))
+        });
+        match runs {
+            Ok((first, second)) => {
+                for (total, run) in totals.iter_mut().zip([&first, &second]) {
+                    total.0 += run.elapsed;
+                    total.1 += run.stats.guesses;
+                }
+                if first.stats != second.stats || first.report.counts != second.report.counts {
+                    differing += 1;
+                }
+                writeln!(out, "{}: {}", label, differences(&first, &second))
+            }
+            Err(e) => writeln!(out, "{}: invalid ({})", label, e),
+        }
+        .map_err(output_error)?;
+    }
+
+    writeln!(out).map_err(output_error)?;
+    for (name, (elapsed, guesses)) in ["first", "second"].iter().zip(totals) {
+        writeln!(out, "{:<6}  {} guesses in {:?}", name, guesses, elapsed).map_err(output_error)?;
+    }
+    writeln!(
+        out,
+        "{} of {} puzzles solved differently",
+        differing,
+        puzzles.len()
+    )
+    .map_err(output_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{differences, run_with};
+    use sudoku::{SudokuBoard, Technique};
+
+    #[test]
+    fn test_differences() {
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let board = SudokuBoard::fill_board(s).unwrap();
+        let singles = [Technique::NakedSingle, Technique::HiddenSingle];
+        let first = run_with(&board, &singles).unwrap();
+        let second = run_with(&board, &Technique::ALL).unwrap();
+        let text = differences(&first, &second);
+        let parts: Vec<&str> = text.split(", ").collect();
+        assert!(parts[0].contains(" vs "));
+        assert_eq!(parts[1], format!("guesses {} vs 0", first.stats.guesses));
+        assert!(parts
+            .iter()
+            .any(|p| p.starts_with("Locked Candidates 0 vs ")));
+        assert!(!text.contains("X-Wing"));
+
+        // the same techniques differ in time alone
+        let again = run_with(&board, &Technique::ALL).unwrap();
+        assert_eq!(differences(&second, &again).split(", ").count(), 2);
+    }
+}
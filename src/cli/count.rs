@@ -0,0 +1,158 @@
+use super::output::{Field, ReportWriter};
+use super::solve::{read_input, read_puzzles, Puzzle};
+use super::validate::read_givens;
+use super::{CountOptions, Failure, Format};
+use std::io::{self, Write};
+
+/// Count the solutions of a puzzle up to `limit`, written as the number
+/// found with a `+` when the limit was reached.  More than one solution is
+/// `2+` when no limit is given.
+pub fn count_puzzle(puzzle: &Puzzle, limit: Option<usize>) -> Result<String, String> {
+    let board = read_givens(puzzle).map_err(|problems| problems.join("; "))?;
+    let cap = limit.unwrap_or(2);
+    let count = board.count_solutions(cap);
+    if count >= cap && count > 1 {
+        Ok(format!("{}+", count))
+    } else {
+        Ok(count.to_string())
+    }
+}
+
+/// Count the solutions of every puzzle in `contents`, read as `solve` reads
+/// them, and write a record each in `format`.  Fails with `InvalidInput` after the last puzzle when any
+/// could not be read.
+pub fn count_all(
+    contents: &str,
+    limit: Option<usize>,
+    format: Format,
+    out: &mut dyn Write,
+) -> Result<(), Failure> {
+    let output_error =
+        |e: io::Error| Failure::Internal(format!("could not write standard output: {}", e));
+    let puzzles = read_puzzles(contents);
+    let mut writer = ReportWriter::new(out, format);
+    let mut invalid = 0;
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        let label = match &puzzle.name {
+            Some(name) => name.trim().to_string(),
+            None => format!("puzzle {}", i + 1),
+        };
+        let (text, fields) = match count_puzzle(puzzle, limit) {
+            Ok(count) => {
+                let fields = [
+                    ("solutions", Field::raw(count.trim_end_matches('+'))),
+                    ("limit_reached", Field::raw(count.ends_with('+'))),
+                    ("error", Field::Null),
+                ];
+                (format!("{}: {}", label, count), fields)
+            }
+            Err(problem) => {
+                invalid += 1;
+                let text = format!("{}: invalid ({})", label, problem);
+                let fields = [
+                    ("solutions", Field::Null),
+                    ("limit_reached", Field::Null),
+                    ("error", Field::Text(problem)),
+                ];
+                (text, fields)
+            }
+        };
+        writer
+            .record(i + 1, puzzle.name.as_deref(), &text, &fields)
+            .map_err(output_error)?;
+    }
+    writer.flush().map_err(output_error)?;
+    Failure::check_unread(invalid, puzzles.len())
+}
+
+pub fn run(options: &CountOptions) -> Result<(), Failure> {
+    let file_contents = read_input(options.input.as_deref())?;
+    count_all(
+        &file_contents,
+        options.limit,
+        options.format,
+        &mut io::stdout().lock(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_all, count_puzzle};
+    use crate::cli::solve::Puzzle;
+    use crate::cli::Format;
+
+    fn puzzle(grid: &str) -> Puzzle {
+        Puzzle {
+            name: None,
+            grid: grid.to_string(),
+            line: None,
+        }
+    }
+
+    #[test]
+    fn test_count_puzzle() {
+        let unique = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        assert_eq!(count_puzzle(&puzzle(unique), None).unwrap(), "1");
+        assert_eq!(count_puzzle(&puzzle(unique), Some(5)).unwrap(), "1");
+        let open = &unique.replacen("4", "-", 2);
+        assert_eq!(count_puzzle(&puzzle(open), None).unwrap(), "2+");
+        assert_eq!(count_puzzle(&puzzle(open), Some(1000)).unwrap(), "42");
+        assert_eq!(
+            count_puzzle(&puzzle(&"-".repeat(81)), Some(7)).unwrap(),
+            "7+"
+        );
+        let repeated = unique.replacen("4", "2", 1);
+        assert!(count_puzzle(&puzzle(&repeated), None).is_err());
+    }
+
+    #[test]
+    fn test_count_all_invalid() {
+        let mut out = Vec::new();
+        let failure = count_all("123\n", None, Format::Grid, &mut out).unwrap_err();
+        assert_eq!(failure.exit_code(), 2);
+        assert_eq!(failure.message(), "1 of 1 puzzles could not be read");
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("puzzle 1: invalid ("));
+
+        let mut out = Vec::new();
+        count_all(&"-".repeat(81), Some(3), Format::Grid, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "puzzle 1: 3+\n");
+    }
+
+    #[test]
+    fn test_count_all_format() {
+        let contents = format!("{}\n123\n", "-".repeat(81));
+        let mut out = Vec::new();
+        count_all(&contents, Some(3), Format::Json, &mut out).unwrap_err();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                "{\"index\":1,\"name\":null,\"solutions\":3,\"limit_reached\":true,\"error\":null}\n",
+                "{\"index\":2,\"name\":null,\"solutions\":null,\"limit_reached\":null,",
+                "\"error\":\"expected 81 squares, found 3\"}\n"
+            )
+        );
+
+        let mut out = Vec::new();
+        count_all(&contents, None, Format::Csv, &mut out).unwrap_err();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                "index,name,solutions,limit_reached,error\n",
+                "1,,2,true,\n",
+                "2,,,,\"expected 81 squares, found 3\"\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use super::output::BoardWriter;
+use super::progress::Progress;
+use super::{Failure, GenerateOptions};
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sudoku::{Difficulty, Generator, GeneratorOptions};
+
+/// The seed given, or one picked from the clock and printed to standard
+/// error so the run can be repeated.
+pub fn seed_or_clock(seed: Option<u64>) -> u64 {
+    match seed {
+        Some(seed) => seed,
+        None => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            let seed = now.as_nanos() as u64;
+            eprintln!("seed: {}", seed);
+            seed
+        }
+    }
+}
+
+/// The failure to report when the generator gives up on the requested
+/// difficulty.
+pub fn no_puzzle(difficulty: Option<Difficulty>, clues: Option<usize>) -> Failure {
+    let difficulty = difficulty.map_or("", |d| d.name());
+    let message = match clues {
+        Some(clues) => format!(
+            "could not make a {} puzzle with {} or fewer clues",
+            difficulty, clues
+        ),
+        None => format!("could not make a {} puzzle", difficulty),
+    };
+    Failure::Unsolvable(message)
+}
+
+pub fn run(options: &GenerateOptions) -> Result<(), Failure> {
+    let seed = seed_or_clock(options.seed);
+    let mut generator = Generator::new(GeneratorOptions {
+        seed,
+        symmetry: options.symmetry,
+        clues: options.clues,
+        difficulty: options.difficulty,
+        ..Default::default()
+    });
+
+    let output_error =
+        |e: io::Error| Failure::Internal(format!("could not write standard output: {}", e));
+    let out = BoardWriter::new(io::stdout().lock(), options.format);
+    let mut out = out.with_pencil_marks(options.pencil_marks);
+    if let Some(rounds) = options.search {
+        let progress = Progress::new(rounds, options.progress);
+        let kept = generator.search_hard(rounds, options.count, |_, _| progress.tick());
+        progress.finish();
+        for (i, rated) in kept.iter().enumerate() {
+            let hardness = &rated.hardness;
+            let name = format!(
+                "Grid {:02} ({}, {} guesses, {} steps)",
+                i + 1,
+                hardness.grade.name(),
+                hardness.guesses,
+                hardness.steps
+            );
+            out.write(Some(&name), &rated.puzzle)
+                .map_err(output_error)?;
+        }
+        return out.flush().map_err(output_error);
+    }
+
+    let progress = Progress::new(options.count, options.progress);
+    for i in 1..=options.count {
+        let puzzle = generator
+            .generate()
+            .ok_or_else(|| no_puzzle(options.difficulty, options.clues))?;
+        progress.tick();
+        let name = format!("Grid {:02}", i);
+        out.write(Some(&name), &puzzle).map_err(output_error)?;
+    }
+    progress.finish();
+    out.flush().map_err(output_error)
+}
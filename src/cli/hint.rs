@@ -0,0 +1,128 @@
+use super::output::BoardWriter;
+use super::solve::read_input;
+use super::{cell_name, house_name, Failure, HintOptions};
+use std::io;
+use sudoku::{Hint, Step, SudokuBoard, SudokuError};
+
+/// Read the puzzle as pencil marks when it is 81 groups of digits and some
+/// square lists more than one candidate, otherwise as a grid.  A `Grid`
+/// header line is skipped.
+pub fn parse_puzzle(contents: &str) -> Result<SudokuBoard, SudokuError> {
+    let contents: String = contents
+        .lines()
+        .filter(|line| !line.contains("Grid"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let marks: Vec<&str> = contents
+        .split_whitespace()
+        .filter(|token| token.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    if marks.len() == 81 && marks.iter().any(|token| token.len() > 1) {
+        SudokuBoard::parse_pencil_marks(&contents)
+    } else {
+        SudokuBoard::parse_grid(&contents)
+    }
+}
+
+/// Write each placement or elimination as `r1c2`, grouped by digit.
+fn describe_moves(moves: &[(usize, usize, i32)]) -> Vec<(i32, String)> {
+    let mut digits: Vec<i32> = moves.iter().map(|(_, _, d)| *d).collect();
+    digits.sort();
+    digits.dedup();
+    digits
+        .into_iter()
+        .map(|digit| {
+            let cells: Vec<String> = moves
+                .iter()
+                .filter(|(_, _, d)| *d == digit)
+                .map(|(row, col, _)| cell_name(*row, *col))
+                .collect();
+            (digit, cells.join(", "))
+        })
+        .collect()
+}
+
+/// Describe a step over several lines: the technique and what it does,
+/// followed by the houses and squares that justify it.
+pub fn describe_step(step: &Step) -> String {
+    let mut lines = vec![step.technique.name().to_string()];
+    for (digit, cells) in describe_moves(&step.placements) {
+        lines.push(format!("  place {} in {}", digit, cells));
+    }
+    for (digit, cells) in describe_moves(&step.eliminations) {
+        lines.push(format!("  remove {} from {}", digit, cells));
+    }
+    if !step.houses.is_empty() {
+        let houses: Vec<String> = step.houses.iter().map(|h| house_name(*h)).collect();
+        lines.push(format!("  houses: {}", houses.join(", ")));
+    }
+    if !step.cells.is_empty() {
+        let cells: Vec<String> = step.cells.iter().map(|(r, c)| cell_name(*r, *c)).collect();
+        lines.push(format!("  cells: {}", cells.join(", ")));
+    }
+    lines.join("\n")
+}
+
+pub fn run(options: &HintOptions) -> Result<(), Failure> {
+    let contents = read_input(options.input.as_deref())?;
+    let board = parse_puzzle(&contents)
+        .map_err(|e| Failure::InvalidInput(format!("invalid puzzle: {}", e)))?;
+    if options.pencil_marks {
+        let mut out = BoardWriter::new(io::stdout().lock(), options.format).with_pencil_marks(true);
+        out.write(None, &board)
+            .and_then(|_| out.flush())
+            .map_err(|e| Failure::Internal(format!("could not write standard output: {}", e)))?;
+    }
+    match board.next_step() {
+        Some(step) => {
+            let hint = Hint::new(step);
+            match options.level {
+                Some(level) => println!("{}", hint.at_level(level)),
+                None => println!("{}", describe_step(&hint.step)),
+            }
+            if options.teach {
+                println!();
+                for line in hint.lesson() {
+                    println!("{}", line);
+                }
+            }
+        }
+        None if board.print_board().contains('-') => {
+            println!("No logical step found, the puzzle needs guessing from here")
+        }
+        None => println!("The puzzle is already solved"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_step, parse_puzzle};
+
+    #[test]
+    fn test_hint() {
+        let grid = concat!(
+            "4----8---\n",
+            "----91-8-\n",
+            "-865-2-3-\n",
+            "-2-4--9--\n",
+            "-1-2----6\n",
+            "367-59---\n",
+            "-----5---\n",
+            "7--8---24\n",
+            "2--93--7-\n",
+        );
+        let board = parse_puzzle(grid).unwrap();
+        let step = board.next_step().unwrap();
+        let description = describe_step(&step);
+        assert!(description.starts_with(step.technique.name()));
+        assert!(description.contains("  place "));
+
+        let marks = format!("{}{}", "5 ", "123456789 ".repeat(80));
+        let board = parse_puzzle(&marks).unwrap();
+        assert_eq!(&board.print_board()[..2], "5-");
+        // rows of 9 squares are a grid, not pencil marks
+        let board = parse_puzzle(&format!("Grid 01\n{}", grid.replace('-', "0"))).unwrap();
+        assert_eq!(&board.print_board()[..9], "4----8---");
+    }
+}
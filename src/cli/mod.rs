@@ -0,0 +1,1209 @@
+//! Command line handling for the `sudoku_bin` binary.
+
+pub mod bench;
+pub mod book;
+pub mod compare;
+pub mod count;
+pub mod generate;
+pub mod hint;
+pub mod output;
+pub mod progress;
+pub mod rate;
+pub mod serve;
+pub mod solve;
+pub mod tui;
+pub mod validate;
+pub mod verify;
+pub mod watch;
+
+use sudoku::{Difficulty, HintLevel, HouseId, Symmetry, Technique};
+
+pub const USAGE: &str = "\
+Solve sudoku puzzles.
+
+Usage:
+    sudoku_bin [solve] [OPTIONS] [FILE]
+    sudoku_bin generate [OPTIONS]
+    sudoku_bin book [OPTIONS] --out FILE
+    sudoku_bin rate [--format F] [FILE]
+    sudoku_bin validate [--format F] [FILE]
+    sudoku_bin verify [--format F] [FILE]
+    sudoku_bin count [--limit N] [--format F] [FILE]
+    sudoku_bin hint [--pencilmarks] [--format F] [--teach] [--level N] [FILE]
+    sudoku_bin compare [--first T] [--second T] [FILE]
+    sudoku_bin tui [FILE]
+    sudoku_bin watch [OPTIONS] DIR
+    sudoku_bin bench [--runs N] [FILE]
+    sudoku_bin --serve-stdio
+    sudoku_bin help
+
+Commands:
+    solve       Solve every puzzle in FILE and print the solutions (default).
+                A summary of the batch is printed to standard error.
+    generate    Make new puzzles with exactly one solution
+    book        Make new puzzles and lay them out for printing, a few to a
+                page, with the solutions at the end.  FILE is written as
+                HTML or PDF, by its extension
+    rate        Grade every puzzle in FILE by the techniques needed to solve
+                it, with an approximate Sudoku Explainer (SE) rating and
+                the first step of its hardest technique, then print how
+                many puzzles got each grade
+    validate    Check that every puzzle in FILE has 81 squares, no repeated
+                givens, and exactly one solution.  Exits with status 2 when
+                any puzzle cannot be read, repeated givens included, and
+                with status 1 when any puzzle has no or several solutions
+    verify      Solve the puzzle of every puzzle and solution pair in FILE
+                and check that the expected solution comes out, showing
+                how the rows differ when it does not.  A pair is two
+                columns of 81 squares on one line, or a puzzle line
+                followed by a solution line.  Exits with status 1 when any
+                solution is not reproduced
+    count       Count the solutions of every puzzle in FILE, printing 0, 1,
+                or 2+.  With `--limit N` solutions are counted up to N
+    hint        Print the next logical step for the one puzzle in FILE.  The
+                puzzle is a grid, or 81 pencil marks separated by spaces,
+                each listing the candidates left for its square.  With
+                `--teach` the step is also explained for someone learning
+                the technique: why it is valid, and what it changes.
+                `--level N` gives away only so much of the step: 1 names
+                the technique, 2 also the houses, 3 also the squares, and
+                4 also the digits to place or remove
+    compare     Solve every puzzle in FILE twice, with two sets of
+                techniques and guessing when they are stuck, and print how
+                the time, guesses, and steps of each technique differ
+    tui         Play the first puzzle in FILE, or a new puzzle when FILE is
+                missing, in the terminal.  Enter digits and pencil marks,
+                ask for hints, and check or finish the puzzle
+    watch       Solve every puzzle file in DIR, then keep solving files
+                as they are added or modified.  The solutions of `NAME`
+                are written to `NAME.solved`
+    bench       Solve every puzzle in FILE N times (default 10) and print
+                the fastest, median, 99th percentile, and slowest solve,
+                and the puzzles solved per second
+    help        Print this message
+
+    --serve-stdio
+                Answer JSON-RPC 2.0 requests, one per line on standard
+                input, with one line each on standard output.  The methods
+                `solve`, `hint`, and `validate` take the puzzle as the
+                `puzzle` parameter
+
+Arguments:
+    FILE     File of puzzles, each 9 lines of 9 squares or one line of all
+             81, optionally after a line containing `Grid` that names it.
+             Unknown squares are `0`, `.`, `-`, or `_`.  Blank lines are
+             skipped.  Reads standard input when FILE is missing or `-`.
+
+Options:
+        --format FORMAT  How `solve`, `generate`, and `watch` write boards,
+                         and `hint --pencilmarks` draws the puzzle: `grid`
+                         (9 lines, default), `line` (81 characters),
+                         `pretty` (framed with box-drawing characters), `csv`
+                         (a `name,board` header then one record per board),
+                         or `json` (one object per line).  `count`, `rate`,
+                         `validate`, and `verify` write a record per puzzle
+                         with `csv` and `json`, starting with its `index`
+                         and `name`, and lines of text with the others;
+                         their totals go to standard error.  `bench`,
+                         `compare`, and `tui` write neither boards nor
+                         records and refuse it
+        --progress       Show the progress of long runs on standard error.
+                         Shown by default when standard error is a terminal
+        --no-progress    Never show the progress
+        --pencilmarks    Show the candidates left in every square.  `grid`
+                         and `pretty` boards draw each square as a 3x3 grid
+                         of candidates, the other formats write 81 groups of
+                         candidates separated by spaces.  `solve` and `watch`
+                         show each puzzle as far as the logical techniques
+                         get it instead of the solution, `generate` shows
+                         the candidates of the new puzzles, and `hint` draws
+                         the puzzle before its hint
+    -h, --help           Print this message
+    -V, --version        Print the version
+
+Exit status:
+    0    Every puzzle was solved
+    1    Some puzzles have no solution, or failed `validate` or `verify`
+    2    The arguments or some puzzles could not be read, such as
+         puzzles with a digit given twice in a house
+    3    Internal error, such as failing to write the output
+
+Solve options:
+    -p, --puzzle GRID    Solve GRID, 81 squares on one line, instead of reading
+                         FILE.  Unknown squares are `0`, `.`, `-`, or `_`
+    -o, --output FILE    Write the solutions to FILE instead of standard output
+        --append         Append to the output file instead of replacing it
+    -j, --jobs N         Solve N puzzles at a time on separate threads.  The
+                         solutions are still written in input order
+    -v, --verbose        Print every logical step taken on each puzzle to
+                         standard error, and how much guessing was left
+        --euler-sum      After the solutions, print the sum of the 3 digit
+                         numbers in the top left corner of every solution,
+                         the answer to Project Euler problem 96.  Printed to
+                         standard error for the `csv` and `json` formats
+        --step-numbers   After each solution, print a grid numbering the
+                         squares the solver filled in, in the order it
+                         filled them in.  Givens are `.`.  Printed to
+                         standard error for the `csv` and `json` formats
+        --json           The same as `--format json`.  Every puzzle gets an
+                         object with the puzzle, its status, the solution
+                         or error, and the solving statistics
+        --mmap           Memory map FILE and read it as one puzzle of 81
+                         squares per line, in place, solving it in chunks
+                         over the `--jobs` threads.  For very large files.
+                         Needs the `mmap` feature, and cannot be used with
+                         `--verbose`, `--pencilmarks`, or `--step-numbers`
+
+Compare options:
+        --first T        Techniques of the first solve (default `singles`)
+        --second T       Techniques of the second solve (default `all`)
+                         T is a comma separated list of `naked-single`,
+                         `hidden-single`, `locked-candidates`, `naked-pair`,
+                         `hidden-pair`, `naked-triple`, `hidden-triple`,
+                         `x-wing`, `singles` (the first two), `all`, or `none`
+
+Watch options:
+        --interval MS    Look for changed files every MS milliseconds
+                         (default 1000)
+        --once           Solve the files already in DIR and exit
+        --stdout         Print the solutions instead of writing them to files
+
+Generate options:
+    -n, --count N        Number of puzzles to make (default 1)
+        --difficulty D   Only make puzzles graded `easy`, `medium`, `hard`,
+                         `expert`, or `diabolical`
+        --symmetry S     Pattern of the givens: `none` (default), `rotational`,
+                         `quarter-turn`, `horizontal`, `vertical`, or `diagonal`
+        --clues N        Stop removing givens at N givens
+        --search ROUNDS  Hunt for hard puzzles: make or change a puzzle in
+                         each of ROUNDS rounds, and print the `--count`
+                         hardest found, hardest first, named with their
+                         grade and the guessing and steps they need
+        --seed N         Seed of the random numbers, the same seed makes the
+                         same puzzles.  A random seed is used and printed to
+                         standard error when missing
+
+Book options:
+        --out FILE       The book to write, `.html` or `.pdf`
+    -n, --count N        Number of puzzles (default 12)
+        --difficulty D   Grade of the puzzles as for `generate`, or `mixed`
+                         (default) for all grades, easiest first
+        --per-page N     Puzzles on each page (default 4)
+        --seed N         Seed of the random numbers, as for `generate`
+";
+
+/// Why a command failed.  Each kind exits with its own status so scripts
+/// can tell them apart.
+#[derive(Debug, PartialEq)]
+pub enum Failure {
+    // Some puzzles have no solution
+    Unsolvable(String),
+    // The arguments or the puzzles could not be read
+    InvalidInput(String),
+    // Anything else, such as failing to write the output
+    Internal(String),
+}
+
+impl Failure {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Failure::Unsolvable(_) => 1,
+            Failure::InvalidInput(_) => 2,
+            Failure::Internal(_) => 3,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Failure::Unsolvable(message)
+            | Failure::InvalidInput(message)
+            | Failure::Internal(message) => message,
+        }
+    }
+
+    /// `InvalidInput` when any of the `count` puzzles of a batch could not
+    /// be read, after the rest were dealt with.
+    pub fn check_unread(invalid: usize, count: usize) -> Result<(), Failure> {
+        if invalid == 0 {
+            return Ok(());
+        }
+        Err(Failure::InvalidInput(format!(
+            "{} of {} puzzles could not be read",
+            invalid, count
+        )))
+    }
+}
+
+/// How a board is written.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Format {
+    // 9 lines of 9 squares
+    #[default]
+    Grid,
+    // a single line of 81 squares
+    Line,
+    // framed with box-drawing characters
+    Pretty,
+    // a CSV record per board
+    Csv,
+    // a JSON object per board
+    Json,
+}
+
+impl Format {
+    fn parse(s: &str) -> Result<Format, String> {
+        match s {
+            "grid" => Ok(Format::Grid),
+            "line" => Ok(Format::Line),
+            "pretty" => Ok(Format::Pretty),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format `{}`", s)),
+        }
+    }
+}
+
+/// Options of the `solve` command.
+#[derive(Debug, PartialEq)]
+pub struct SolveOptions {
+    // File to read puzzles from, standard input when None
+    pub input: Option<String>,
+    // A puzzle given on the command line, read instead of the input
+    pub puzzle: Option<String>,
+    // File to write solutions to, standard output when None
+    pub output: Option<String>,
+    // Append to the output file instead of truncating it
+    pub append: bool,
+    pub format: Format,
+    // Number of threads solving puzzles
+    pub jobs: usize,
+    // Show the progress line, decided by whether stderr is a terminal when None
+    pub progress: Option<bool>,
+    // Print the steps taken on each puzzle
+    pub verbose: bool,
+    // Print the Project Euler 96 sum of the solutions
+    pub euler_sum: bool,
+    // Print the candidates left after the logical techniques, not the solution
+    pub pencil_marks: bool,
+    // Print the order the squares were filled in after each solution
+    pub step_numbers: bool,
+    // Memory map the input file and read it as one puzzle per line
+    pub mmap: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> SolveOptions {
+        SolveOptions {
+            input: None,
+            puzzle: None,
+            output: None,
+            append: false,
+            format: Format::default(),
+            jobs: 1,
+            progress: None,
+            verbose: false,
+            euler_sum: false,
+            pencil_marks: false,
+            step_numbers: false,
+            mmap: false,
+        }
+    }
+}
+
+/// Options of the `count` command.
+#[derive(Debug, Default, PartialEq)]
+pub struct CountOptions {
+    // File of puzzles, standard input when None
+    pub input: Option<String>,
+    // Count solutions up to this many, only telling 0, 1, or more when None
+    pub limit: Option<usize>,
+    pub format: Format,
+}
+
+/// Options of the `generate` command.
+#[derive(Debug, PartialEq)]
+pub struct GenerateOptions {
+    pub count: usize,
+    pub difficulty: Option<Difficulty>,
+    pub symmetry: Symmetry,
+    pub clues: Option<usize>,
+    // Seed of the random numbers, picked from the clock when None
+    pub seed: Option<u64>,
+    pub format: Format,
+    // Show the progress line, decided by whether stderr is a terminal when None
+    pub progress: Option<bool>,
+    // Print the candidates of each puzzle
+    pub pencil_marks: bool,
+    // Hunt for the hardest puzzles over this many rounds
+    pub search: Option<usize>,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> GenerateOptions {
+        GenerateOptions {
+            count: 1,
+            difficulty: None,
+            symmetry: Symmetry::None,
+            clues: None,
+            seed: None,
+            format: Format::default(),
+            progress: None,
+            pencil_marks: false,
+            search: None,
+        }
+    }
+}
+
+/// The file format of a book.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BookFormat {
+    Html,
+    Pdf,
+}
+
+/// Options of the `book` command.
+#[derive(Debug, PartialEq)]
+pub struct BookOptions {
+    pub out: String,
+    pub format: BookFormat,
+    pub count: usize,
+    // Grade of every puzzle, a mix of grades when None
+    pub difficulty: Option<Difficulty>,
+    pub per_page: usize,
+    // Seed of the random numbers, picked from the clock when None
+    pub seed: Option<u64>,
+}
+
+/// Options of the `watch` command.
+#[derive(Debug, PartialEq)]
+pub struct WatchOptions {
+    pub dir: String,
+    // Time between looks at the directory
+    pub interval_ms: u64,
+    // Solve the files already there, then stop
+    pub once: bool,
+    // Print the solutions instead of writing them beside each file
+    pub stdout: bool,
+    pub format: Format,
+    // Write the candidates left after the logical techniques, not the solution
+    pub pencil_marks: bool,
+}
+
+/// Options of the `bench` command.
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    // File of puzzles, standard input when None
+    pub input: Option<String>,
+    // Times each puzzle is solved
+    pub runs: usize,
+}
+
+/// Options of the `compare` command.
+#[derive(Debug, PartialEq)]
+pub struct CompareOptions {
+    // File of puzzles, standard input when None
+    pub input: Option<String>,
+    // Techniques of each of the two solves, from the easiest to the hardest
+    pub first: Vec<Technique>,
+    pub second: Vec<Technique>,
+}
+
+/// Options of the `hint` command.
+#[derive(Debug, Default, PartialEq)]
+pub struct HintOptions {
+    // File of the puzzle, standard input when None
+    pub input: Option<String>,
+    // Draw the candidates of the puzzle before the hint
+    pub pencil_marks: bool,
+    // How the candidates are drawn
+    pub format: Format,
+    // Explain why the step is valid, one sentence per line
+    pub teach: bool,
+    // Tell only this much of the step, all of it when None
+    pub level: Option<HintLevel>,
+}
+
+/// Options of the commands that only read a file of puzzles.
+#[derive(Debug, Default, PartialEq)]
+pub struct InputOptions {
+    // File of puzzles, standard input when None
+    pub input: Option<String>,
+}
+
+/// Options of the commands that read a file of puzzles and report on each.
+#[derive(Debug, Default, PartialEq)]
+pub struct ReportOptions {
+    // File of puzzles, standard input when None
+    pub input: Option<String>,
+    // How the report on each puzzle is written
+    pub format: Format,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    Version,
+    Solve(SolveOptions),
+    Generate(GenerateOptions),
+    Book(BookOptions),
+    Rate(ReportOptions),
+    Validate(ReportOptions),
+    Verify(ReportOptions),
+    Count(CountOptions),
+    Hint(HintOptions),
+    Compare(CompareOptions),
+    Tui(InputOptions),
+    Watch(WatchOptions),
+    Bench(BenchOptions),
+    ServeStdio,
+}
+
+/// Walks the arguments, splitting `--flag=value` into the flag and its value.
+struct ArgParser<'a> {
+    args: std::slice::Iter<'a, String>,
+    // value given inline with the last flag
+    inline_value: Option<&'a str>,
+    flag: &'a str,
+}
+
+impl<'a> ArgParser<'a> {
+    fn new(args: &'a [String]) -> ArgParser<'a> {
+        ArgParser {
+            args: args.iter(),
+            inline_value: None,
+            flag: "",
+        }
+    }
+
+    /// The next flag or positional argument.
+    fn next(&mut self) -> Result<Option<&'a str>, String> {
+        if self.inline_value.is_some() {
+            return Err(format!("option `{}` does not take a value", self.flag));
+        }
+        let arg = match self.args.next() {
+            Some(arg) => arg.as_str(),
+            None => return Ok(None),
+        };
+        self.flag = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                self.inline_value = Some(value);
+                flag
+            }
+            _ => arg,
+        };
+        Ok(Some(self.flag))
+    }
+
+    /// The value of the flag just returned by `next`.
+    fn value(&mut self) -> Result<&'a str, String> {
+        match self.inline_value.take() {
+            Some(value) => Ok(value),
+            None => self
+                .args
+                .next()
+                .map(|s| s.as_str())
+                .ok_or_else(|| format!("option `{}` needs a value", self.flag)),
+        }
+    }
+
+    /// The value of the flag just returned by `next`, as a positive number.
+    fn count(&mut self) -> Result<usize, String> {
+        let value = self.value()?;
+        match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!(
+                "option `{}` needs a positive number, not `{}`",
+                self.flag, value
+            )),
+        }
+    }
+}
+
+/// Parse the arguments given after the program name.
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    match args.first().map(|s| s.as_str()) {
+        Some("help") => Ok(Command::Help),
+        Some("solve") => parse_solve(ArgParser::new(&args[1..])),
+        Some("generate") => parse_generate(ArgParser::new(&args[1..])),
+        Some("book") => parse_book(ArgParser::new(&args[1..])),
+        Some("rate") => parse_report(ArgParser::new(&args[1..]), Command::Rate),
+        Some("validate") => parse_report(ArgParser::new(&args[1..]), Command::Validate),
+        Some("verify") => parse_report(ArgParser::new(&args[1..]), Command::Verify),
+        Some("count") => parse_count(ArgParser::new(&args[1..])),
+        Some("hint") => parse_hint(ArgParser::new(&args[1..])),
+        Some("compare") => parse_compare(ArgParser::new(&args[1..])),
+        Some("tui") => parse_input(ArgParser::new(&args[1..]), "tui", Command::Tui),
+        Some("watch") => parse_watch(ArgParser::new(&args[1..])),
+        Some("bench") => parse_bench(ArgParser::new(&args[1..])),
+        Some("--serve-stdio") if args.len() == 1 => Ok(Command::ServeStdio),
+        _ => parse_solve(ArgParser::new(args)),
+    }
+}
+
+fn parse_solve(mut args: ArgParser) -> Result<Command, String> {
+    let mut options = SolveOptions::default();
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-p" | "--puzzle" => options.puzzle = Some(args.value()?.to_string()),
+            "-o" | "--output" => options.output = Some(args.value()?.to_string()),
+            "--append" => options.append = true,
+            "--format" => options.format = Format::parse(args.value()?)?,
+            "-j" | "--jobs" => options.jobs = args.count()?,
+            "--json" => options.format = Format::Json,
+            "-v" | "--verbose" => options.verbose = true,
+            "--euler-sum" => options.euler_sum = true,
+            "--step-numbers" => options.step_numbers = true,
+            "--pencilmarks" => options.pencil_marks = true,
+            "--mmap" => options.mmap = true,
+            "--progress" => options.progress = Some(true),
+            "--no-progress" => options.progress = Some(false),
+            "-" => set_input(&mut options.input, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut options.input, arg)?,
+        }
+    }
+    if options.append && options.output.is_none() {
+        return Err("`--append` needs `--output`".to_string());
+    }
+    if options.puzzle.is_some() && options.input.is_some() {
+        return Err("`--puzzle` cannot be used with a FILE".to_string());
+    }
+    if options.mmap {
+        if !cfg!(feature = "mmap") {
+            return Err("`--mmap` needs sudoku_bin built with the `mmap` feature".to_string());
+        }
+        if options.input.is_none() {
+            return Err("`--mmap` needs a FILE".to_string());
+        }
+        if options.verbose || options.pencil_marks || options.step_numbers {
+            return Err(
+                "`--mmap` cannot be used with `--verbose`, `--pencilmarks`, or `--step-numbers`"
+                    .to_string(),
+            );
+        }
+    }
+    Ok(Command::Solve(options))
+}
+
+fn parse_generate(mut args: ArgParser) -> Result<Command, String> {
+    let mut options = GenerateOptions::default();
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-n" | "--count" => options.count = args.count()?,
+            "--difficulty" => {
+                let value = args.value()?;
+                let difficulty = Difficulty::ALL.into_iter().find(|d| d.name() == value);
+                options.difficulty =
+                    Some(difficulty.ok_or_else(|| format!("unknown difficulty `{}`", value))?);
+            }
+            "--symmetry" => {
+                let value = args.value()?;
+                let symmetry = Symmetry::ALL.into_iter().find(|s| s.name() == value);
+                options.symmetry =
+                    symmetry.ok_or_else(|| format!("unknown symmetry `{}`", value))?;
+            }
+            "--clues" => options.clues = Some(args.count()?),
+            "--search" => options.search = Some(args.count()?),
+            "--seed" => {
+                let value = args.value()?;
+                let seed = value.parse::<u64>();
+                options.seed = Some(seed.map_err(|_| format!("invalid seed `{}`", value))?);
+            }
+            "--format" => options.format = Format::parse(args.value()?)?,
+            "--pencilmarks" => options.pencil_marks = true,
+            "--progress" => options.progress = Some(true),
+            "--no-progress" => options.progress = Some(false),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+    Ok(Command::Generate(options))
+}
+
+fn parse_book(mut args: ArgParser) -> Result<Command, String> {
+    let mut out = None;
+    let mut options = BookOptions {
+        out: String::new(),
+        format: BookFormat::Html,
+        count: 12,
+        difficulty: None,
+        per_page: 4,
+        seed: None,
+    };
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--out" => out = Some(args.value()?.to_string()),
+            "-n" | "--count" => options.count = args.count()?,
+            "--difficulty" => {
+                let value = args.value()?;
+                options.difficulty = match value {
+                    "mixed" => None,
+                    _ => {
+                        let difficulty = Difficulty::ALL.into_iter().find(|d| d.name() == value);
+                        Some(difficulty.ok_or_else(|| format!("unknown difficulty `{}`", value))?)
+                    }
+                };
+            }
+            "--per-page" => options.per_page = args.count()?,
+            "--seed" => {
+                let value = args.value()?;
+                let seed = value.parse::<u64>();
+                options.seed = Some(seed.map_err(|_| format!("invalid seed `{}`", value))?);
+            }
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+    options.out = out.ok_or("`book` needs `--out FILE`")?;
+    let extension = options
+        .out
+        .rsplit_once('.')
+        .map(|(_, e)| e.to_ascii_lowercase());
+    options.format = match extension.as_deref() {
+        Some("html" | "htm") => BookFormat::Html,
+        Some("pdf") => BookFormat::Pdf,
+        _ => return Err(format!("`{}` is not a `.html` or `.pdf` file", options.out)),
+    };
+    Ok(Command::Book(options))
+}
+
+fn parse_count(mut args: ArgParser) -> Result<Command, String> {
+    let mut options = CountOptions::default();
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--limit" => options.limit = Some(args.count()?),
+            "--format" => options.format = Format::parse(args.value()?)?,
+            "-" => set_input(&mut options.input, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut options.input, arg)?,
+        }
+    }
+    Ok(Command::Count(options))
+}
+
+fn parse_watch(mut args: ArgParser) -> Result<Command, String> {
+    let mut dir = None;
+    let mut options = WatchOptions {
+        dir: String::new(),
+        interval_ms: 1000,
+        once: false,
+        stdout: false,
+        format: Format::default(),
+        pencil_marks: false,
+    };
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--interval" => options.interval_ms = args.count()? as u64,
+            "--once" => options.once = true,
+            "--stdout" => options.stdout = true,
+            "--format" => options.format = Format::parse(args.value()?)?,
+            "--pencilmarks" => options.pencil_marks = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut dir, arg)?,
+        }
+    }
+    options.dir = dir.ok_or("`watch` needs a directory")?;
+    Ok(Command::Watch(options))
+}
+
+fn parse_bench(mut args: ArgParser) -> Result<Command, String> {
+    let mut options = BenchOptions {
+        input: None,
+        runs: 10,
+    };
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--runs" => options.runs = args.count()?,
+            "--format" => return Err(no_boards("bench")),
+            "-" => set_input(&mut options.input, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut options.input, arg)?,
+        }
+    }
+    Ok(Command::Bench(options))
+}
+
+fn parse_hint(mut args: ArgParser) -> Result<Command, String> {
+    let mut options = HintOptions::default();
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--pencilmarks" => options.pencil_marks = true,
+            "--format" => options.format = Format::parse(args.value()?)?,
+            "--teach" => options.teach = true,
+            "--level" => {
+                let n = args.count()?;
+                match HintLevel::from_number(n) {
+                    Some(level) => options.level = Some(level),
+                    None => return Err(format!("hint level {} is not from 1 to 4", n)),
+                }
+            }
+            "-" => set_input(&mut options.input, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut options.input, arg)?,
+        }
+    }
+    Ok(Command::Hint(options))
+}
+
+/// Read a comma separated list of techniques, named like `naked-pair`, or
+/// `singles`, `all`, or `none`.  The techniques are sorted from the easiest
+/// to the hardest, the order the solver tries them.
+fn parse_techniques(s: &str) -> Result<Vec<Technique>, String> {
+    let mut techniques = Vec::new();
+    for name in s.split(',').map(str::trim) {
+        match name {
+            "none" => (),
+            "all" => techniques.extend(Technique::ALL),
+            "singles" => techniques.extend([Technique::NakedSingle, Technique::HiddenSingle]),
+            _ => {
+                let technique = Technique::ALL
+                    .into_iter()
+                    .find(|t| t.name().to_ascii_lowercase().replace(' ', "-") == name);
+                techniques.push(technique.ok_or_else(|| format!("unknown technique `{}`", name))?);
+            }
+        }
+    }
+    techniques.sort();
+    techniques.dedup();
+    Ok(techniques)
+}
+
+fn parse_compare(mut args: ArgParser) -> Result<Command, String> {
+    let mut options = CompareOptions {
+        input: None,
+        first: parse_techniques("singles")?,
+        second: Technique::ALL.to_vec(),
+    };
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--first" => options.first = parse_techniques(args.value()?)?,
+            "--second" => options.second = parse_techniques(args.value()?)?,
+            "--format" => return Err(no_boards("compare")),
+            "-" => set_input(&mut options.input, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut options.input, arg)?,
+        }
+    }
+    Ok(Command::Compare(options))
+}
+
+/// The error for `--format` given to a command that writes neither boards
+/// nor a report per puzzle.
+fn no_boards(name: &str) -> String {
+    format!(
+        "`{}` writes no boards or records, so `--format` does not apply",
+        name
+    )
+}
+
+/// Parse the arguments of a command that reads an input file and reports on
+/// each puzzle.
+fn parse_report(
+    mut args: ArgParser,
+    command: fn(ReportOptions) -> Command,
+) -> Result<Command, String> {
+    let mut options = ReportOptions::default();
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--format" => options.format = Format::parse(args.value()?)?,
+            "-" => set_input(&mut options.input, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut options.input, arg)?,
+        }
+    }
+    Ok(command(options))
+}
+
+/// Parse the arguments of a command that only reads an input file.
+fn parse_input(
+    mut args: ArgParser,
+    name: &str,
+    command: fn(InputOptions) -> Command,
+) -> Result<Command, String> {
+    let mut options = InputOptions::default();
+    while let Some(arg) = args.next()? {
+        match arg {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--format" => return Err(no_boards(name)),
+            "-" => set_input(&mut options.input, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => set_input(&mut options.input, arg)?,
+        }
+    }
+    Ok(command(options))
+}
+
+/// Name a square the way players write it, e.g. `r3c7`.
+pub fn cell_name(row: usize, col: usize) -> String {
+    format!("r{}c{}", row, col)
+}
+
+pub fn house_name(house: HouseId) -> String {
+    house.to_string()
+}
+
+/// Remember the input file, `-` meaning standard input.  Only one input may
+/// be given.
+fn set_input(input: &mut Option<String>, arg: &str) -> Result<(), String> {
+    if input.is_some() {
+        return Err(format!("unexpected argument `{}`", arg));
+    }
+    if arg != "-" {
+        *input = Some(arg.to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_args, parse_techniques, BenchOptions, BookFormat, BookOptions, Command,
+        CompareOptions, CountOptions, Failure, Format, GenerateOptions, HintOptions, ReportOptions,
+        SolveOptions, WatchOptions,
+    };
+    use sudoku::{Difficulty, HintLevel, Symmetry, Technique};
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let file = SolveOptions {
+            input: Some("puzzles.txt".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(parse(&[]), Ok(Command::Solve(SolveOptions::default())));
+        assert_eq!(parse(&["-"]), Ok(Command::Solve(SolveOptions::default())));
+        assert_eq!(parse(&["puzzles.txt"]), Ok(Command::Solve(file)));
+        assert_eq!(parse(&["solve", "--help"]), Ok(Command::Help));
+        assert_eq!(parse(&["help"]), Ok(Command::Help));
+        assert_eq!(parse(&["-V"]), Ok(Command::Version));
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&["a.txt", "b.txt"]).is_err());
+        match parse(&["solve", "--puzzle", "53..7...."]) {
+            Ok(Command::Solve(options)) => assert_eq!(options.puzzle.as_deref(), Some("53..7....")),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["-p", "53..7....", "puzzles.txt"]).is_err());
+        assert_eq!(parse(&["--serve-stdio"]), Ok(Command::ServeStdio));
+        assert!(parse(&["--serve-stdio", "puzzles.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_output() {
+        let options = SolveOptions {
+            input: Some("in.txt".to_string()),
+            puzzle: None,
+            output: Some("out.txt".to_string()),
+            append: true,
+            format: Format::Json,
+            jobs: 4,
+            progress: Some(false),
+            verbose: true,
+            euler_sum: true,
+            pencil_marks: false,
+            step_numbers: true,
+            mmap: false,
+        };
+        let args = [
+            "in.txt",
+            "-o",
+            "out.txt",
+            "--append",
+            "--format",
+            "line",
+            "-j",
+            "4",
+            "--json",
+            "--no-progress",
+            "-v",
+            "--euler-sum",
+            "--step-numbers",
+        ];
+        assert_eq!(parse(&args), Ok(Command::Solve(options)));
+        let args = ["--output=out.txt", "--format=grid"];
+        match parse(&args) {
+            Ok(Command::Solve(options)) => {
+                assert_eq!(options.output.as_deref(), Some("out.txt"));
+                assert_eq!(options.format, Format::Grid);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--append"]).is_err());
+        assert!(parse(&["--output"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        match parse(&["--format", "pretty"]) {
+            Ok(Command::Solve(options)) => assert_eq!(options.format, Format::Pretty),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["--jobs", "0"]).is_err());
+        assert!(parse(&["--jobs=many"]).is_err());
+        assert!(parse(&["--append=yes", "-o", "out.txt"]).is_err());
+
+        // a memory map needs a file, and keeps only the solutions
+        assert!(parse(&["--mmap"]).is_err());
+        assert!(parse(&["--mmap", "in.txt", "--step-numbers"]).is_err());
+        let mapped = parse(&["--mmap", "in.txt", "-j", "2"]);
+        if cfg!(feature = "mmap") {
+            match mapped {
+                Ok(Command::Solve(options)) => assert!(options.mmap && options.jobs == 2),
+                other => panic!("unexpected {:?}", other),
+            }
+        } else {
+            assert!(mapped.is_err());
+        }
+    }
+
+    #[test]
+    fn test_failure_exit_code() {
+        assert_eq!(Failure::Unsolvable(String::new()).exit_code(), 1);
+        assert_eq!(Failure::InvalidInput(String::new()).exit_code(), 2);
+        let failure = Failure::Internal("disk full".to_string());
+        assert_eq!(failure.exit_code(), 3);
+        assert_eq!(failure.message(), "disk full");
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        let options = GenerateOptions {
+            count: 3,
+            difficulty: Some(Difficulty::Hard),
+            symmetry: Symmetry::Diagonal,
+            clues: Some(28),
+            seed: Some(99),
+            format: Format::Line,
+            progress: Some(true),
+            pencil_marks: false,
+            search: Some(500),
+        };
+        let args = [
+            "generate",
+            "-n",
+            "3",
+            "--difficulty=hard",
+            "--symmetry",
+            "diagonal",
+            "--clues",
+            "28",
+            "--seed",
+            "99",
+            "--format",
+            "line",
+            "--progress",
+            "--search",
+            "500",
+        ];
+        assert_eq!(parse(&args), Ok(Command::Generate(options)));
+        assert_eq!(
+            parse(&["generate"]),
+            Ok(Command::Generate(GenerateOptions::default()))
+        );
+        assert!(parse(&["generate", "--difficulty", "silly"]).is_err());
+        assert!(parse(&["generate", "--symmetry", "spiral"]).is_err());
+        assert!(parse(&["generate", "--seed", "-1"]).is_err());
+        assert!(parse(&["generate", "puzzles.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_book() {
+        let options = BookOptions {
+            out: "book.PDF".to_string(),
+            format: BookFormat::Pdf,
+            count: 100,
+            difficulty: Some(Difficulty::Easy),
+            per_page: 6,
+            seed: Some(5),
+        };
+        let args = [
+            "book",
+            "--count",
+            "100",
+            "--difficulty",
+            "easy",
+            "--per-page=6",
+            "--seed",
+            "5",
+            "--out",
+            "book.PDF",
+        ];
+        assert_eq!(parse(&args), Ok(Command::Book(options)));
+        match parse(&["book", "--difficulty", "mixed", "--out", "book.html"]) {
+            Ok(Command::Book(options)) => {
+                assert_eq!(options.format, BookFormat::Html);
+                assert_eq!((options.count, options.per_page), (12, 4));
+                assert_eq!(options.difficulty, None);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["book"]).is_err());
+        assert!(parse(&["book", "--out", "book.txt"]).is_err());
+        assert!(parse(&["book", "--out", "book.pdf", "--per-page", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_pencil_marks() {
+        match parse(&["--pencilmarks", "puzzles.txt"]) {
+            Ok(Command::Solve(options)) => assert!(options.pencil_marks),
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse(&["generate", "--pencilmarks"]) {
+            Ok(Command::Generate(options)) => assert!(options.pencil_marks),
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse(&["watch", "--pencilmarks", "dir"]) {
+            Ok(Command::Watch(options)) => assert!(options.pencil_marks),
+            other => panic!("unexpected {:?}", other),
+        }
+        let options = HintOptions {
+            input: Some("puzzle.txt".to_string()),
+            pencil_marks: true,
+            format: Format::Grid,
+            teach: true,
+            level: None,
+        };
+        let args = ["hint", "puzzle.txt", "--pencilmarks", "--teach"];
+        assert_eq!(parse(&args), Ok(Command::Hint(options)));
+        match parse(&["hint", "--pencilmarks", "--format", "line"]) {
+            Ok(Command::Hint(options)) => assert_eq!(options.format, Format::Line),
+            other => panic!("unexpected {:?}", other),
+        }
+        for command in ["bench", "compare", "tui"] {
+            assert_eq!(
+                parse(&[command, "--format", "line"]),
+                Err(format!(
+                    "`{}` writes no boards or records, so `--format` does not apply",
+                    command
+                ))
+            );
+        }
+        match parse(&["hint", "--level", "2"]) {
+            Ok(Command::Hint(options)) => assert_eq!(options.level, Some(HintLevel::House)),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse(&["hint", "--level", "5"]).is_err());
+        assert_eq!(parse(&["hint"]), Ok(Command::Hint(HintOptions::default())));
+        // `rate` prints no boards
+        assert!(parse(&["rate", "--pencilmarks"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let options = CompareOptions {
+            input: Some("puzzles.txt".to_string()),
+            first: vec![Technique::NakedSingle, Technique::HiddenSingle],
+            second: Technique::ALL.to_vec(),
+        };
+        assert_eq!(
+            parse(&["compare", "puzzles.txt"]),
+            Ok(Command::Compare(options))
+        );
+        let args = ["compare", "--first=none", "--second", "x-wing,singles"];
+        match parse(&args) {
+            Ok(Command::Compare(options)) => {
+                assert_eq!(options.first, vec![]);
+                let second = [
+                    Technique::NakedSingle,
+                    Technique::HiddenSingle,
+                    Technique::XWing,
+                ];
+                assert_eq!(options.second, second);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            parse_techniques("locked-candidates, naked-single,all").unwrap(),
+            Technique::ALL
+        );
+        assert!(parse_techniques("naked-quad").is_err());
+        assert!(parse(&["compare", "--first"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        let options = ReportOptions {
+            input: Some("puzzles.txt".to_string()),
+            format: Format::Grid,
+        };
+        assert_eq!(parse(&["rate", "puzzles.txt"]), Ok(Command::Rate(options)));
+        assert_eq!(
+            parse(&["rate", "-"]),
+            Ok(Command::Rate(ReportOptions::default()))
+        );
+        assert!(parse(&["rate", "a.txt", "b.txt"]).is_err());
+        assert!(parse(&["rate", "--jobs", "2"]).is_err());
+        let options = ReportOptions {
+            input: None,
+            format: Format::Json,
+        };
+        assert_eq!(
+            parse(&["validate", "--format", "json"]),
+            Ok(Command::Validate(options))
+        );
+        let options = ReportOptions {
+            input: None,
+            format: Format::Csv,
+        };
+        assert_eq!(
+            parse(&["verify", "--format=csv"]),
+            Ok(Command::Verify(options))
+        );
+        assert!(parse(&["rate", "--format", "xml"]).is_err());
+        let options = CountOptions {
+            input: Some("puzzle.txt".to_string()),
+            limit: Some(10),
+            format: Format::Json,
+        };
+        assert_eq!(
+            parse(&["count", "--limit=10", "--format", "json", "puzzle.txt"]),
+            Ok(Command::Count(options))
+        );
+        assert!(parse(&["count", "--limit", "0"]).is_err());
+        let options = WatchOptions {
+            dir: "inbox".to_string(),
+            interval_ms: 250,
+            once: true,
+            stdout: true,
+            format: Format::Line,
+            pencil_marks: false,
+        };
+        let args = [
+            "watch",
+            "--interval",
+            "250",
+            "--once",
+            "--stdout",
+            "--format",
+            "line",
+            "inbox",
+        ];
+        assert_eq!(parse(&args), Ok(Command::Watch(options)));
+        assert!(parse(&["watch"]).is_err());
+        let options = BenchOptions {
+            input: Some("puzzles.txt".to_string()),
+            runs: 3,
+        };
+        assert_eq!(
+            parse(&["bench", "--runs", "3", "puzzles.txt"]),
+            Ok(Command::Bench(options))
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use super::Format;
+use std::io::{self, Write};
+use sudoku::{json_string, SudokuBoard};
+
+/// Quote a CSV field when it holds a comma, quote, or line break.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Writes boards one after another in the chosen format, the way every
+/// command that prints boards does.
+///
+/// Names are written on their own line before `grid` and `pretty` boards,
+/// and as a field of `csv` and `json` records.  `line` boards are 81
+/// characters alone so each board is exactly one line.
+///
+/// With pencil marks, `grid` and `pretty` boards are drawn with every square
+/// showing its candidates, and the other formats write the 81 groups of
+/// candidates of `print_pencil_marks` instead of the 81 squares.
+pub struct BoardWriter<W: Write> {
+    out: W,
+    format: Format,
+    pencil_marks: bool,
+    // whether the CSV header has been written
+    started: bool,
+}
+
+impl<W: Write> BoardWriter<W> {
+    pub fn new(out: W, format: Format) -> BoardWriter<W> {
+        BoardWriter {
+            out,
+            format,
+            pencil_marks: false,
+            started: false,
+        }
+    }
+
+    /// Write the candidates of every square instead of only the known ones.
+    pub fn with_pencil_marks(mut self, pencil_marks: bool) -> BoardWriter<W> {
+        self.pencil_marks = pencil_marks;
+        self
+    }
+
+    pub fn write(&mut self, name: Option<&str>, board: &SudokuBoard) -> io::Result<()> {
+        let out = &mut self.out;
+        let squares = if self.pencil_marks {
+            board.print_pencil_marks()
+        } else {
+            board.print_board()
+        };
+        match self.format {
+            Format::Grid | Format::Pretty => {
+                if let Some(name) = name {
+                    writeln!(out, "{}", name)?;
+                }
+                match self.format {
+                    _ if self.pencil_marks => writeln!(out, "{}", board.candidate_grid()),
+                    Format::Pretty => writeln!(out, "{:#}", board),
+                    _ => writeln!(out, "{}", board),
+                }
+            }
+            Format::Line => writeln!(out, "{}", squares),
+            Format::Csv => {
+                if !self.started {
+                    writeln!(out, "name,board")?;
+                }
+                self.started = true;
+                let name = csv_field(name.unwrap_or(""));
+                writeln!(out, "{},{}", name, squares)
+            }
+            Format::Json => {
+                let name = name.map(json_string).unwrap_or_else(|| "null".to_string());
+                writeln!(out, "{{\"name\":{},\"board\":\"{}\"}}", name, squares)
+            }
+        }
+    }
+
+    /// Write a line that is not a board, such as a total.  Skipped for the
+    /// `csv` and `json` formats, which only hold records, and written to
+    /// standard error instead.
+    pub fn note(&mut self, line: &str) -> io::Result<()> {
+        match self.format {
+            Format::Csv | Format::Json => {
+                eprintln!("{}", line);
+                Ok(())
+            }
+            _ => writeln!(self.out, "{}", line),
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// A value of a `ReportWriter` record.
+pub enum Field {
+    // Quoted in JSON
+    Text(String),
+    // Written as it is in JSON, for numbers and booleans
+    Raw(String),
+    // Empty in CSV, null in JSON
+    Null,
+}
+
+impl Field {
+    /// `Text`, or `Null` when there is no text.
+    pub fn text(text: Option<String>) -> Field {
+        text.map_or(Field::Null, Field::Text)
+    }
+
+    /// `Raw` of a number or boolean.
+    pub fn raw(value: impl ToString) -> Field {
+        Field::Raw(value.to_string())
+    }
+}
+
+/// Writes a record per puzzle for the commands that report on puzzles
+/// rather than print boards, such as `count`, `rate`, and `validate`.
+///
+/// The `csv` format writes a header of the field names then a line per
+/// record, and `json` an object per line.  Every record starts with the
+/// `index` of the puzzle, counted from 1, and its `name`.  The other formats
+/// write the line of text each command reads best as.
+pub struct ReportWriter<W: Write> {
+    out: W,
+    format: Format,
+    // whether the CSV header has been written
+    started: bool,
+}
+
+impl<W: Write> ReportWriter<W> {
+    pub fn new(out: W, format: Format) -> ReportWriter<W> {
+        ReportWriter {
+            out,
+            format,
+            started: false,
+        }
+    }
+
+    /// Write the record of the puzzle numbered `index`: `text` for the
+    /// text formats, `fields` by name for `csv` and `json`.
+    pub fn record(
+        &mut self,
+        index: usize,
+        name: Option<&str>,
+        text: &str,
+        fields: &[(&str, Field)],
+    ) -> io::Result<()> {
+        let name = Field::text(name.map(|name| name.trim().to_string()));
+        let index = Field::raw(index);
+        let all: Vec<(&str, &Field)> = [("index", &index), ("name", &name)]
+            .into_iter()
+            .chain(fields.iter().map(|(key, value)| (*key, value)))
+            .collect();
+        match self.format {
+            Format::Csv => {
+                if !self.started {
+                    let keys: Vec<&str> = all.iter().map(|(key, _)| *key).collect();
+                    writeln!(self.out, "{}", keys.join(","))?;
+                }
+                self.started = true;
+                let values: Vec<String> = all
+                    .iter()
+                    .map(|(_, value)| match value {
+                        Field::Text(text) | Field::Raw(text) => csv_field(text),
+                        Field::Null => String::new(),
+                    })
+                    .collect();
+                writeln!(self.out, "{}", values.join(","))
+            }
+            Format::Json => {
+                let members: Vec<String> = all
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Field::Text(text) => json_string(text),
+                            Field::Raw(raw) => raw.clone(),
+                            Field::Null => "null".to_string(),
+                        };
+                        format!("{}:{}", json_string(key), value)
+                    })
+                    .collect();
+                writeln!(self.out, "{{{}}}", members.join(","))
+            }
+            Format::Grid | Format::Line | Format::Pretty => writeln!(self.out, "{}", text),
+        }
+    }
+
+    /// Write a line that is not a record, such as a total, to standard
+    /// error for the `csv` and `json` formats, as `BoardWriter::note` does.
+    pub fn note(&mut self, line: &str) -> io::Result<()> {
+        match self.format {
+            Format::Csv | Format::Json => {
+                eprintln!("{}", line);
+                Ok(())
+            }
+            _ => writeln!(self.out, "{}", line),
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_field, BoardWriter, Field, ReportWriter};
+    use crate::cli::Format;
+    use sudoku::SudokuBoard;
+
+    fn written(format: Format) -> String {
+        written_with(format, false)
+    }
+
+    fn written_with(format: Format, pencil_marks: bool) -> String {
+        let board = SudokuBoard::new();
+        let mut writer = BoardWriter::new(Vec::new(), format).with_pencil_marks(pencil_marks);
+        writer.write(Some("Grid 01"), &board).unwrap();
+        writer.write(None, &board).unwrap();
+        String::from_utf8(writer.out).unwrap()
+    }
+
+    #[test]
+    fn test_board_writer() {
+        let empty = "-".repeat(81);
+        assert_eq!(written(Format::Line), format!("{0}\n{0}\n", empty));
+        assert_eq!(
+            written(Format::Csv),
+            format!("name,board\nGrid 01,{0}\n,{0}\n", empty)
+        );
+        assert_eq!(
+            written(Format::Json),
+            format!(
+                "{{\"name\":\"Grid 01\",\"board\":\"{0}\"}}\n{{\"name\":null,\"board\":\"{0}\"}}\n",
+                empty
+            )
+        );
+        let grid = written(Format::Grid);
+        assert!(grid.starts_with("Grid 01\n---------\n"));
+        assert_eq!(grid.lines().count(), 19);
+        assert!(written(Format::Pretty).starts_with("Grid 01\n┏"));
+    }
+
+    #[test]
+    fn test_pencil_marks() {
+        let all = vec!["123456789"; 81].join(" ");
+        assert_eq!(written_with(Format::Line, true), format!("{0}\n{0}\n", all));
+        assert!(written_with(Format::Csv, true).contains(&format!("Grid 01,{}\n", all)));
+        let grid = written_with(Format::Grid, true);
+        assert!(grid.starts_with("Grid 01\n┏"));
+        assert!(grid.contains("┃ 1 2 3 │ 1 2 3 │"));
+        assert_eq!(grid, written_with(Format::Pretty, true));
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("Grid 01"), "Grid 01");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_report_writer() {
+        let written = |format: Format| {
+            let mut writer = ReportWriter::new(Vec::new(), format);
+            let fields = [
+                ("valid", Field::raw(false)),
+                ("problem", Field::Text("no solution, at all".to_string())),
+            ];
+            writer
+                .record(1, Some("Grid 01 "), "Grid 01: invalid", &fields)
+                .unwrap();
+            let fields = [("valid", Field::raw(true)), ("problem", Field::Null)];
+            writer.record(2, None, "puzzle 2: ok", &fields).unwrap();
+            String::from_utf8(writer.out).unwrap()
+        };
+        assert_eq!(
+            written(Format::Csv),
+            "index,name,valid,problem\n1,Grid 01,false,\"no solution, at all\"\n2,,true,\n"
+        );
+        assert_eq!(
+            written(Format::Json),
+            concat!(
+                "{\"index\":1,\"name\":\"Grid 01\",\"valid\":false,\"problem\":\"no solution, at all\"}\n",
+                "{\"index\":2,\"name\":null,\"valid\":true,\"problem\":null}\n"
+            )
+        );
+        assert_eq!(written(Format::Line), "Grid 01: invalid\npuzzle 2: ok\n");
+        assert_eq!(written(Format::Grid), written(Format::Pretty));
+    }
+}
@@ -0,0 +1,105 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often the progress line is redrawn at most.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Width of the bar, in characters.
+const BAR_WIDTH: usize = 30;
+
+/// A progress line on standard error for long batch runs, showing how much
+/// is done, the throughput, and the time left.  It can be ticked from
+/// several threads at once.
+pub struct Progress {
+    enabled: bool,
+    total: usize,
+    done: AtomicUsize,
+    start: Instant,
+    // when the line was drawn last, None before the first draw
+    last_draw: Mutex<Option<Instant>>,
+}
+
+impl Progress {
+    /// A progress line for `total` items.  `show` forces it on or off, when
+    /// None it is shown only when standard error is a terminal.
+    pub fn new(total: usize, show: Option<bool>) -> Progress {
+        Progress {
+            enabled: show.unwrap_or_else(|| io::stderr().is_terminal()),
+            total,
+            done: AtomicUsize::new(0),
+            start: Instant::now(),
+            last_draw: Mutex::new(None),
+        }
+    }
+
+    /// Count one more item as done, redrawing the line if it is due.
+    pub fn tick(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if !self.enabled {
+            return;
+        }
+        let mut last_draw = self.last_draw.lock().unwrap();
+        let now = Instant::now();
+        if done < self.total && last_draw.is_some_and(|t| now - t < REDRAW_INTERVAL) {
+            return;
+        }
+        *last_draw = Some(now);
+        eprint!("\r{}", self.line(done, now - self.start));
+        let _ = io::stderr().flush();
+    }
+
+    /// Clear the line, so later messages start on an empty line.
+    pub fn finish(&self) {
+        if self.enabled && self.last_draw.lock().unwrap().is_some() {
+            eprint!(
+                "\r{}\r",
+                " ".repeat(self.line(self.total, Duration::ZERO).len())
+            );
+        }
+    }
+
+    fn line(&self, done: usize, elapsed: Duration) -> String {
+        let total = self.total.max(1);
+        let filled = done * BAR_WIDTH / total;
+        let rate = done as f64 / elapsed.as_secs_f64().max(1e-9);
+        let eta = if done == 0 {
+            "--".to_string()
+        } else {
+            let left = (self.total - done) as f64 / rate;
+            format!("{}:{:02}", left as u64 / 60, left as u64 % 60)
+        };
+        format!(
+            "[{}{}] {}/{} {:>3}% {:.0}/s ETA {}",
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            done,
+            self.total,
+            done * 100 / total,
+            rate,
+            eta
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Progress;
+    use std::time::Duration;
+
+    #[test]
+    fn test_progress_line() {
+        let progress = Progress::new(200, Some(false));
+        assert_eq!(
+            progress.line(50, Duration::from_secs(5)),
+            format!(
+                "[{}{}] 50/200  25% 10/s ETA 0:15",
+                "#".repeat(7),
+                "-".repeat(23)
+            )
+        );
+        progress.tick();
+        progress.finish();
+    }
+}
@@ -0,0 +1,193 @@
+use super::output::{Field, ReportWriter};
+use super::solve::{read_input, read_puzzles, Puzzle};
+use super::{cell_name, Failure, Format, ReportOptions};
+use std::io::{self, Write};
+use sudoku::{Difficulty, SeRating, Step, SudokuError};
+
+/// How one puzzle rates.
+pub struct Rating {
+    pub difficulty: Difficulty,
+    pub se: SeRating,
+    // The first step of the hardest technique, None when no step is needed
+    pub hardest: Option<Step>,
+}
+
+/// Grade one puzzle and find the step of its hardest technique.  Puzzles
+/// that cannot be read are reported as errors.
+pub fn rate_puzzle(puzzle: &Puzzle) -> Result<Rating, SudokuError> {
+    let board = puzzle.board()?;
+    Ok(Rating {
+        difficulty: board.difficulty(),
+        se: board.se_rating(),
+        hardest: board.hardest_step()?,
+    })
+}
+
+/// The technique of a step with its digits and squares, e.g.
+/// "X-Wing on 7 in r2c2, r2c8, r8c2, r8c8".
+pub fn describe_hardest(step: &Step) -> String {
+    let digits: Vec<String> = step.digits.iter().map(|d| d.to_string()).collect();
+    let cells: Vec<String> = step.cells.iter().map(|(r, c)| cell_name(*r, *c)).collect();
+    format!(
+        "{} on {} in {}",
+        step.technique.name(),
+        digits.join(", "),
+        cells.join(", ")
+    )
+}
+
+/// Count how many ratings got each difficulty, in the order of
+/// `Difficulty::ALL`.
+pub fn histogram(ratings: &[Difficulty]) -> [usize; 5] {
+    let mut counts = [0; 5];
+    for rating in ratings {
+        let i = Difficulty::ALL.iter().position(|d| d == rating).unwrap();
+        counts[i] += 1;
+    }
+    counts
+}
+
+/// Rate every puzzle in `contents`, writing a record each in `format`, and
+/// draw a histogram of the ratings.  Fails with `InvalidInput` after the
+/// histogram when any puzzle could not be read.
+pub fn rate_all(contents: &str, format: Format, out: &mut dyn Write) -> Result<(), Failure> {
+    let output_error =
+        |e: io::Error| Failure::Internal(format!("could not write standard output: {}", e));
+    let puzzles = read_puzzles(contents);
+    let mut writer = ReportWriter::new(out, format);
+    let mut ratings = Vec::new();
+    let mut invalid = 0;
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        let label = match &puzzle.name {
+            Some(name) => name.trim().to_string(),
+            None => format!("puzzle {}", i + 1),
+        };
+        let (text, fields) = match rate_puzzle(puzzle) {
+            Ok(rating) => {
+                ratings.push(rating.difficulty);
+                let hardest = rating.hardest.as_ref().map(describe_hardest);
+                let mut text = format!("{}: {}, SE {}", label, rating.difficulty.name(), rating.se);
+                if let Some(hardest) = &hardest {
+                    text += &format!(", hardest step {}", hardest);
+                }
+                let fields = [
+                    (
+                        "difficulty",
+                        Field::Text(rating.difficulty.name().to_string()),
+                    ),
+                    ("se", Field::raw(format!("{:.1}", rating.se.value))),
+                    ("se_lower_bound", Field::raw(rating.se.lower_bound)),
+                    ("hardest", Field::text(hardest)),
+                    ("error", Field::Null),
+                ];
+                (text, fields)
+            }
+            Err(e) => {
+                invalid += 1;
+                let fields = [
+                    ("difficulty", Field::Null),
+                    ("se", Field::Null),
+                    ("se_lower_bound", Field::Null),
+                    ("hardest", Field::Null),
+                    ("error", Field::Text(e.to_string())),
+                ];
+                (format!("{}: invalid ({})", label, e), fields)
+            }
+        };
+        writer
+            .record(i + 1, puzzle.name.as_deref(), &text, &fields)
+            .map_err(output_error)?;
+    }
+
+    writer.note("").map_err(output_error)?;
+    let counts = histogram(&ratings);
+    let widest = counts.iter().max().copied().unwrap_or(0).max(1);
+    for (difficulty, count) in Difficulty::ALL.iter().zip(counts) {
+        // scale the bars so the longest one is 40 characters
+        let bar = "#".repeat((count * 40).div_ceil(widest));
+        let line = format!("{:<10} {:>5} {}", difficulty.name(), count, bar);
+        writer.note(line.trim_end()).map_err(output_error)?;
+    }
+    if invalid > 0 {
+        let line = format!("{:<10} {:>5}", "invalid", invalid);
+        writer.note(&line).map_err(output_error)?;
+    }
+    writer.flush().map_err(output_error)?;
+    Failure::check_unread(invalid, puzzles.len())
+}
+
+pub fn run(options: &ReportOptions) -> Result<(), Failure> {
+    let file_contents = read_input(options.input.as_deref())?;
+    rate_all(&file_contents, options.format, &mut io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_hardest, histogram, rate_all, rate_puzzle};
+    use crate::cli::solve::{read_puzzles, Puzzle};
+    use crate::cli::Format;
+    use sudoku::Difficulty;
+
+    #[test]
+    fn test_rate_puzzles() {
+        let contents = std::fs::read_to_string("test_sudoku.txt").unwrap();
+        let puzzles = read_puzzles(&contents);
+        let ratings: Vec<Difficulty> = puzzles
+            .iter()
+            .map(|p| rate_puzzle(p).unwrap().difficulty)
+            .collect();
+        assert_eq!(histogram(&ratings).iter().sum::<usize>(), puzzles.len());
+        assert_eq!(
+            histogram(&[Difficulty::Easy, Difficulty::Hard, Difficulty::Easy]),
+            [2, 0, 1, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_describe_hardest() {
+        let puzzle = Puzzle {
+            name: None,
+            grid:
+                "100000569492056108056109240009640801064010000218035604040500016905061402621000005"
+                    .to_string(),
+            line: None,
+        };
+        let rating = rate_puzzle(&puzzle).unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Expert);
+        assert!(rating.se.value >= 3.2);
+        let text = describe_hardest(&rating.hardest.unwrap());
+        assert!(text.starts_with("X-Wing on 7 in r"), "{}", text);
+        assert_eq!(text.matches('r').count(), 4);
+    }
+
+    #[test]
+    fn test_rate_all_invalid() {
+        let mut out = Vec::new();
+        let contents = format!("{}\n123\n", "-".repeat(81));
+        let failure = rate_all(&contents, Format::Grid, &mut out).unwrap_err();
+        assert_eq!(failure.exit_code(), 2);
+        assert_eq!(failure.message(), "1 of 2 puzzles could not be read");
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("puzzle 2: invalid ("));
+        assert!(text.trim_end().ends_with("invalid        1"));
+    }
+
+    #[test]
+    fn test_rate_all_format() {
+        let contents = format!("{}\n123\n", "-".repeat(81));
+        let mut out = Vec::new();
+        rate_all(&contents, Format::Json, &mut out).unwrap_err();
+        let text = String::from_utf8(out).unwrap();
+        // the histogram goes to standard error
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.starts_with("{\"index\":1,\"name\":null,\"difficulty\":\""));
+        assert!(text.contains("\"se_lower_bound\":"));
+        assert!(text.ends_with("\"error\":\"expected 81 squares, found 3\"}\n"));
+
+        let mut out = Vec::new();
+        rate_all(&contents, Format::Csv, &mut out).unwrap_err();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("index,name,difficulty,se,se_lower_bound,hardest,error\n1,,"));
+        assert!(text.ends_with("2,,,,,,\"expected 81 squares, found 3\"\n"));
+    }
+}
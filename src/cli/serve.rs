@@ -0,0 +1,204 @@
+//! The `--serve-stdio` mode: JSON-RPC 2.0 requests, one per line on
+//! standard input, each answered by one line on standard output.
+//!
+//! ```text
+//! {"jsonrpc":"2.0","id":1,"method":"solve","params":{"puzzle":"4----8---..."}}
+//! {"jsonrpc":"2.0","id":1,"result":{"solution":"492378165...","guesses":0,"backtracks":0}}
+//! ```
+//!
+//! The methods are `solve`, `hint`, and `validate`, each taking the puzzle
+//! as the `puzzle` parameter.
+
+use super::hint::parse_puzzle;
+use super::solve::fill_in;
+use super::validate::{check_solutions, describe_error};
+use super::Failure;
+use std::io::{self, BufRead, Write};
+use sudoku::{json_string, Json, SudokuBoard};
+
+// Error codes defined by JSON-RPC 2.0
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+/// The puzzle was read but has no solution.
+const NOT_SOLVABLE: i32 = 1;
+
+/// Moves as `[row, col, digit]` triples.
+fn moves(moves: &[(usize, usize, i32)]) -> String {
+    let moves: Vec<String> = moves
+        .iter()
+        .map(|(r, c, d)| format!("[{},{},{}]", r, c, d))
+        .collect();
+    format!("[{}]", moves.join(","))
+}
+
+/// The `puzzle` string of the params of a request.
+fn puzzle(params: Option<&Json>) -> Result<&str, (i32, String)> {
+    match params.and_then(|p| p.get("puzzle")) {
+        Some(Json::String(puzzle)) => Ok(puzzle),
+        _ => Err((INVALID_PARAMS, "missing `puzzle`".into())),
+    }
+}
+
+/// The board of the `puzzle` param, which must be readable.
+fn board(params: Option<&Json>) -> Result<SudokuBoard, (i32, String)> {
+    parse_puzzle(puzzle(params)?).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+/// Run one method.  The result is JSON text, the error a code and message.
+/// The method is looked up before its params are checked.  Every method
+/// reads the puzzle the same way, as `hint` on the command line does.
+fn call(method: &str, params: Option<&Json>) -> Result<String, (i32, String)> {
+    match method {
+        "solve" => {
+            let board = board(params)?;
+            let (board, stats) = fill_in(&board).map_err(|e| (NOT_SOLVABLE, e.to_string()))?;
+            Ok(format!(
+                "{{\"solution\":{},\"guesses\":{},\"backtracks\":{}}}",
+                json_string(&board.print_board()),
+                stats.guesses,
+                stats.backtracks
+            ))
+        }
+        "hint" => {
+            let board = board(params)?;
+            Ok(match board.hint() {
+                Some(hint) => format!(
+                    "{{\"technique\":{},\"explanation\":{},\"placements\":{},\"eliminations\":{}}}",
+                    json_string(hint.technique().name()),
+                    json_string(&hint.explanation),
+                    moves(&hint.step.placements),
+                    moves(&hint.step.eliminations)
+                ),
+                None => "null".to_string(),
+            })
+        }
+        "validate" => {
+            let problems = match &parse_puzzle(puzzle(params)?) {
+                Ok(board) => check_solutions(board),
+                Err(e) => vec![describe_error(e)],
+            };
+            let problems: Vec<String> = problems.iter().map(|p| json_string(p)).collect();
+            Ok(format!(
+                "{{\"valid\":{},\"problems\":[{}]}}",
+                problems.is_empty(),
+                problems.join(",")
+            ))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+    }
+}
+
+/// Answer one request line.  Notifications, requests without an `id`, get
+/// no answer.
+pub fn respond(line: &str) -> Option<String> {
+    let answer = |id: &Json, outcome: Result<String, (i32, String)>| {
+        let outcome = match outcome {
+            Ok(result) => format!("\"result\":{}", result),
+            Err((code, message)) => format!(
+                "\"error\":{{\"code\":{},\"message\":{}}}",
+                code,
+                json_string(&message)
+            ),
+        };
+        Some(format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":{},{}}}",
+            id.write(),
+            outcome
+        ))
+    };
+    let request = match Json::parse(line) {
+        Ok(request @ Json::Object(_)) => request,
+        Ok(_) => return answer(&Json::Null, Err((INVALID_REQUEST, "not an object".into()))),
+        Err(e) => return answer(&Json::Null, Err((PARSE_ERROR, e.to_string()))),
+    };
+    let id = request.get("id")?;
+    let method = match request.get("method") {
+        Some(Json::String(method)) => method,
+        _ => return answer(id, Err((INVALID_REQUEST, "missing method".into()))),
+    };
+    answer(id, call(method, request.get("params")))
+}
+
+pub fn run() -> Result<(), Failure> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let write_failed = |e: io::Error| Failure::Internal(format!("cannot write: {}", e));
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| Failure::Internal(format!("cannot read: {}", e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = respond(&line) {
+            writeln!(out, "{}", response).map_err(write_failed)?;
+            out.flush().map_err(write_failed)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::respond;
+    use sudoku::SudokuBoard;
+
+    const PUZZLE: &str = concat!(
+        "4----8---",
+        "----91-8-",
+        "-865-2-3-",
+        "-2-4--9--",
+        "-1-2----6",
+        "367-59---",
+        "-----5---",
+        "7--8---24",
+        "2--93--7-"
+    );
+
+    fn request(id: &str, method: &str, puzzle: &str) -> String {
+        format!(
+            "{{\"jsonrpc\": \"2.0\", \"id\": {}, \"method\": \"{}\", \"params\": {{\"puzzle\": \"{}\"}}}}",
+            id, method, puzzle
+        )
+    }
+
+    #[test]
+    fn test_respond() {
+        let solved = respond(&request("1", "solve", PUZZLE)).unwrap();
+        assert!(solved.starts_with("{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"solution\":\"4923"));
+
+        let hint = respond(&request("\"a\"", "hint", PUZZLE)).unwrap();
+        assert!(hint.starts_with("{\"jsonrpc\":\"2.0\",\"id\":\"a\",\"result\":{\"technique\":"));
+
+        let valid = respond(&request("2", "validate", PUZZLE)).unwrap();
+        assert!(valid.ends_with("\"result\":{\"valid\":true,\"problems\":[]}}"));
+        let invalid = respond(&request("2", "validate", "11")).unwrap();
+        assert!(invalid.contains("\"valid\":false"));
+
+        // pencil marks are read the same way by every method
+        let marks = SudokuBoard::parse_grid(PUZZLE)
+            .unwrap()
+            .print_pencil_marks();
+        let marks = marks.replace('\n', "\\n");
+        for method in ["solve", "hint", "validate"] {
+            let response = respond(&request("4", method, &marks)).unwrap();
+            assert!(response.contains("\"result\":"), "{}: {}", method, response);
+        }
+
+        let error = |line: &str| {
+            let response = respond(line).unwrap();
+            let code = response.split("\"code\":").nth(1).unwrap();
+            code.split(',').next().unwrap().parse::<i32>().unwrap()
+        };
+        assert_eq!(error(&request("3", "solve", "123")), -32602);
+        assert_eq!(error(&request("3", "guess", PUZZLE)), -32601);
+        assert_eq!(error("{\"id\": 3, \"method\": \"solve\"}"), -32602);
+        // an unknown method is reported before its missing params
+        assert_eq!(error("{\"id\":1,\"method\":\"nope\"}"), -32601);
+        assert_eq!(error("[1]"), -32600);
+        assert_eq!(error("{"), -32700);
+        assert_eq!(error(&"[".repeat(200000)), -32700);
+        // a notification gets no answer
+        assert_eq!(respond("{\"method\": \"solve\"}"), None);
+    }
+}
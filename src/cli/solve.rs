@@ -0,0 +1,696 @@
+use super::hint::describe_step;
+use super::output::BoardWriter;
+use super::progress::Progress;
+use super::{Failure, Format, SolveOptions};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use sudoku::{is_blank, json_string, SolveOutcome, SolveStats, SudokuBoard, SudokuError};
+
+/// A puzzle read from the input.
+pub struct Puzzle {
+    // The `Grid` header line before the puzzle, if there was one
+    pub name: Option<String>,
+    pub grid: String,
+    // The line of the input the puzzle starts on, its header if it has one
+    pub line: Option<usize>,
+}
+
+impl Puzzle {
+    /// The puzzle's number, name, and line, for messages about it.
+    pub fn describe(&self, index: usize) -> String {
+        let mut description = format!("puzzle {}", index);
+        if let Some(name) = &self.name {
+            description += &format!(" ({})", name.trim());
+        }
+        if let Some(line) = self.line {
+            description += &format!(" at line {}", line);
+        }
+        description
+    }
+
+    /// The board of the puzzle, read with `parse_grid`, so a record cut
+    /// short fails with `InvalidCellCount`.
+    pub fn board(&self) -> Result<SudokuBoard, SudokuError> {
+        SudokuBoard::parse_grid(&self.grid)
+    }
+}
+
+/// The number of squares written on a line, known or not.
+pub fn count_squares(line: &str) -> usize {
+    line.chars()
+        .filter(|c| c.is_ascii_digit() || is_blank(*c))
+        .count()
+}
+
+/// The result of solving one puzzle.
+pub struct Outcome {
+    pub result: Result<(SudokuBoard, SolveStats), SudokuError>,
+    pub elapsed: Duration,
+    // Lines describing the steps taken, empty unless tracing
+    pub trace: Vec<String>,
+}
+
+/// Totals over every puzzle of a batch, printed once the batch is done.
+#[derive(Default)]
+struct Summary {
+    solved: usize,
+    // description of each puzzle that failed, with the reason
+    failures: Vec<(String, SudokuError)>,
+    total_time: Duration,
+    slowest: Duration,
+    guesses: usize,
+}
+
+impl Summary {
+    fn add(&mut self, index: usize, puzzle: &Puzzle, outcome: Outcome) -> Option<SudokuBoard> {
+        self.total_time += outcome.elapsed;
+        self.slowest = self.slowest.max(outcome.elapsed);
+        match outcome.result {
+            Ok((board, stats)) => {
+                self.solved += 1;
+                self.guesses += stats.guesses;
+                Some(board)
+            }
+            Err(e) => {
+                self.failures.push((puzzle.describe(index), e));
+                None
+            }
+        }
+    }
+
+    /// Why the batch failed, if it did.  Puzzles that could not be read
+    /// count before puzzles that have no solution.
+    fn failure(&self) -> Option<Failure> {
+        let count = self.solved + self.failures.len();
+        let message = format!("{} of {} puzzles failed", self.failures.len(), count);
+        if self.failures.iter().any(|(_, e)| is_input_error(e)) {
+            Some(Failure::InvalidInput(message))
+        } else if !self.failures.is_empty() {
+            Some(Failure::Unsolvable(message))
+        } else {
+            None
+        }
+    }
+
+    fn print(&self, wall_time: Duration) {
+        let count = self.solved + self.failures.len();
+        let average = self.total_time / count.max(1) as u32;
+        eprintln!(
+            "Solved {} of {} puzzles in {:?} ({:?} per puzzle, slowest {:?}), {} guesses",
+            self.solved, count, wall_time, average, self.slowest, self.guesses
+        );
+        self.print_failures();
+    }
+
+    fn print_failures(&self) {
+        for (puzzle, e) in &self.failures {
+            eprintln!("  {} failed: {}", puzzle, e);
+        }
+    }
+}
+
+/// Whether the error means the puzzle could not be read, rather than
+/// having no solution.
+fn is_input_error(e: &SudokuError) -> bool {
+    matches!(
+        e,
+        SudokuError::InvalidRange
+            | SudokuError::InvalidCellCount(_)
+            | SudokuError::InvalidCharacter(_)
+            | SudokuError::ConflictingGivens { .. }
+            | SudokuError::Parse(_)
+            | SudokuError::InvalidEncoding
+            | SudokuError::UnsupportedVersion(_)
+    )
+}
+
+/// Read the whole input file, standard input when no file was given.
+pub fn read_input(input: Option<&str>) -> Result<String, Failure> {
+    let contents = match input {
+        Some(file_name) => fs::read_to_string(file_name),
+        None => {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents).map(|_| contents)
+        }
+    };
+    contents.map_err(|e| {
+        let name = input.unwrap_or("standard input");
+        Failure::InvalidInput(format!("could not read {}: {}", name, e))
+    })
+}
+
+/// Split the input into puzzles.  A puzzle is 9 lines, or a single line
+/// of all 81 squares, optionally after a line containing `Grid` that names
+/// it.  Line endings may be CRLF, and
+/// blank lines and trailing whitespace are skipped.  A record cut short by
+/// the next header or the end of the input is kept as it is, so it fails to
+/// parse on its own instead of running into the puzzle after it.
+pub fn read_puzzles(contents: &str) -> Vec<Puzzle> {
+    let mut puzzles = Vec::new();
+    let mut name = None;
+    let mut start = None;
+    let mut collected_lines = 0;
+    let mut grid = String::new();
+    let mut flush = |name: &mut Option<String>, start: &mut Option<usize>, grid: &mut String| {
+        puzzles.push(Puzzle {
+            name: name.take(),
+            grid: std::mem::take(grid),
+            line: start.take(),
+        });
+    };
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim_end();
+        if line.trim_start().is_empty() {
+            continue;
+        }
+        if line.contains("Grid") {
+            if collected_lines > 0 || name.is_some() {
+                flush(&mut name, &mut start, &mut grid);
+                collected_lines = 0;
+            }
+            name = Some(line.to_string());
+            start = Some(i + 1);
+        } else {
+            // a line holding the whole puzzle is a record of its own
+            collected_lines += match count_squares(line) {
+                81 if collected_lines == 0 => 9,
+                _ => 1,
+            };
+            grid += line;
+            start.get_or_insert(i + 1);
+            if collected_lines == 9 {
+                flush(&mut name, &mut start, &mut grid);
+                collected_lines = 0;
+            }
+        }
+    }
+    if collected_lines > 0 || name.is_some() {
+        flush(&mut name, &mut start, &mut grid);
+    }
+    puzzles
+}
+
+/// Describe the logical steps that solve as much of the board as the
+/// techniques can, one line per step and its details.
+pub fn trace_steps(board: &SudokuBoard) -> Vec<String> {
+    let mut board = board.clone();
+    let mut trace = Vec::new();
+    let mut number = 0;
+    while let Some(step) = board.next_step() {
+        number += 1;
+        for (i, line) in describe_step(&step).lines().enumerate() {
+            match i {
+                0 => trace.push(format!("step {}: {}", number, line)),
+                _ => trace.push(line.to_string()),
+            }
+        }
+        if board.apply_step(&step).is_err() {
+            trace.push("  the step leaves a square without candidates".to_string());
+            break;
+        }
+    }
+    trace
+}
+
+/// The board as far as the logical techniques get it, with the candidates
+/// left in each unknown square, to show where solving gets stuck.
+pub fn logical_position(grid: &str) -> Result<SudokuBoard, SudokuError> {
+    let mut board = SudokuBoard::parse_grid(grid)?;
+    board.solve_with_report()?;
+    Ok(board)
+}
+
+/// The board with the first solution the search found filled in, and how
+/// much guessing it took.  A board with no solution is a `NotSolvable`
+/// error, as the output reports it.
+pub fn fill_in(board: &SudokuBoard) -> Result<(SudokuBoard, SolveStats), SudokuError> {
+    let (outcome, stats) = board.solve_outcome_first_with_stats();
+    let solution = match outcome {
+        SolveOutcome::Found(solution)
+        | SolveOutcome::Unique(solution)
+        | SolveOutcome::Multiple(solution, _) => solution,
+        SolveOutcome::Unsolvable => return Err(SudokuError::NotSolvable(None)),
+        SolveOutcome::Incomplete(e) => return Err(e),
+    };
+    let mut solved = board.clone();
+    solved.fill_solution(&solution)?;
+    Ok((solved, stats))
+}
+
+pub fn solve_puzzle(puzzle: &Puzzle, verbose: bool) -> Outcome {
+    let start = Instant::now();
+    let mut trace = Vec::new();
+    let result = puzzle.board().and_then(|board| {
+        if verbose {
+            trace = trace_steps(&board);
+        }
+        let (board, stats) = fill_in(&board)?;
+        if verbose && stats.guesses > 0 {
+            trace.push(format!(
+                "no logical step left, solved by guessing {} times with {} backtracks",
+                stats.guesses, stats.backtracks
+            ));
+        }
+        Ok((board, stats))
+    });
+    Outcome {
+        result,
+        elapsed: start.elapsed(),
+        trace,
+    }
+}
+
+/// Solve every puzzle, spreading the work over `jobs` threads.  The
+/// outcomes are returned in the same order as the puzzles.
+pub fn solve_all(
+    puzzles: &[Puzzle],
+    jobs: usize,
+    verbose: bool,
+    progress: &Progress,
+) -> Vec<Outcome> {
+    let solve_puzzle = |puzzle| {
+        let outcome = solve_puzzle(puzzle, verbose);
+        progress.tick();
+        outcome
+    };
+    if jobs <= 1 {
+        return puzzles.iter().map(solve_puzzle).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let mut outcomes: Vec<Option<Outcome>> = puzzles.iter().map(|_| None).collect();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        match puzzles.get(i) {
+                            Some(puzzle) => done.push((i, solve_puzzle(puzzle))),
+                            None => return done,
+                        }
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (i, outcome) in worker.join().unwrap() {
+                outcomes[i] = Some(outcome);
+            }
+        }
+    });
+    outcomes.into_iter().map(|o| o.unwrap()).collect()
+}
+
+/// Open the output named by the options, standard output when no file was
+/// given.
+fn open_output(options: &SolveOptions) -> io::Result<Box<dyn Write>> {
+    match &options.output {
+        Some(file_name) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(options.append)
+                .truncate(!options.append)
+                .open(file_name)?;
+            Ok(Box::new(io::BufWriter::new(file)))
+        }
+        None => Ok(Box::new(io::stdout().lock())),
+    }
+}
+
+/// The number formed by the first three squares of a solved board, as
+/// summed by Project Euler problem 96.
+pub fn euler_number(board: &SudokuBoard) -> u32 {
+    board.as_bytes()[..3]
+        .iter()
+        .fold(0, |n, b| n * 10 + (b - b'0') as u32)
+}
+
+/// Describe one puzzle and its outcome as a single line JSON object.
+pub fn json_outcome(index: usize, puzzle: &Puzzle, outcome: &Outcome) -> String {
+    let name = match &puzzle.name {
+        Some(name) => json_string(name.trim()),
+        None => "null".to_string(),
+    };
+    let fields = match &outcome.result {
+        Ok((board, stats)) => format!(
+            "\"status\":\"solved\",\"solution\":{},\"error\":null,\"guesses\":{},\"backtracks\":{}",
+            json_string(&board.print_board()),
+            stats.guesses,
+            stats.backtracks
+        ),
+        Err(e) => format!(
+            "\"status\":\"error\",\"solution\":null,\"error\":{},\"guesses\":null,\"backtracks\":null",
+            json_string(&e.to_string())
+        ),
+    };
+    format!(
+        "{{\"index\":{},\"name\":{},\"puzzle\":{},{},\"elapsed_us\":{}}}",
+        index,
+        name,
+        json_string(&puzzle.grid),
+        fields,
+        outcome.elapsed.as_micros()
+    )
+}
+
+/// The puzzles to solve: the one given with `--puzzle`, or those of the
+/// input.
+fn load_puzzles(options: &SolveOptions) -> Result<Vec<Puzzle>, Failure> {
+    match &options.puzzle {
+        Some(grid) => {
+            let board = SudokuBoard::parse_grid(grid)
+                .map_err(|e| Failure::InvalidInput(format!("invalid puzzle: {}", e)))?;
+            Ok(vec![Puzzle {
+                name: None,
+                grid: board.print_board(),
+                line: None,
+            }])
+        }
+        None => Ok(read_puzzles(&read_input(options.input.as_deref())?)),
+    }
+}
+
+/// Solve the puzzles of a memory mapped input file, one per line, and write
+/// the solutions in order.  Only the digits of each solution are kept, so
+/// puzzles are not timed one by one.
+#[cfg(feature = "mmap")]
+fn run_mapped(options: &SolveOptions) -> Result<(), Failure> {
+    let file_name = options.input.as_deref().unwrap_or_default();
+    let puzzles = sudoku::MappedPuzzles::open(file_name)
+        .map_err(|e| Failure::InvalidInput(format!("could not read {}: {}", file_name, e)))?;
+    let output_error = |e: io::Error| {
+        let name = options.output.as_deref().unwrap_or("standard output");
+        Failure::Internal(format!("could not write {}: {}", name, e))
+    };
+    let mut out = BoardWriter::new(open_output(options).map_err(output_error)?, options.format);
+
+    let start = Instant::now();
+    let outcomes = puzzles.solve_all(options.jobs);
+    let wall_time = start.elapsed();
+    let mut summary = Summary::default();
+    let mut euler_sum = 0;
+    for (i, ((line, result), (_, record))) in
+        outcomes.into_iter().zip(puzzles.records()).enumerate()
+    {
+        let puzzle = Puzzle {
+            name: None,
+            grid: String::from_utf8_lossy(record).into_owned(),
+            line: Some(line),
+        };
+        let outcome = Outcome {
+            result: result.and_then(|(digits, stats)| {
+                SudokuBoard::from_record(&digits).map(|board| (board, stats))
+            }),
+            elapsed: Duration::ZERO,
+            trace: Vec::new(),
+        };
+        if options.format == Format::Json {
+            let record = json_outcome(i + 1, &puzzle, &outcome);
+            writeln!(out.get_mut(), "{}", record).map_err(output_error)?;
+        }
+        if let Some(board) = summary.add(i + 1, &puzzle, outcome) {
+            euler_sum += euler_number(&board);
+            if options.format != Format::Json {
+                out.write(None, &board).map_err(output_error)?;
+            }
+        }
+    }
+    if options.euler_sum {
+        out.note(&format!("Euler sum: {}", euler_sum))
+            .map_err(output_error)?;
+    }
+    out.flush().map_err(output_error)?;
+    eprintln!(
+        "Solved {} of {} puzzles in {:?}, {} guesses",
+        summary.solved,
+        summary.solved + summary.failures.len(),
+        wall_time,
+        summary.guesses
+    );
+    summary.print_failures();
+    match summary.failure() {
+        Some(failure) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+pub fn run(options: &SolveOptions) -> Result<(), Failure> {
+    #[cfg(feature = "mmap")]
+    if options.mmap {
+        return run_mapped(options);
+    }
+    let puzzles = load_puzzles(options)?;
+    let output_error = |e: io::Error| {
+        let name = options.output.as_deref().unwrap_or("standard output");
+        Failure::Internal(format!("could not write {}: {}", name, e))
+    };
+    let out = BoardWriter::new(open_output(options).map_err(output_error)?, options.format);
+    let mut out = out.with_pencil_marks(options.pencil_marks);
+
+    let start = Instant::now();
+    let progress = Progress::new(puzzles.len(), options.progress);
+    let outcomes = solve_all(&puzzles, options.jobs, options.verbose, &progress);
+    progress.finish();
+    let mut summary = Summary::default();
+    let mut euler_sum = 0;
+    for (i, (puzzle, outcome)) in puzzles.iter().zip(outcomes).enumerate() {
+        if !outcome.trace.is_empty() {
+            out.flush().map_err(output_error)?;
+            match &puzzle.name {
+                Some(name) => eprintln!("{}", name.trim()),
+                None => eprintln!("puzzle {}", i + 1),
+            }
+            for line in &outcome.trace {
+                eprintln!("  {}", line);
+            }
+        }
+        // JSON records every puzzle, with its status and statistics
+        if options.format == Format::Json {
+            let record = json_outcome(i + 1, puzzle, &outcome);
+            writeln!(out.get_mut(), "{}", record).map_err(output_error)?;
+        }
+        if let Some(board) = summary.add(i + 1, puzzle, outcome) {
+            euler_sum += euler_number(&board);
+            if options.format != Format::Json {
+                let name = puzzle.name.as_deref().map(str::trim);
+                let board = if options.pencil_marks {
+                    logical_position(&puzzle.grid).unwrap_or(board)
+                } else {
+                    board
+                };
+                out.write(name, &board).map_err(output_error)?;
+            }
+            if options.step_numbers {
+                if let Ok(order) = puzzle
+                    .board()
+                    .and_then(|mut board| board.solve_with_order())
+                {
+                    out.note(&format!("Step numbers\n{}", order))
+                        .map_err(output_error)?;
+                }
+            }
+        }
+    }
+    if options.euler_sum {
+        out.note(&format!("Euler sum: {}", euler_sum))
+            .map_err(output_error)?;
+    }
+    out.flush().map_err(output_error)?;
+    summary.print(start.elapsed());
+    match summary.failure() {
+        Some(failure) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        euler_number, json_outcome, load_puzzles, read_puzzles, solve_all, solve_puzzle,
+        trace_steps, Summary,
+    };
+    use crate::cli::progress::Progress;
+    use crate::cli::{Failure, SolveOptions};
+
+    #[test]
+    fn test_read_puzzles() {
+        let contents = format!(
+            "Grid 01\n{}Grid 02\n{}",
+            "123456789\n".repeat(9),
+            "0\n".repeat(9)
+        );
+        let puzzles = read_puzzles(&contents);
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].name.as_deref(), Some("Grid 01"));
+        assert_eq!(puzzles[0].grid, "123456789".repeat(9));
+        assert_eq!(puzzles[1].grid, "0".repeat(9));
+        assert!(solve_puzzle(&puzzles[0], false).result.is_err());
+    }
+
+    #[test]
+    fn test_read_messy_puzzles() {
+        let grid = "123456789\r\n".repeat(9);
+        let contents = format!(
+            "Grid 01 \r\n{}\r\n\r\nGrid 02\r\n{}Grid 03\n\n{}  \n",
+            grid,
+            "000000000\n".repeat(5),
+            "000000000  \n\n".repeat(9)
+        );
+        let puzzles = read_puzzles(&contents);
+        assert_eq!(puzzles.len(), 3);
+        assert_eq!(puzzles[0].name.as_deref(), Some("Grid 01"));
+        assert_eq!(puzzles[0].grid, "123456789".repeat(9));
+        assert_eq!(puzzles[0].line, Some(1));
+        // the short record stays on its own
+        assert_eq!(puzzles[1].grid, "0".repeat(45));
+        assert_eq!(puzzles[1].describe(2), "puzzle 2 (Grid 02) at line 13");
+        assert_eq!(puzzles[2].grid, "0".repeat(81));
+        assert_eq!(puzzles[2].line, Some(19));
+
+        // as does a short final record, and a header with nothing after it
+        let puzzles = read_puzzles(&format!("{}123\nGrid 02", grid));
+        assert_eq!(puzzles.len(), 3);
+        assert_eq!(puzzles[1].grid, "123");
+        assert_eq!(puzzles[1].describe(2), "puzzle 2 at line 10");
+        assert_eq!(puzzles[2].grid, "");
+        assert!(solve_puzzle(&puzzles[1], false).result.is_err());
+        assert!(read_puzzles("\n \r\n").is_empty());
+
+        // one puzzle per line, as `--format line` writes them
+        let line =
+            "4....8.......91.8..865.2.3..2.4..9...1.2....6367.59........5___7..8...242..93..7.";
+        let puzzles = read_puzzles(&format!("{0}\n{0}\nGrid 03\n{0}\n", line));
+        assert_eq!(puzzles.len(), 3);
+        assert_eq!(puzzles[1].line, Some(2));
+        assert_eq!(puzzles[2].name.as_deref(), Some("Grid 03"));
+        for puzzle in &puzzles {
+            assert_eq!(puzzle.grid, line);
+            assert!(solve_puzzle(puzzle, false).result.is_ok());
+        }
+        assert!(matches!(
+            puzzles[0].board(),
+            Ok(board) if board.print_board() == line.replace(['.', '_'], "-")
+        ));
+    }
+
+    #[test]
+    fn test_euler_number() {
+        let contents = std::fs::read_to_string("test_sudoku.txt").unwrap();
+        let puzzles = read_puzzles(&contents);
+        let (board, _) = solve_puzzle(&puzzles[0], false).result.unwrap();
+        assert_eq!(euler_number(&board), 127);
+    }
+
+    #[test]
+    fn test_inline_puzzle() {
+        let grid = concat!(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6",
+            ".6....28....419..5....8..79"
+        );
+        let options = SolveOptions {
+            puzzle: Some(grid.to_string()),
+            ..Default::default()
+        };
+        let puzzles = load_puzzles(&options).unwrap();
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].grid, grid.replace('.', "-"));
+        assert!(solve_puzzle(&puzzles[0], false).result.is_ok());
+
+        let options = SolveOptions {
+            puzzle: Some(grid[..80].to_string()),
+            ..Default::default()
+        };
+        assert_eq!(load_puzzles(&options).err().unwrap().exit_code(), 2);
+    }
+
+    #[test]
+    fn test_summary_failure() {
+        // r1c8 and r1c9 can both only be 9
+        let unsolvable = format!(
+            "123456700\n{}000000080\n{}000000008\n{}",
+            "000000000\n".repeat(2),
+            "000000000\n".repeat(2),
+            "000000000\n".repeat(2)
+        );
+        let contents = format!("{}{}0000000001\n", unsolvable, "000000000\n".repeat(8));
+        let puzzles = read_puzzles(&contents);
+        let mut summary = Summary::default();
+        assert_eq!(summary.failure(), None);
+        summary.add(1, &puzzles[0], solve_puzzle(&puzzles[0], false));
+        assert_eq!(
+            summary.failure(),
+            Some(Failure::Unsolvable("1 of 1 puzzles failed".to_string()))
+        );
+        // an 82nd square does not fit on the board
+        summary.add(2, &puzzles[1], solve_puzzle(&puzzles[1], false));
+        assert_eq!(summary.failure().unwrap().exit_code(), 2);
+
+        // repeated givens cannot be read, as with `solve -p`
+        let repeated = format!("11{}\n", "0".repeat(79));
+        let puzzles = read_puzzles(&repeated);
+        let mut summary = Summary::default();
+        summary.add(1, &puzzles[0], solve_puzzle(&puzzles[0], false));
+        assert_eq!(summary.failure().unwrap().exit_code(), 2);
+        let options = SolveOptions {
+            puzzle: Some(repeated.trim().to_string()),
+            ..Default::default()
+        };
+        assert_eq!(load_puzzles(&options).err().unwrap().exit_code(), 2);
+    }
+
+    #[test]
+    fn test_trace_steps() {
+        let contents = std::fs::read_to_string("test_sudoku.txt").unwrap();
+        let puzzles = read_puzzles(&contents);
+        let outcome = solve_puzzle(&puzzles[0], true);
+        let board = sudoku::SudokuBoard::fill_board(&puzzles[0].grid).unwrap();
+        assert_eq!(outcome.trace, trace_steps(&board));
+        assert!(outcome.trace[0].starts_with("step 1: "));
+        assert!(outcome.trace[1].starts_with("  place "));
+        assert!(solve_puzzle(&puzzles[0], false).trace.is_empty());
+    }
+
+    #[test]
+    fn test_json_outcome() {
+        let contents = format!(
+            "Grid 01\n{}Grid 02\n{}",
+            "123456789\n".repeat(9),
+            "0\n".repeat(9)
+        );
+        let puzzles = read_puzzles(&contents);
+        let mut outcome = solve_puzzle(&puzzles[0], false);
+        outcome.elapsed = std::time::Duration::from_micros(12);
+        assert_eq!(
+            json_outcome(1, &puzzles[0], &outcome),
+            format!(
+                concat!(
+                    "{{\"index\":1,\"name\":\"Grid 01\",\"puzzle\":\"{}\",",
+                    "\"status\":\"error\",\"solution\":null,\"error\":\"1 is given in both r1c1 and r2c1\",",
+                    "\"guesses\":null,\"backtracks\":null,\"elapsed_us\":12}}"
+                ),
+                "123456789".repeat(9)
+            )
+        );
+    }
+
+    #[test]
+    fn test_solve_all_keeps_order() {
+        let contents = std::fs::read_to_string("test_sudoku.txt").unwrap();
+        let puzzles = read_puzzles(&contents);
+        let progress = Progress::new(puzzles.len() * 2, Some(false));
+        let expected: Vec<String> = solve_all(&puzzles, 1, false, &progress)
+            .into_iter()
+            .map(|o| o.result.unwrap().0.print_board())
+            .collect();
+        let parallel: Vec<String> = solve_all(&puzzles, 3, false, &progress)
+            .into_iter()
+            .map(|o| o.result.unwrap().0.print_board())
+            .collect();
+        assert_eq!(parallel, expected);
+    }
+}
@@ -0,0 +1,366 @@
+use super::hint::describe_step;
+use super::solve::{fill_in, read_input, read_puzzles};
+use super::{Failure, InputOptions};
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+use sudoku::{Generator, GeneratorOptions, SudokuBoard};
+
+const HELP: &str =
+    "arrows/hjkl move  1-9 enter  0 clear  p pencil marks  ? hint  c check  s solve  q quit";
+
+/// A key the game reacts to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Digit(u8),
+    Clear,
+    Pencil,
+    Hint,
+    Check,
+    Solve,
+    Quit,
+    Other,
+}
+
+/// State of a puzzle being played: what the player entered on top of the
+/// givens, their pencil marks, and where the cursor is.
+pub struct Game {
+    givens: [[u8; 9]; 9],
+    // the givens and the player's entries, 0 when empty
+    values: [[u8; 9]; 9],
+    // pencil marks of each square, bit 0 meaning the digit 1
+    marks: [[u16; 9]; 9],
+    // entries the last check found different from the solution
+    wrong: [[bool; 9]; 9],
+    solution: Option<[[u8; 9]; 9]>,
+    cursor: (usize, usize),
+    // digits toggle pencil marks instead of entering values
+    pencil: bool,
+    status: String,
+}
+
+impl Game {
+    pub fn new(puzzle: &SudokuBoard) -> Game {
+        let givens = puzzle.to_array();
+        let solution = fill_in(puzzle).ok().map(|(solved, _)| solved.to_array());
+        Game {
+            givens,
+            values: givens,
+            marks: [[0; 9]; 9],
+            wrong: [[false; 9]; 9],
+            solution,
+            cursor: (0, 0),
+            pencil: false,
+            status: String::new(),
+        }
+    }
+
+    /// React to a key.  Returns false when the player quits.
+    pub fn handle(&mut self, key: Key) -> bool {
+        let (row, col) = self.cursor;
+        let given = self.givens[row][col] != 0;
+        self.status.clear();
+        match key {
+            Key::Up => self.cursor.0 = (row + 8) % 9,
+            Key::Down => self.cursor.0 = (row + 1) % 9,
+            Key::Left => self.cursor.1 = (col + 8) % 9,
+            Key::Right => self.cursor.1 = (col + 1) % 9,
+            Key::Digit(_) | Key::Clear if given => self.status = "That square is a given".into(),
+            Key::Digit(d) if self.pencil => self.marks[row][col] ^= 1 << (d - 1),
+            Key::Digit(d) => {
+                self.values[row][col] = d;
+                self.wrong[row][col] = false;
+                if self.values == self.solution.unwrap_or([[10; 9]; 9]) {
+                    self.status = "Solved!".into();
+                }
+            }
+            Key::Clear => {
+                self.values[row][col] = 0;
+                self.marks[row][col] = 0;
+                self.wrong[row][col] = false;
+            }
+            Key::Pencil => self.pencil = !self.pencil,
+            Key::Hint => self.status = self.hint(),
+            Key::Check => self.check(),
+            Key::Solve => match self.solution {
+                Some(solution) => {
+                    self.values = solution;
+                    self.wrong = [[false; 9]; 9];
+                    self.status = "Solved by the solver".into();
+                }
+                None => self.status = "The puzzle has no solution".into(),
+            },
+            Key::Quit => return false,
+            Key::Other => self.status = HELP.into(),
+        }
+        true
+    }
+
+    /// The next logical step from the entries and pencil marks so far.
+    fn hint(&self) -> String {
+        let mut marks = String::new();
+        for (values, marks_row) in self.values.iter().zip(&self.marks) {
+            for (value, mark) in values.iter().zip(marks_row) {
+                let cell: String = match (value, mark) {
+                    (0, 0) => "123456789".into(),
+                    (0, mark) => (1..=9)
+                        .filter(|d| mark & (1 << (d - 1)) != 0)
+                        .map(|d| d.to_string())
+                        .collect(),
+                    (value, _) => value.to_string(),
+                };
+                marks += &cell;
+                marks.push(' ');
+            }
+        }
+        match SudokuBoard::parse_pencil_marks(&marks) {
+            Ok(board) => match board.next_step() {
+                Some(step) => describe_step(&step).replace("\n ", ";"),
+                None => "No logical step found".into(),
+            },
+            Err(_) => "The entries and pencil marks contradict each other".into(),
+        }
+    }
+
+    /// Mark the entries that differ from the solution.
+    fn check(&mut self) {
+        let solution = match self.solution {
+            Some(solution) => solution,
+            None => {
+                self.status = "The puzzle has no solution".into();
+                return;
+            }
+        };
+        let mut wrong = 0;
+        let rows = self.wrong.iter_mut().zip(&self.values).zip(&solution);
+        for ((wrong_row, values), solution_row) in rows {
+            for ((w, value), answer) in wrong_row.iter_mut().zip(values).zip(solution_row) {
+                *w = *value != 0 && value != answer;
+                wrong += *w as usize;
+            }
+        }
+        self.status = match wrong {
+            0 => "No mistakes so far".into(),
+            n => format!("{} entries are wrong", n),
+        };
+    }
+
+    /// Draw the whole screen.  Lines end in `\r\n` as the terminal is raw.
+    pub fn render(&self) -> String {
+        let mut lines = vec![" ┌───────┬───────┬───────┐".to_string()];
+        for r in 0..9 {
+            if r == 3 || r == 6 {
+                lines.push(" ├───────┼───────┼───────┤".to_string());
+            }
+            let mut line = " │".to_string();
+            for c in 0..9 {
+                let text = match self.values[r][c] {
+                    0 => "·".to_string(),
+                    v => v.to_string(),
+                };
+                let style = if self.givens[r][c] != 0 {
+                    "\x1b[1m"
+                } else if self.wrong[r][c] {
+                    "\x1b[31m"
+                } else {
+                    "\x1b[34m"
+                };
+                let cursor = if self.cursor == (r, c) { "\x1b[7m" } else { "" };
+                line += &format!(" {}{}{}\x1b[0m", style, cursor, text);
+                if c % 3 == 2 {
+                    line += " │";
+                }
+            }
+            lines.push(line);
+        }
+        lines.push(" └───────┴───────┴───────┘".to_string());
+
+        let (row, col) = self.cursor;
+        let marks: Vec<String> = (1..=9)
+            .filter(|d| self.marks[row][col] & (1 << (d - 1)) != 0)
+            .map(|d| d.to_string())
+            .collect();
+        let mode = if self.pencil {
+            "pencil marks"
+        } else {
+            "digits"
+        };
+        lines.push(format!(
+            " r{}c{}  entering {}  marks: {}",
+            row + 1,
+            col + 1,
+            mode,
+            marks.join(" ")
+        ));
+        lines.push(format!(" {}", self.status));
+        lines.push(format!(" {}", HELP));
+        format!("\x1b[H\x1b[2J{}\r\n", lines.join("\r\n"))
+    }
+}
+
+/// Turn the bytes of a key press into a key.  Arrow keys arrive as
+/// `ESC [ A` through `ESC [ D`.
+fn read_key(input: &mut impl Read) -> io::Result<Key> {
+    let mut byte = [0u8];
+    input.read_exact(&mut byte)?;
+    Ok(match byte[0] {
+        0x1b => {
+            input.read_exact(&mut byte)?;
+            if byte[0] != b'[' {
+                return Ok(Key::Other);
+            }
+            input.read_exact(&mut byte)?;
+            match byte[0] {
+                b'A' => Key::Up,
+                b'B' => Key::Down,
+                b'C' => Key::Right,
+                b'D' => Key::Left,
+                _ => Key::Other,
+            }
+        }
+        b'k' => Key::Up,
+        b'j' => Key::Down,
+        b'h' => Key::Left,
+        b'l' => Key::Right,
+        d @ b'1'..=b'9' => Key::Digit(d - b'0'),
+        b'0' | b' ' | 0x7f | 0x08 => Key::Clear,
+        b'p' => Key::Pencil,
+        b'?' => Key::Hint,
+        b'c' => Key::Check,
+        b's' => Key::Solve,
+        // ctrl-c does not send a signal in raw mode
+        b'q' | 0x03 => Key::Quit,
+        _ => Key::Other,
+    })
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Puts the terminal in raw mode on the alternate screen, restoring it when
+/// dropped.
+struct RawTerminal {
+    saved: String,
+}
+
+impl RawTerminal {
+    fn enable() -> io::Result<RawTerminal> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        Ok(RawTerminal { saved })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+pub fn run(options: &InputOptions) -> Result<(), Failure> {
+    let puzzle = match &options.input {
+        Some(file_name) => {
+            let contents = read_input(Some(file_name))?;
+            let puzzle = read_puzzles(&contents)
+                .into_iter()
+                .next()
+                .ok_or_else(|| Failure::InvalidInput(format!("no puzzle in {}", file_name)))?;
+            puzzle
+                .board()
+                .map_err(|e| Failure::InvalidInput(format!("invalid puzzle: {}", e)))?
+        }
+        None => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            Generator::new(GeneratorOptions {
+                seed: now.as_nanos() as u64,
+                ..Default::default()
+            })
+            .generate()
+            .expect("a puzzle of any difficulty is made on the first attempt")
+        }
+    };
+
+    let mut game = Game::new(&puzzle);
+    let terminal_error =
+        |e: io::Error| Failure::Internal(format!("could not use the terminal: {}", e));
+    let _terminal = RawTerminal::enable().map_err(terminal_error)?;
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    loop {
+        write!(stdout, "{}", game.render()).map_err(terminal_error)?;
+        stdout.flush().map_err(terminal_error)?;
+        if !game.handle(read_key(&mut stdin).map_err(terminal_error)?) {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_key, Game, Key};
+    use sudoku::SudokuBoard;
+
+    fn game() -> Game {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        Game::new(&SudokuBoard::fill_board(s).unwrap())
+    }
+
+    #[test]
+    fn test_read_key() {
+        let key = |bytes: &[u8]| read_key(&mut &bytes[..]).unwrap();
+        assert_eq!(key(b"\x1b[A"), Key::Up);
+        assert_eq!(key(b"\x1b[D"), Key::Left);
+        assert_eq!(key(b"7"), Key::Digit(7));
+        assert_eq!(key(b"\x7f"), Key::Clear);
+        assert_eq!(key(b"q"), Key::Quit);
+    }
+
+    #[test]
+    fn test_game() {
+        let mut game = game();
+        // r1c1 is a given
+        game.handle(Key::Digit(5));
+        assert_eq!(game.values[0][0], 4);
+        game.handle(Key::Right);
+        game.handle(Key::Digit(9));
+        game.handle(Key::Check);
+        assert_eq!(game.wrong[0][1], game.solution.unwrap()[0][1] != 9);
+        game.handle(Key::Pencil);
+        game.handle(Key::Right);
+        game.handle(Key::Digit(3));
+        assert_eq!(game.marks[0][2], 0b100);
+        assert!(game.render().contains("marks: 3"));
+        game.handle(Key::Hint);
+        assert!(!game.status.is_empty());
+        game.handle(Key::Solve);
+        assert_eq!(Some(game.values), game.solution);
+        assert!(!game.handle(Key::Quit));
+    }
+}
@@ -0,0 +1,192 @@
+use super::output::{Field, ReportWriter};
+use super::solve::{read_input, read_puzzles, Puzzle};
+use super::{cell_name, house_name, Failure, Format, ReportOptions};
+use std::io::{self, Write};
+use sudoku::{HouseId, SudokuBoard, SudokuError};
+
+/// Read a puzzle, checking that it has 81 squares and no repeated givens.
+/// Returns what is wrong with it otherwise.
+pub fn read_givens(puzzle: &Puzzle) -> Result<SudokuBoard, Vec<String>> {
+    SudokuBoard::parse_grid(&puzzle.grid).map_err(|e| vec![describe_error(&e)])
+}
+
+/// Say what is wrong with the givens, naming the house a repeated digit is
+/// given in.
+pub fn describe_error(e: &SudokuError) -> String {
+    let SudokuError::ConflictingGivens {
+        digit,
+        first,
+        second,
+    } = e
+    else {
+        return e.to_string();
+    };
+    let index = |(row, col): (usize, usize)| (row - 1) * 9 + col - 1;
+    let house = HouseId::all().find(|house| {
+        let cells = house.cells();
+        cells.contains(&index(*first)) && cells.contains(&index(*second))
+    });
+    match house {
+        Some(house) => format!(
+            "{} is given more than once in {} ({}, {})",
+            digit,
+            house_name(house),
+            cell_name(first.0, first.1),
+            cell_name(second.0, second.1)
+        ),
+        None => e.to_string(),
+    }
+}
+
+/// Find what is wrong with a puzzle.  An empty list means the puzzle is
+/// well formed and has exactly one solution.  The error is what kept the
+/// puzzle from being read, repeated givens among it.
+pub fn validate_puzzle(puzzle: &Puzzle) -> Result<Vec<String>, Vec<String>> {
+    read_givens(puzzle).map(|board| check_solutions(&board))
+}
+
+/// What is wrong with a board that was read: an empty list when it has
+/// exactly one solution.
+pub fn check_solutions(board: &SudokuBoard) -> Vec<String> {
+    match board.count_solutions(2) {
+        0 => vec!["no solution".to_string()],
+        1 => Vec::new(),
+        _ => vec!["more than one solution".to_string()],
+    }
+}
+
+/// Validate every puzzle in `contents`, writing a record each in `format`.
+/// Fails with `InvalidInput` when any puzzle could not be read, and with
+/// `Unsolvable` when the rest were read but some do not have exactly one
+/// solution.
+pub fn validate_all(contents: &str, format: Format, out: &mut dyn Write) -> Result<(), Failure> {
+    let output_error =
+        |e: io::Error| Failure::Internal(format!("could not write standard output: {}", e));
+    let puzzles = read_puzzles(contents);
+    let mut writer = ReportWriter::new(out, format);
+    let (mut failed, mut unread) = (0, 0);
+    for (i, puzzle) in puzzles.iter().enumerate() {
+        let label = match &puzzle.name {
+            Some(name) => name.trim().to_string(),
+            None => format!("puzzle {}", i + 1),
+        };
+        let problems = validate_puzzle(puzzle).unwrap_or_else(|problems| {
+            unread += 1;
+            problems
+        });
+        let text = if problems.is_empty() {
+            format!("{}: ok", label)
+        } else {
+            failed += 1;
+            let lines: Vec<String> = problems.iter().map(|p| format!("  {}", p)).collect();
+            format!("{}: invalid\n{}", label, lines.join("\n"))
+        };
+        let fields = [
+            ("valid", Field::raw(problems.is_empty())),
+            ("problems", Field::Text(problems.join("; "))),
+        ];
+        writer
+            .record(i + 1, puzzle.name.as_deref(), &text, &fields)
+            .map_err(output_error)?;
+    }
+    writer.flush().map_err(output_error)?;
+    Failure::check_unread(unread, puzzles.len())?;
+    if failed > 0 {
+        return Err(Failure::Unsolvable(format!(
+            "{} of {} puzzles are invalid",
+            failed,
+            puzzles.len()
+        )));
+    }
+    Ok(())
+}
+
+pub fn run(options: &ReportOptions) -> Result<(), Failure> {
+    let file_contents = read_input(options.input.as_deref())?;
+    validate_all(&file_contents, options.format, &mut io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_all, validate_puzzle};
+    use crate::cli::solve::Puzzle;
+    use crate::cli::Format;
+
+    fn puzzle(grid: &str) -> Puzzle {
+        Puzzle {
+            name: None,
+            grid: grid.to_string(),
+            line: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_puzzle() {
+        let unique = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        assert!(validate_puzzle(&puzzle(unique)).unwrap().is_empty());
+        assert_eq!(
+            validate_puzzle(&puzzle(&unique[..80])).unwrap_err(),
+            vec!["expected 81 squares, found 80"]
+        );
+        assert_eq!(
+            validate_puzzle(&puzzle(&unique.replacen("4", "2", 1))).unwrap_err(),
+            vec!["2 is given more than once in column 1 (r1c1, r9c1)"]
+        );
+        assert_eq!(
+            validate_puzzle(&puzzle(&unique.replacen("-", "x", 1))).unwrap_err(),
+            vec!["unexpected character 'x'"]
+        );
+        assert_eq!(
+            validate_puzzle(&puzzle(&unique.replacen("---", "--4", 1))).unwrap_err(),
+            vec!["4 is given more than once in row 1 (r1c1, r1c4)"]
+        );
+        assert_eq!(
+            validate_puzzle(&puzzle(&"-".repeat(81))).unwrap(),
+            vec!["more than one solution"]
+        );
+    }
+
+    #[test]
+    fn test_validate_all_exit_code() {
+        // repeated givens cannot be read, like any other malformed puzzle
+        let repeated = format!("11{}\n", "-".repeat(79));
+        let mut out = Vec::new();
+        let failure = validate_all(&repeated, Format::Grid, &mut out).unwrap_err();
+        assert_eq!(failure.exit_code(), 2);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("puzzle 1: invalid\n  1 is given more than once in row 1"));
+
+        let mut out = Vec::new();
+        let failure = validate_all(&"-".repeat(81), Format::Grid, &mut out).unwrap_err();
+        assert_eq!(failure.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_validate_all_format() {
+        let contents = format!("Grid 01\n{}\n", "-".repeat(81));
+        let mut out = Vec::new();
+        validate_all(&contents, Format::Json, &mut out).unwrap_err();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"index\":1,\"name\":\"Grid 01\",\"valid\":false,\"problems\":\"more than one solution\"}\n"
+        );
+
+        let mut out = Vec::new();
+        validate_all(&contents, Format::Csv, &mut out).unwrap_err();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "index,name,valid,problems\n1,Grid 01,false,more than one solution\n"
+        );
+    }
+}
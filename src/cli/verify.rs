@@ -0,0 +1,203 @@
+use super::output::{Field, ReportWriter};
+use super::solve::{fill_in, read_input};
+use super::{cell_name, Failure, ReportOptions};
+use std::io;
+use sudoku::SudokuBoard;
+
+/// A puzzle and the solution it is expected to have.
+#[derive(Debug, PartialEq)]
+pub struct Pair {
+    // Line of the input the puzzle is on, counting from 1
+    pub line: usize,
+    pub puzzle: String,
+    pub expected: String,
+}
+
+/// Read puzzle and solution pairs, either as two columns separated by
+/// whitespace or a comma, or as a puzzle line followed by its solution
+/// line.  Blank lines and lines starting with `#` are skipped.
+pub fn read_pairs(contents: &str) -> Result<Vec<Pair>, String> {
+    let mut pairs = Vec::new();
+    // a puzzle waiting for its solution on the next line
+    let mut pending: Option<(usize, &str)> = None;
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|f| !f.is_empty())
+            .collect();
+        match (&fields[..], pending.take()) {
+            (&[puzzle, expected], None) => pairs.push(Pair {
+                line: i + 1,
+                puzzle: puzzle.to_string(),
+                expected: expected.to_string(),
+            }),
+            (&[puzzle], None) => pending = Some((i + 1, puzzle)),
+            (&[expected], Some((line, puzzle))) => pairs.push(Pair {
+                line,
+                puzzle: puzzle.to_string(),
+                expected: expected.to_string(),
+            }),
+            (_, Some((line, _))) => {
+                return Err(format!("the puzzle on line {} has no solution line", line))
+            }
+            _ => {
+                return Err(format!(
+                    "line {} has {} fields, expected a puzzle and its solution",
+                    i + 1,
+                    fields.len()
+                ))
+            }
+        }
+    }
+    match pending {
+        Some((line, _)) => Err(format!("the puzzle on line {} has no solution line", line)),
+        None => Ok(pairs),
+    }
+}
+
+/// The rows of the expected solution beside the rows the solver found, with
+/// the squares that differ named after each row that has them.
+pub fn diff(expected: &str, solved: &str) -> Vec<String> {
+    let mut lines = vec![format!("{:<9}  {}", "expected", "solved")];
+    for row in 0..9 {
+        let range = row * 9..row * 9 + 9;
+        let (want, got) = (&expected[range.clone()], &solved[range]);
+        let squares: Vec<String> = want
+            .chars()
+            .zip(got.chars())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(col, _)| cell_name(row + 1, col + 1))
+            .collect();
+        if squares.is_empty() {
+            lines.push(format!("{}  {}", want, got));
+        } else {
+            lines.push(format!("{}  {}  <- {}", want, got, squares.join(", ")));
+        }
+    }
+    lines
+}
+
+/// Solve the puzzle of a pair and compare it with the expected solution.
+/// Returns what went wrong, nothing when the solution was reproduced.
+pub fn verify_pair(pair: &Pair) -> Vec<String> {
+    let puzzle = match SudokuBoard::parse_grid(&pair.puzzle) {
+        Ok(puzzle) => puzzle,
+        Err(e) => return vec![format!("invalid puzzle: {}", e)],
+    };
+    // read as is, a wrong solution is what we are looking for
+    let expected = &pair.expected;
+    if expected.len() != 81 || !expected.chars().all(|c| ('1'..='9').contains(&c)) {
+        return vec!["the expected solution is not 81 digits from 1 to 9".to_string()];
+    }
+    let solved = match fill_in(&puzzle) {
+        Ok((solved, _)) => solved,
+        Err(e) => return vec![format!("the solver found no solution: {}", e)],
+    };
+    let solved = solved.print_board();
+    if solved == *expected {
+        return Vec::new();
+    }
+    let mut problems = diff(expected, &solved);
+    if puzzle.count_solutions(2) > 1 {
+        problems.push("the puzzle has more than one solution".to_string());
+    }
+    problems
+}
+
+pub fn run(options: &ReportOptions) -> Result<(), Failure> {
+    let contents = read_input(options.input.as_deref())?;
+    let pairs = read_pairs(&contents).map_err(Failure::InvalidInput)?;
+    let output_error =
+        |e: io::Error| Failure::Internal(format!("could not write standard output: {}", e));
+    let mut writer = ReportWriter::new(io::stdout().lock(), options.format);
+
+    let mut failed = 0;
+    for (i, pair) in pairs.iter().enumerate() {
+        let problems = verify_pair(pair);
+        let text = if problems.is_empty() {
+            format!("line {}: ok", pair.line)
+        } else {
+            failed += 1;
+            let lines: Vec<String> = problems.iter().map(|p| format!("  {}", p)).collect();
+            format!("line {}: mismatch\n{}", pair.line, lines.join("\n"))
+        };
+        let fields = [
+            ("line", Field::raw(pair.line)),
+            ("ok", Field::raw(problems.is_empty())),
+            ("problems", Field::Text(problems.join("; "))),
+        ];
+        writer
+            .record(i + 1, None, &text, &fields)
+            .map_err(output_error)?;
+    }
+    writer.flush().map_err(output_error)?;
+    if failed > 0 {
+        return Err(Failure::Unsolvable(format!(
+            "{} of {} solutions were not reproduced",
+            failed,
+            pairs.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_pairs, verify_pair, Pair};
+
+    const PUZZLE: &str =
+        "4....8.......91.8..865.2.3..2.4..9...1.2....6367.59........5...7..8...242..93..7.";
+    const SOLUTION: &str =
+        "492378165573691482186542739825463917914287356367159248641725893739816524258934671";
+
+    #[test]
+    fn test_read_pairs() {
+        let contents = format!(
+            "# archive\n{0} {1}\n\n{0},{1}\n{0}\n{1}\n",
+            PUZZLE, SOLUTION
+        );
+        let pairs = read_pairs(&contents).unwrap();
+        let lines: Vec<usize> = pairs.iter().map(|p| p.line).collect();
+        assert_eq!(lines, vec![2, 4, 5]);
+        assert!(pairs
+            .iter()
+            .all(|p| p.puzzle == PUZZLE && p.expected == SOLUTION));
+
+        assert_eq!(
+            read_pairs(&format!("{}\n", PUZZLE)),
+            Err("the puzzle on line 1 has no solution line".to_string())
+        );
+        assert_eq!(
+            read_pairs(&format!("{0}\n{0} {1}\n", PUZZLE, SOLUTION)),
+            Err("the puzzle on line 1 has no solution line".to_string())
+        );
+        assert!(read_pairs("a b c\n").is_err());
+    }
+
+    #[test]
+    fn test_verify_pair() {
+        let pair = |expected: &str| Pair {
+            line: 1,
+            puzzle: PUZZLE.to_string(),
+            expected: expected.to_string(),
+        };
+        assert!(verify_pair(&pair(SOLUTION)).is_empty());
+
+        // the last two squares of the first row swapped
+        let wrong = SOLUTION.replacen("165", "156", 1);
+        let problems = verify_pair(&pair(&wrong));
+        assert_eq!(problems[0], "expected   solved");
+        assert_eq!(problems[1], "492378156  492378165  <- r1c8, r1c9");
+        assert_eq!(problems[2], "573691482  573691482");
+        assert_eq!(problems.len(), 10);
+
+        let not_digits = vec!["the expected solution is not 81 digits from 1 to 9"];
+        assert_eq!(verify_pair(&pair(&SOLUTION[..80])), not_digits);
+        assert_eq!(verify_pair(&pair(PUZZLE)), not_digits);
+    }
+}
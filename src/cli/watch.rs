@@ -0,0 +1,194 @@
+use super::output::BoardWriter;
+use super::progress::Progress;
+use super::solve::{logical_position, read_puzzles, solve_all};
+use super::{Failure, WatchOptions};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Extension of the files the solutions are written to.  These files are
+/// never solved themselves.
+const SOLVED_EXTENSION: &str = "solved";
+
+/// Remembers when each file in the directory was last modified, to find the
+/// files that are new or changed since the last look.
+pub struct Watcher {
+    dir: PathBuf,
+    seen: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    pub fn new(dir: &Path) -> Watcher {
+        Watcher {
+            dir: dir.to_path_buf(),
+            seen: HashMap::new(),
+        }
+    }
+
+    /// The puzzle files added or modified since the last call, in name order.
+    pub fn changed_files(&mut self) -> io::Result<Vec<PathBuf>> {
+        let mut changed = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() || path.extension().is_some_and(|e| e == SOLVED_EXTENSION) {
+                continue;
+            }
+            let modified = metadata.modified()?;
+            if self.seen.insert(path.clone(), modified) != Some(modified) {
+                changed.push(path);
+            }
+        }
+        changed.sort();
+        Ok(changed)
+    }
+}
+
+/// Solve every puzzle of a file, writing the solutions to `out`.  Returns
+/// how many puzzles there were and how many failed.
+pub fn solve_file(
+    path: &Path,
+    options: &WatchOptions,
+    out: &mut dyn Write,
+) -> Result<(usize, usize), Failure> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| Failure::InvalidInput(format!("could not read {}: {}", path.display(), e)))?;
+    let puzzles = read_puzzles(&contents);
+    let outcomes = solve_all(
+        &puzzles,
+        1,
+        false,
+        &Progress::new(puzzles.len(), Some(false)),
+    );
+    let output_error =
+        |e: io::Error| Failure::Internal(format!("could not write the solutions: {}", e));
+    let mut out = BoardWriter::new(out, options.format).with_pencil_marks(options.pencil_marks);
+    let mut failed = 0;
+    for (puzzle, outcome) in puzzles.iter().zip(outcomes) {
+        let name = puzzle.name.as_deref().map(str::trim);
+        match outcome.result {
+            Ok((board, _)) if options.pencil_marks => {
+                let board = logical_position(&puzzle.grid).unwrap_or(board);
+                out.write(name, &board).map_err(output_error)?
+            }
+            Ok((board, _)) => out.write(name, &board).map_err(output_error)?,
+            Err(e) => {
+                failed += 1;
+                out.note(&format!("error: {}", e)).map_err(output_error)?;
+            }
+        }
+    }
+    out.flush().map_err(output_error)?;
+    Ok((puzzles.len(), failed))
+}
+
+/// Solve a changed file, writing the solutions beside it or to standard
+/// output, and report what happened.
+fn process(path: &Path, options: &WatchOptions) -> Result<(), Failure> {
+    let (count, failed) = if options.stdout {
+        let mut out = io::stdout().lock();
+        solve_file(path, options, &mut out)?
+    } else {
+        let mut solved = path.as_os_str().to_owned();
+        solved.push(".");
+        solved.push(SOLVED_EXTENSION);
+        let mut out = Vec::new();
+        let result = solve_file(path, options, &mut out)?;
+        fs::write(&solved, out).map_err(|e| {
+            Failure::Internal(format!(
+                "could not write {}: {}",
+                Path::new(&solved).display(),
+                e
+            ))
+        })?;
+        result
+    };
+    eprintln!(
+        "{}: solved {} of {} puzzles",
+        path.display(),
+        count - failed,
+        count
+    );
+    Ok(())
+}
+
+pub fn run(options: &WatchOptions) -> Result<(), Failure> {
+    let mut watcher = Watcher::new(Path::new(&options.dir));
+    let read_error =
+        |e: io::Error| Failure::InvalidInput(format!("could not read {}: {}", options.dir, e));
+    loop {
+        for path in watcher.changed_files().map_err(read_error)? {
+            // a file that cannot be read or solved should not stop the watch
+            if let Err(failure) = process(&path, options) {
+                eprintln!("{}: {}", path.display(), failure.message());
+            }
+        }
+        if options.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(options.interval_ms));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_file, Watcher};
+    use crate::cli::{Failure, Format, WatchOptions};
+    use std::fs;
+
+    #[test]
+    fn test_changed_files() {
+        let dir = std::env::temp_dir().join(format!("sudoku_watch_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let puzzle = dir.join("a.txt");
+        fs::write(&puzzle, "123456789\n").unwrap();
+        fs::write(dir.join("a.txt.solved"), "").unwrap();
+
+        let mut watcher = Watcher::new(&dir);
+        assert_eq!(watcher.changed_files().unwrap(), vec![puzzle.clone()]);
+        assert!(watcher.changed_files().unwrap().is_empty());
+        let file = fs::File::options().write(true).open(&puzzle).unwrap();
+        file.set_modified(std::time::SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(watcher.changed_files().unwrap(), vec![puzzle]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_solve_file() {
+        let options = WatchOptions {
+            dir: ".".to_string(),
+            interval_ms: 0,
+            once: true,
+            stdout: true,
+            format: Format::Line,
+            pencil_marks: false,
+        };
+        let dir = std::env::temp_dir().join(format!("sudoku_watch_file_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let missing = dir.join("missing.txt");
+        match solve_file(&missing, &options, &mut Vec::new()) {
+            Err(Failure::InvalidInput(message)) => {
+                assert!(message.starts_with(&format!("could not read {}:", missing.display())))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // a name that is not UTF-8 is still read from the file
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let path = dir.join(std::ffi::OsStr::from_bytes(b"puzzle\xff.txt"));
+            fs::write(&path, "4----8---".repeat(9)).unwrap();
+            let mut out = Vec::new();
+            let (count, _) = solve_file(&path, &options, &mut out).unwrap();
+            assert_eq!(count, 1);
+            assert!(!out.is_empty());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{char_from32, BoxValue, Node, SudokuBoard};
+
+const GIVEN: &str = "\x1b[1;34m";
+const SOLVED: &str = "\x1b[32m";
+const ELIMINATED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Colors are used unless the `NO_COLOR` environment variable is set to a
+/// non-empty value, see <https://no-color.org>.
+pub fn color_enabled() -> bool {
+    std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+fn paint(text: &str, color: &str, enabled: bool) -> String {
+    if enabled {
+        format!("{}{}{}", color, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+fn known_text(node: &Node, enabled: bool) -> Option<String> {
+    match node.value {
+        BoxValue::Known(v) => {
+            let text = char_from32(v).unwrap_or('?').to_string();
+            let color = if node.given { GIVEN } else { SOLVED };
+            Some(paint(&text, color, enabled))
+        }
+        BoxValue::Unknown(_) => None,
+    }
+}
+
+impl SudokuBoard {
+    /// Draw the board like `pretty_print` with the givens and the squares
+    /// filled in since in different colors.
+    pub fn colored_print(&self) -> String {
+        self.colored_print_with(color_enabled())
+    }
+
+    /// Draw the candidates left in every square.  Each square is 9 characters
+    /// wide with candidate `d` in position `d`.  Candidates that were still
+    /// possible in `previous` but have been eliminated since are shown in red.
+    pub fn colored_pencil_marks(&self, previous: Option<&SudokuBoard>) -> String {
+        self.colored_pencil_marks_with(previous, color_enabled())
+    }
+
+    fn colored_print_with(&self, enabled: bool) -> String {
+        self.pretty_print_with(|node| known_text(node, enabled).unwrap_or_else(|| " ".to_string()))
+    }
+
+    fn colored_pencil_marks_with(&self, previous: Option<&SudokuBoard>, enabled: bool) -> String {
+        let mut lines = Vec::new();
+        for (r, row) in self.board.iter().enumerate() {
+            if r == 3 || r == 6 {
+                lines.push(vec!["-".repeat(33); 3].join("+"));
+            }
+            let cells: Vec<String> = row
+                .iter()
+                .map(|node| {
+                    let before = previous.map(|p| &p.board[node.row - 1][node.col - 1].value);
+                    pencil_cell(node, before, enabled)
+                })
+                .collect();
+            let boxes: Vec<String> = cells
+                .chunks(3)
+                .map(|chunk| format!(" {} ", chunk.join("  ")))
+                .collect();
+            lines.push(boxes.join("|"));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Draw one square of the pencil-mark view, 9 characters wide.
+fn pencil_cell(node: &Node, before: Option<&BoxValue>, enabled: bool) -> String {
+    if let Some(text) = known_text(node, enabled) {
+        return format!("    {}    ", text);
+    }
+    let mut cell = String::new();
+    for d in 1..=9 {
+        let now = matches!(&node.value, BoxValue::Unknown(s) if s.contains(&d));
+        let was = matches!(before, Some(BoxValue::Unknown(s)) if s.contains(&d));
+        let text = char_from32(d).unwrap_or('?').to_string();
+        if now {
+            cell.push_str(&text);
+        } else if was {
+            cell.push_str(&paint(&text, ELIMINATED, enabled));
+        } else {
+            cell.push('.');
+        }
+    }
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    fn puzzle() -> SudokuBoard {
+        let s = concat!(
+            "500300600",
+            "004001750",
+            "000059100",
+            "403200070",
+            "006000000",
+            "000000904",
+            "700090315",
+            "035000806",
+            "619080000"
+        );
+        SudokuBoard::parse_strict(s).unwrap()
+    }
+
+    #[test]
+    fn test_colored_print() {
+        let mut board = puzzle();
+        board.solve_first().unwrap();
+        let plain = board.colored_print_with(false);
+        assert_eq!(plain, board.pretty_print());
+
+        let colored = board.colored_print_with(true);
+        let first_row = colored.lines().nth(1).unwrap();
+        assert!(first_row.starts_with("┃ \x1b[1;34m5\x1b[0m │ \x1b[32m8\x1b[0m │"));
+    }
+
+    #[test]
+    fn test_colored_pencil_marks() {
+        let previous = puzzle();
+        let mut board = previous.clone();
+        board.solve_first().unwrap();
+        let plain = previous.colored_pencil_marks_with(None, false);
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert!(lines[0].starts_with("     5      .2....789  12....78. |"));
+        assert_eq!(lines[3], vec!["-".repeat(33); 3].join("+"));
+        assert!(!plain.contains('\x1b'));
+
+        let colored = board.colored_pencil_marks_with(Some(&previous), true);
+        assert!(colored.contains("\x1b[32m8\x1b[0m"));
+        assert!(!colored.contains("\x1b[31m"));
+
+        let mut eliminated = previous.clone();
+        eliminated.eliminate(at(1, 2), 2).unwrap();
+        let colored = eliminated.colored_pencil_marks_with(Some(&previous), true);
+        let first_row = colored.lines().next().unwrap();
+        assert!(first_row.contains(" .\x1b[31m2\x1b[0m....789 "));
+        assert_eq!(colored.matches("\x1b[31m").count(), 1);
+    }
+}
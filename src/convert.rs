@@ -0,0 +1,120 @@
+use crate::parse::is_blank;
+use crate::{char_from32, i32_from_char, BoxValue, SudokuBoard, SudokuError};
+
+impl TryFrom<[[u8; 9]; 9]> for SudokuBoard {
+    type Error = SudokuError;
+
+    /// Initialize the board from rows of numeric values, 0 meaning unknown.
+    fn try_from(grid: [[u8; 9]; 9]) -> Result<SudokuBoard, SudokuError> {
+        let mut board = SudokuBoard::new();
+        for (r, row) in grid.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                match value {
+                    0 => (),
+                    1..=9 => board.mark_as_given(r + 1, c + 1, *value as i32)?,
+                    _ => return Err(SudokuError::InvalidRange),
+                }
+            }
+        }
+        Ok(board)
+    }
+}
+
+impl TryFrom<&[u8; 81]> for SudokuBoard {
+    type Error = SudokuError;
+
+    /// Initialize the board from 81 ASCII characters, top to bottom left to
+    /// right.  Unknown squares are marked with `0`, `.`, `-`, or `_`.
+    fn try_from(cells: &[u8; 81]) -> Result<SudokuBoard, SudokuError> {
+        let mut board = SudokuBoard::new();
+        for (i, b) in cells.iter().enumerate() {
+            let c = *b as char;
+            if is_blank(c) {
+                continue;
+            }
+            match i32_from_char(c) {
+                Some(v) => board.mark_as_given(i / 9 + 1, i % 9 + 1, v)?,
+                None => return Err(SudokuError::InvalidCharacter(c)),
+            }
+        }
+        Ok(board)
+    }
+}
+
+impl SudokuBoard {
+    /// Get the board as 81 ASCII characters, the same text `print_board`
+    /// returns without allocating a `String`.
+    pub fn as_bytes(&self) -> [u8; 81] {
+        let mut cells = [b'-'; 81];
+        for (cell, node) in cells.iter_mut().zip(self.board.iter().flatten()) {
+            if let BoxValue::Known(v) = node.value {
+                *cell = char_from32(v).unwrap_or('?') as u8;
+            }
+        }
+        cells
+    }
+
+    /// Get the board as rows of numeric values, 0 meaning unknown.
+    pub fn to_array(&self) -> [[u8; 9]; 9] {
+        let mut grid = [[0u8; 9]; 9];
+        for node in self.board.iter().flatten() {
+            if let BoxValue::Known(v) = node.value {
+                grid[node.row - 1][node.col - 1] = v as u8;
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SudokuBoard;
+    use crate::SudokuError;
+
+    #[test]
+    fn test_array_round_trip() {
+        let mut grid = [[0u8; 9]; 9];
+        grid[0][0] = 5;
+        grid[4][7] = 3;
+        grid[8][8] = 9;
+        let board = SudokuBoard::try_from(grid).unwrap();
+        assert_eq!(&board.print_board()[..9], "5--------");
+        assert_eq!(board.to_array(), grid);
+    }
+
+    #[test]
+    fn test_bytes_view() {
+        let s = concat!(
+            "4....8...",
+            "----91-8-",
+            "_865_2_3_",
+            "020400900",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        let cells: &[u8; 81] = s.as_bytes().try_into().unwrap();
+        let board = SudokuBoard::try_from(cells).unwrap();
+        assert_eq!(&board.as_bytes()[..18], b"4----8-------91-8-");
+        assert_eq!(board.as_bytes(), board.print_board().as_bytes());
+
+        let mut cells = *cells;
+        cells[5] = b'x';
+        assert!(matches!(
+            SudokuBoard::try_from(&cells),
+            Err(SudokuError::InvalidCharacter('x'))
+        ));
+    }
+
+    #[test]
+    fn test_array_invalid() {
+        let mut grid = [[0u8; 9]; 9];
+        grid[2][2] = 10;
+        assert!(matches!(
+            SudokuBoard::try_from(grid),
+            Err(SudokuError::InvalidRange)
+        ));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::parse::is_blank;
+use crate::{i32_from_char, Json, SudokuBoard, SudokuError, SudokuResult};
+use std::fmt;
+
+/// The ways of writing puzzles `parse_any` tells apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputFormat {
+    // One puzzle of 81 squares on a single line
+    Line,
+    // One puzzle over several lines, with or without a frame
+    Grid,
+    // SadMan Sudoku `.sdk`: `#` metadata lines, or a `[Puzzle]` section,
+    // then the puzzle as 9 lines
+    Sdk,
+    // `.sdm`: one puzzle of 81 squares per line
+    Sdm,
+    // JSON objects with the puzzle as a `board`, `puzzle`, or `grid`
+    // string or array of numbers, or an array of 81 numbers
+    Json,
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            InputFormat::Line => "line",
+            InputFormat::Grid => "grid",
+            InputFormat::Sdk => "sdk",
+            InputFormat::Sdm => "sdm",
+            InputFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Whether the line is exactly 81 squares with nothing around them.
+fn is_puzzle_line(line: &str) -> bool {
+    let line = line.trim();
+    line.chars().count() == 81
+        && line
+            .chars()
+            .all(|c| is_blank(c) || i32_from_char(c).is_some())
+}
+
+/// Whether the text is JSON rather than starting with an SDK section such
+/// as `[Puzzle]`: an object, or an array whose first item is a number or
+/// another array or object.
+fn is_json(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('{') => true,
+        Some('[') => chars
+            .next()
+            .is_some_and(|c| c.is_ascii_digit() || c.is_whitespace() || c == '[' || c == '{'),
+        _ => false,
+    }
+}
+
+/// Add the puzzles of a JSON value: an object holding the puzzle as its
+/// `board`, `puzzle`, or `grid`, an array of such objects, or the numbers
+/// of one puzzle.
+fn json_boards(value: &Json, boards: &mut Vec<SudokuBoard>) -> SudokuResult {
+    match value {
+        Json::Object(_) => {
+            let puzzle = ["board", "puzzle", "grid"]
+                .iter()
+                .find_map(|key| value.get(key))
+                .ok_or_else(|| {
+                    SudokuError::InvalidJson("no `board`, `puzzle`, or `grid`".to_string())
+                })?;
+            boards.push(match puzzle {
+                Json::String(grid) => SudokuBoard::parse_grid(grid)?,
+                numbers => json_board(numbers)?,
+            });
+        }
+        Json::Array(items)
+            if items
+                .first()
+                .is_some_and(|item| matches!(item, Json::Object(_))) =>
+        {
+            for item in items {
+                json_boards(item, boards)?;
+            }
+        }
+        numbers => boards.push(json_board(numbers)?),
+    }
+    Ok(())
+}
+
+/// A puzzle written as an array of 81 numbers, 0 for an unknown square,
+/// possibly nested as 9 rows.
+fn json_board(value: &Json) -> Result<SudokuBoard, SudokuError> {
+    fn digits(value: &Json, cells: &mut String) -> SudokuResult {
+        match value {
+            Json::Number(n) if n.fract() == 0.0 && (0.0..=9.0).contains(n) => {
+                cells.push(char::from(b'0' + *n as u8));
+            }
+            Json::Number(_) => return Err(SudokuError::InvalidRange),
+            Json::Array(items) => {
+                for item in items {
+                    digits(item, cells)?;
+                }
+            }
+            _ => return Err(SudokuError::InvalidJson("expected a number".to_string())),
+        }
+        Ok(())
+    }
+    let mut cells = String::new();
+    digits(value, &mut cells)?;
+    SudokuBoard::parse_grid(&cells)
+}
+
+/// Read a JSON document: objects holding puzzles, one after the other as
+/// JSON lines or in an array, or a single array of numbers.
+pub(crate) fn parse_json(s: &str) -> Result<Vec<SudokuBoard>, SudokuError> {
+    let mut boards = Vec::new();
+    for value in Json::parse_many(s)? {
+        json_boards(&value, &mut boards)?;
+    }
+    Ok(boards)
+}
+
+/// Read a SadMan Sudoku file: the lines of its `[Puzzle]` section when it
+/// has sections, otherwise every line that is not `#` metadata.
+pub(crate) fn parse_sdk(s: &str) -> Result<SudokuBoard, SudokuError> {
+    let mut section: Option<&str> = None;
+    let mut rows = String::new();
+    for line in s.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = Some(line);
+        } else if !line.starts_with('#')
+            && !line.is_empty()
+            && section.is_none_or(|name| name.eq_ignore_ascii_case("[puzzle]"))
+        {
+            rows.push_str(line);
+            rows.push('\n');
+        }
+    }
+    SudokuBoard::parse_grid(&rows)
+}
+
+impl SudokuBoard {
+    /// Read puzzles written in any of the formats of `InputFormat`, telling
+    /// which it is from the text, and return the format with the puzzles.
+    /// Only `Sdm` and `Json` hold more than one.
+    ///
+    /// JSON starts with `{`, or with `[` followed by a number, a space, or
+    /// another array or object.  SDK has `#` metadata lines or sections
+    /// such as `[Puzzle]` or `[Properties]`, in any case, and a text of 81
+    /// squares per line is one puzzle on a line, or SDM when there are
+    /// several.  Anything else is read as a grid like `parse_grid`, failing
+    /// as it does.
+    pub fn parse_any(s: &str) -> Result<(InputFormat, Vec<SudokuBoard>), SudokuError> {
+        let text = s.trim();
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if is_json(text) {
+            return Ok((InputFormat::Json, parse_json(text)?));
+        }
+        if lines
+            .iter()
+            .any(|line| line.starts_with('#') || line.starts_with('['))
+        {
+            return Ok((InputFormat::Sdk, vec![parse_sdk(text)?]));
+        }
+        if !lines.is_empty() && lines.iter().all(|line| is_puzzle_line(line)) {
+            let boards = lines
+                .iter()
+                .map(|line| SudokuBoard::parse_grid(line))
+                .collect::<Result<Vec<_>, _>>()?;
+            let format = if boards.len() == 1 {
+                InputFormat::Line
+            } else {
+                InputFormat::Sdm
+            };
+            return Ok((format, boards));
+        }
+        Ok((InputFormat::Grid, vec![SudokuBoard::parse_grid(text)?]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InputFormat;
+    use crate::{SudokuBoard, SudokuError};
+
+    const PUZZLE: &str =
+        "4....8.......91.8..865.2.3..2.4..9...1.2....6367.59........5...7..8...242..93..7.";
+
+    fn detect(s: &str) -> (InputFormat, Vec<String>) {
+        let (format, boards) = SudokuBoard::parse_any(s).unwrap();
+        (format, boards.iter().map(|b| b.print_board()).collect())
+    }
+
+    #[test]
+    fn test_parse_any() {
+        let expected = PUZZLE.replace('.', "-");
+        let rows: Vec<&str> = (0..9).map(|r| &PUZZLE[r * 9..r * 9 + 9]).collect();
+        let grid = rows.join("\n");
+
+        assert_eq!(detect(PUZZLE), (InputFormat::Line, vec![expected.clone()]));
+        assert_eq!(
+            detect(&format!("{}\n\n{}\n", PUZZLE, PUZZLE.replace('.', "0"))),
+            (InputFormat::Sdm, vec![expected.clone(), expected.clone()])
+        );
+        assert_eq!(detect(&grid), (InputFormat::Grid, vec![expected.clone()]));
+        assert_eq!(
+            detect(&format!("#AAuthor\n#DA description\n{}\n", grid)),
+            (InputFormat::Sdk, vec![expected.clone()])
+        );
+        assert_eq!(
+            detect(&format!(
+                "[Puzzle]\n{}\n[State]\n{}\n",
+                grid,
+                "1".repeat(81)
+            )),
+            (InputFormat::Sdk, vec![expected.clone()])
+        );
+
+        let json = format!(
+            "{{\"name\":\"a\",\"board\":\"{0}\"}}\n{{\"name\":null,\"board\":\"{0}\"}}\n",
+            PUZZLE
+        );
+        assert_eq!(
+            detect(&json),
+            (InputFormat::Json, vec![expected.clone(), expected.clone()])
+        );
+        let numbers: Vec<String> = expected
+            .chars()
+            .map(|c| c.to_digit(10).unwrap_or(0).to_string())
+            .collect();
+        let array = format!("[{}]", numbers.join(", "));
+        assert_eq!(detect(&array), (InputFormat::Json, vec![expected.clone()]));
+
+        assert!(matches!(
+            SudokuBoard::parse_any("123"),
+            Err(SudokuError::InvalidCellCount(3))
+        ));
+        for header in ["[puzzle]", "[Properties]\nlevel=1\n[Puzzle]"] {
+            assert_eq!(
+                detect(&format!("{}\n{}\n", header, grid)).0,
+                InputFormat::Sdk
+            );
+        }
+        // the numbers as the value of a key, and a string with escapes
+        let object = format!("{{\"board\": [{}]}}", numbers.join(","));
+        assert_eq!(detect(&object), (InputFormat::Json, vec![expected.clone()]));
+        let escaped = format!("[{{\"puzzle\":\"{}\"}}]", grid.replace('\n', "\\n"));
+        assert_eq!(detect(&escaped), (InputFormat::Json, vec![expected]));
+        assert!(matches!(
+            SudokuBoard::parse_any("{\"name\": \"a\"}"),
+            Err(SudokuError::InvalidJson(_))
+        ));
+        let nested = format!("[\n[{}]\n]", numbers.join(",\n"));
+        assert_eq!(detect(&nested).0, InputFormat::Json);
+        assert!(SudokuBoard::parse_any("[1, true]").is_err());
+        assert_eq!(InputFormat::Sdm.to_string(), "sdm");
+    }
+}
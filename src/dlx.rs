@@ -0,0 +1,408 @@
+use crate::{BoxValue, CellPos, SudokuBoard, SudokuError, SudokuResult};
+
+/// Rules a variant puzzle adds to the rows, columns, and boxes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Variant {
+    // Extra groups of 9 squares, (row, col) from 1, that hold every digit
+    // once, such as the diagonals
+    pub regions: Vec<Vec<(usize, usize)>>,
+    // No two squares a knight's move apart hold the same digit
+    pub anti_knight: bool,
+}
+
+impl Variant {
+    /// Both long diagonals hold every digit once, as in sudoku X.
+    pub fn diagonals() -> Variant {
+        Variant {
+            regions: vec![
+                (1..=9).map(|i| (i, i)).collect(),
+                (1..=9).map(|i| (i, 10 - i)).collect(),
+            ],
+            anti_knight: false,
+        }
+    }
+
+    /// Check the variant's regions, and that no digit is given twice in a
+    /// region or a knight's move apart.  `cells` are the givens of the
+    /// squares read left to right and top to bottom.
+    pub(crate) fn check_givens(&self, cells: &[Option<u8>]) -> SudokuResult {
+        let position = |i: usize| (i / 9 + 1, i % 9 + 1);
+        let clash = |i: usize, j: usize| match (cells[i], cells[j]) {
+            (Some(a), Some(b)) if a == b => Err(SudokuError::ConflictingGivens {
+                digit: a,
+                first: position(i.min(j)),
+                second: position(i.max(j)),
+            }),
+            _ => Ok(()),
+        };
+        for region in self.region_cells()? {
+            for (k, i) in region.iter().enumerate() {
+                for j in &region[k + 1..] {
+                    clash(*i, *j)?;
+                }
+            }
+        }
+        if self.anti_knight {
+            for i in 0..81 {
+                for j in knight_moves(i).filter(|j| *j > i) {
+                    clash(i, j)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The indexes of the squares of each region, checking that each has 9
+    /// different squares on the board.
+    fn region_cells(&self) -> Result<Vec<Vec<usize>>, SudokuError> {
+        let mut regions = Vec::new();
+        for region in &self.regions {
+            let mut cells = region
+                .iter()
+                .map(|(row, col)| Ok(CellPos::new(*row, *col)?.index()))
+                .collect::<Result<Vec<usize>, SudokuError>>()?;
+            cells.sort();
+            cells.dedup();
+            if cells.len() != 9 {
+                return Err(SudokuError::InvalidRange);
+            }
+            regions.push(cells);
+        }
+        Ok(regions)
+    }
+}
+
+/// Knuth's dancing links over a sparse 0/1 matrix.  Node 0 is the root,
+/// nodes 1 to `columns` the column headers, and the rest hold the 1s.
+/// Primary columns must be covered exactly once, secondary columns at most
+/// once, so only primary headers are linked to the root.
+struct Dlx {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    // the column header of each node
+    col: Vec<usize>,
+    // the matrix row of each node
+    row: Vec<usize>,
+    // the number of nodes in each column, by header
+    size: Vec<usize>,
+}
+
+impl Dlx {
+    fn new(primary: usize, secondary: usize) -> Dlx {
+        let n = primary + secondary + 1;
+        let mut dlx = Dlx {
+            left: (0..n).collect(),
+            right: (0..n).collect(),
+            up: (0..n).collect(),
+            down: (0..n).collect(),
+            col: (0..n).collect(),
+            row: vec![usize::MAX; n],
+            size: vec![0; n],
+        };
+        for c in 0..=primary {
+            dlx.right[c] = (c + 1) % (primary + 1);
+            dlx.left[(c + 1) % (primary + 1)] = c;
+        }
+        dlx
+    }
+
+    /// Add a matrix row with 1s in the columns, numbered from 0.
+    fn add_row(&mut self, row: usize, columns: &[usize]) {
+        let first = self.col.len();
+        for (k, c) in columns.iter().enumerate() {
+            let header = c + 1;
+            let node = first + k;
+            self.col.push(header);
+            self.row.push(row);
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+            let (prev, next) = if k == 0 {
+                (node, node)
+            } else {
+                (node - 1, first)
+            };
+            self.left.push(prev);
+            self.right.push(next);
+            self.right[prev] = node;
+            self.left[next] = node;
+        }
+    }
+
+    fn cover(&mut self, c: usize) {
+        self.right[self.left[c]] = self.right[c];
+        self.left[self.right[c]] = self.left[c];
+        let mut i = self.down[c];
+        while i != c {
+            let mut j = self.right[i];
+            while j != i {
+                self.down[self.up[j]] = self.down[j];
+                self.up[self.down[j]] = self.up[j];
+                self.size[self.col[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, c: usize) {
+        let mut i = self.up[c];
+        while i != c {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.col[j]] += 1;
+                self.down[self.up[j]] = j;
+                self.up[self.down[j]] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        self.right[self.left[c]] = c;
+        self.left[self.right[c]] = c;
+    }
+
+    /// Find exact covers, stopping once `limit` are counted.  The rows of
+    /// the first one found are kept in `first`.
+    fn search(
+        &mut self,
+        chosen: &mut Vec<usize>,
+        count: &mut usize,
+        limit: usize,
+        first: &mut Vec<usize>,
+    ) {
+        if self.right[0] == 0 {
+            if *count == 0 {
+                *first = chosen.clone();
+            }
+            *count += 1;
+            return;
+        }
+        // the primary column with the fewest rows left
+        let mut c = self.right[0];
+        let mut j = self.right[c];
+        while j != 0 {
+            if self.size[j] < self.size[c] {
+                c = j;
+            }
+            j = self.right[j];
+        }
+        if self.size[c] == 0 {
+            return;
+        }
+        self.cover(c);
+        let mut r = self.down[c];
+        while r != c && *count < limit {
+            chosen.push(self.row[r]);
+            let mut j = self.right[r];
+            while j != r {
+                self.cover(self.col[j]);
+                j = self.right[j];
+            }
+            self.search(chosen, count, limit, first);
+            let mut j = self.left[r];
+            while j != r {
+                self.uncover(self.col[j]);
+                j = self.left[j];
+            }
+            chosen.pop();
+            r = self.down[r];
+        }
+        self.uncover(c);
+    }
+}
+
+/// The squares a knight's move from the square at `i`.
+fn knight_moves(i: usize) -> impl Iterator<Item = usize> {
+    let (r, c) = ((i / 9) as isize, (i % 9) as isize);
+    [
+        (-2, -1),
+        (-2, 1),
+        (-1, -2),
+        (-1, 2),
+        (1, -2),
+        (1, 2),
+        (2, -1),
+        (2, 1),
+    ]
+    .into_iter()
+    .map(move |(dr, dc)| (r + dr, c + dc))
+    .filter(|(r, c)| (0..9).contains(r) && (0..9).contains(c))
+    .map(|(r, c)| (r * 9 + c) as usize)
+}
+
+impl SudokuBoard {
+    /// Find solutions with an exact cover search, counting up to `limit`.
+    /// Every square, and every digit of every house and region, is a
+    /// column that must be covered once.  Each pair of squares a knight's
+    /// move apart, with each digit, is a column covered at most once.
+    fn exact_cover(
+        &self,
+        variant: &Variant,
+        limit: usize,
+    ) -> Result<(usize, Vec<usize>), SudokuError> {
+        let regions = variant.region_cells()?;
+        let mut knight_pairs = Vec::new();
+        if variant.anti_knight {
+            for i in 0..81 {
+                knight_pairs.extend(knight_moves(i).filter(|j| *j > i).map(|j| (i, j)));
+            }
+        }
+        let primary = 4 * 81 + 9 * regions.len();
+        let mut dlx = Dlx::new(primary, 9 * knight_pairs.len());
+        for (i, node) in self.board.iter().flatten().enumerate() {
+            let digits: Vec<usize> = match &node.value {
+                BoxValue::Known(v) => vec![*v as usize],
+                BoxValue::Unknown(set) => set.iter().map(|d| *d as usize).collect(),
+            };
+            let (r, c, b) = (i / 9, i % 9, (i / 27) * 3 + (i % 9) / 3);
+            for d in digits {
+                let mut columns = vec![
+                    i,
+                    81 + r * 9 + d - 1,
+                    162 + c * 9 + d - 1,
+                    243 + b * 9 + d - 1,
+                ];
+                for (k, cells) in regions.iter().enumerate() {
+                    if cells.contains(&i) {
+                        columns.push(324 + k * 9 + d - 1);
+                    }
+                }
+                for (k, (a, b)) in knight_pairs.iter().enumerate() {
+                    if *a == i || *b == i {
+                        columns.push(primary + k * 9 + d - 1);
+                    }
+                }
+                dlx.add_row(i * 9 + d - 1, &columns);
+            }
+        }
+        let mut count = 0;
+        let mut first = Vec::new();
+        if limit > 0 {
+            dlx.search(&mut Vec::new(), &mut count, limit, &mut first);
+        }
+        Ok((count, first))
+    }
+
+    /// Solve the board with an exact cover search under the rules of a
+    /// variant, as well as the usual ones.  Fails with `InvalidRange` when a
+    /// region is not 9 different squares of the board, and `NotSolvable`
+    /// when there is no solution, leaving the board unchanged.
+    pub fn solve_variant(&mut self, variant: &Variant) -> SudokuResult {
+        let (count, rows) = self.exact_cover(variant, 1)?;
+        if count == 0 {
+            return Err(SudokuError::NotSolvable(None));
+        }
+        for row in rows {
+            let node = &mut self.board[row / 81][row / 9 % 9];
+            node.value = BoxValue::Known((row % 9 + 1) as i32);
+        }
+        self.unknown_values = 0;
+        Ok(())
+    }
+
+    /// Count the solutions of the board under the rules of a variant,
+    /// stopping once `limit` are found.
+    pub fn count_variant_solutions(
+        &self,
+        variant: &Variant,
+        limit: usize,
+    ) -> Result<usize, SudokuError> {
+        Ok(self.exact_cover(variant, limit)?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{knight_moves, Variant};
+    use crate::pos::at;
+    use crate::{SudokuBoard, SudokuError};
+
+    #[test]
+    fn test_plain_sudoku() {
+        let s = concat!(
+            "---------",
+            "-----3-85",
+            "--1-2----",
+            "---5-7---",
+            "--4---1--",
+            "-9-------",
+            "5------73",
+            "--2-1----",
+            "----4---9"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        let mut expected = puzzle.clone();
+        expected.solve_first().unwrap();
+        let mut sboard = puzzle.clone();
+        sboard.solve_variant(&Variant::default()).unwrap();
+        assert!(sboard == expected && sboard.is_solved());
+        assert_eq!(
+            sboard.print_given_mask(),
+            puzzle.print_given_mask().replace('-', "S")
+        );
+        assert_eq!(
+            puzzle
+                .count_variant_solutions(&Variant::default(), 2)
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_variants() {
+        let mut diagonal = SudokuBoard::new();
+        diagonal.solve_variant(&Variant::diagonals()).unwrap();
+        assert!(diagonal.is_solved() && diagonal.is_valid());
+        for cells in Variant::diagonals().regions {
+            let mut digits: Vec<u8> = cells
+                .iter()
+                .map(|(r, c)| diagonal.get(at(*r, *c)).unwrap())
+                .collect();
+            digits.sort();
+            assert_eq!(digits, (1..=9).collect::<Vec<u8>>());
+        }
+
+        let anti_knight = Variant {
+            anti_knight: true,
+            ..Variant::default()
+        };
+        // an empty board takes the search a long while to fill, so start
+        // from a row of a grid known to have no knight clashes
+        let mut sboard = SudokuBoard::fill_board("123456789").unwrap();
+        sboard.solve_variant(&anti_knight).unwrap();
+        assert!(sboard.is_solved() && sboard.is_valid());
+        let line = sboard.print_board().into_bytes();
+        assert!((0..81).all(|i| knight_moves(i).all(|j| line[i] != line[j])));
+
+        // r2c2 is a knight's move from r1c4, though in another row,
+        // column, and box
+        let mut knight = SudokuBoard::new();
+        knight.set(at(1, 4), 4).unwrap();
+        knight.set(at(2, 2), 4).unwrap();
+        assert_eq!(
+            knight
+                .count_variant_solutions(&Variant::default(), 1)
+                .unwrap(),
+            1
+        );
+        assert_eq!(knight.count_variant_solutions(&anti_knight, 1).unwrap(), 0);
+        assert!(matches!(
+            knight.solve_variant(&anti_knight),
+            Err(SudokuError::NotSolvable(None))
+        ));
+
+        let bad = Variant {
+            regions: vec![vec![(1, 1); 9]],
+            anti_knight: false,
+        };
+        assert!(matches!(
+            SudokuBoard::new().solve_variant(&bad),
+            Err(SudokuError::InvalidRange)
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{BoxValue, CellPos, SolveStats, SudokuBoard, SudokuError};
+
+/// A puzzle being set, one given at a time, that keeps its solution up to
+/// date.  The alternatives of the puzzle are updated in place on each
+/// change, and the last solution is kept as long as it still fits, so
+/// tweaking the givens over and over does not solve from scratch each time.
+pub struct ClueEditor {
+    puzzle: SudokuBoard,
+    // The last solution found, None when the puzzle has none
+    solution: Option<SudokuBoard>,
+}
+
+impl ClueEditor {
+    /// Start editing a puzzle, solving it once.
+    pub fn new(puzzle: SudokuBoard) -> ClueEditor {
+        let mut editor = ClueEditor {
+            puzzle,
+            solution: None,
+        };
+        let _ = editor.resolve();
+        editor
+    }
+
+    /// The puzzle with the givens so far.
+    pub fn puzzle(&self) -> &SudokuBoard {
+        &self.puzzle
+    }
+
+    /// A solution of the puzzle, or None when it has none.
+    pub fn solution(&self) -> Option<&SudokuBoard> {
+        self.solution.as_ref()
+    }
+
+    /// Stop editing and give back the puzzle.
+    pub fn into_puzzle(self) -> SudokuBoard {
+        self.puzzle
+    }
+
+    fn resolve(&mut self) -> Result<SolveStats, SudokuError> {
+        let mut solved = self.puzzle.clone();
+        let result = solved.solve_first();
+        self.solution = result.is_ok().then_some(solved);
+        result
+    }
+
+    /// Add a given to an unknown square, and re-solve when needed.  When the
+    /// last solution already has the digit there, or there was none, adding
+    /// a given cannot change that, so no search is made and the stats are
+    /// empty.
+    ///
+    /// Fails with `AlreadyKnown` for a known square, and with
+    /// `ConflictingGivens` or `NotSolvable` when the digit cannot go there,
+    /// leaving the puzzle unchanged.  Fails with `NotSolvable` after adding
+    /// the given when the puzzle is left without a solution.
+    pub fn add_given(&mut self, pos: CellPos, digit: u8) -> Result<SolveStats, SudokuError> {
+        if !(1..=9).contains(&digit) {
+            return Err(SudokuError::InvalidRange);
+        }
+        let (row, col) = (pos.row.get(), pos.col.get());
+        if let BoxValue::Known(_) = self.puzzle.cell(pos).value {
+            return Err(SudokuError::AlreadyKnown { row, col });
+        }
+        let mut puzzle = self.puzzle.clone();
+        puzzle.mark_as_given(row, col, i32::from(digit))?;
+        self.puzzle = puzzle;
+        match &self.solution {
+            Some(solution) if solution.get(pos) == Some(digit) => Ok(SolveStats::default()),
+            Some(_) => self.resolve(),
+            None => Err(SudokuError::NotSolvable(None)),
+        }
+    }
+
+    /// Remove the given of a square, and re-solve when needed.  A solution
+    /// of the puzzle still is one with fewer givens, so a search is only
+    /// made when there was none.  Removing a square that is not a given
+    /// does nothing.
+    pub fn remove_given(&mut self, pos: CellPos) -> Result<SolveStats, SudokuError> {
+        if !self.puzzle.is_given(pos) {
+            return Ok(SolveStats::default());
+        }
+        self.puzzle.clear(pos)?;
+        match self.solution {
+            Some(_) => Ok(SolveStats::default()),
+            None => self.resolve(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClueEditor;
+    use crate::pos::at;
+    use crate::{SolveStats, SudokuBoard, SudokuError};
+
+    #[test]
+    fn test_clue_editor() {
+        let mut editor = ClueEditor::new(SudokuBoard::new());
+        let first = editor.solution().unwrap().clone();
+        let digit = first.get(at(5, 5)).unwrap();
+
+        // the solution already fits, so nothing is searched
+        assert_eq!(
+            editor.add_given(at(5, 5), digit).unwrap(),
+            SolveStats::default()
+        );
+        assert!(editor.solution() == Some(&first));
+        assert!(editor.puzzle().is_given(at(5, 5)));
+
+        // a given that does not fit needs a new solution
+        let other = first.get(at(1, 1)).unwrap() % 9 + 1;
+        let other = if other == digit { other % 9 + 1 } else { other };
+        editor.add_given(at(1, 1), other).unwrap();
+        let second = editor.solution().unwrap();
+        assert!(second.is_solved() && second.is_valid());
+        assert_eq!(second.get(at(1, 1)), Some(other));
+        assert_eq!(second.get(at(5, 5)), Some(digit));
+
+        assert!(matches!(
+            editor.add_given(at(1, 1), 3),
+            Err(SudokuError::AlreadyKnown { row: 1, col: 1 })
+        ));
+        assert!(matches!(
+            editor.add_given(at(1, 2), other),
+            Err(SudokuError::ConflictingGivens { .. })
+        ));
+        assert!(!editor.puzzle().is_given(at(1, 2)));
+
+        assert_eq!(
+            editor.remove_given(at(1, 1)).unwrap(),
+            SolveStats::default()
+        );
+        assert!(!editor.puzzle().is_given(at(1, 1)));
+        assert_eq!(editor.puzzle().candidates(at(1, 1)).count(), 9);
+        let mut expected = SudokuBoard::new();
+        expected.set(at(5, 5), digit).unwrap();
+        assert!(editor.puzzle() == &expected);
+
+        // 9 cannot go anywhere in row 1 until a given is removed
+        let s = "---123456--9".to_string() + &"-".repeat(69);
+        let mut editor = ClueEditor::new(SudokuBoard::fill_board(&s).unwrap());
+        assert!(editor.solution().is_none());
+        assert!(matches!(
+            editor.add_given(at(9, 9), 1),
+            Err(SudokuError::NotSolvable(None))
+        ));
+        editor.remove_given(at(2, 3)).unwrap();
+        assert!(editor.solution().unwrap().is_solved());
+    }
+}
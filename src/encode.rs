@@ -0,0 +1,331 @@
+use crate::{BoxValue, SudokuBoard, SudokuError};
+use std::collections::BTreeSet;
+
+/// Version written in the first byte of every encoding.
+pub const BINARY_VERSION: u8 = 2;
+
+/// Flag set in the second byte when the candidate section follows the values.
+const FLAG_CANDIDATES: u8 = 0b0000_0001;
+
+/// The URL-safe base64 alphabet from RFC 4648.
+const CODE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// 81 values at 4 bits each.
+const VALUES_LEN: usize = 41;
+/// 81 given flags at 1 bit each.
+const GIVENS_LEN: usize = 11;
+/// 81 candidate sets at 9 bits each.
+const CANDIDATES_LEN: usize = 92;
+
+impl SudokuBoard {
+    /// Encode the board in a compact binary form suited for storing large
+    /// puzzle databases.
+    ///
+    /// The layout is:
+    ///
+    /// * byte 0: format version (`BINARY_VERSION`)
+    /// * byte 1: flags, bit 0 is set when candidates are included
+    /// * 41 bytes: every square as a 4 bit value, high nibble first, 0 when unknown
+    /// * 11 bytes: every square as a bit set when its value is a given,
+    ///   least significant bit first
+    /// * 92 bytes (optional): every square as a 9 bit candidate set, least
+    ///   significant bit first, bit 0 meaning the digit 1
+    pub fn to_bytes(&self, include_candidates: bool) -> Vec<u8> {
+        let mut bytes = vec![BINARY_VERSION, 0];
+        bytes.resize(2 + VALUES_LEN + GIVENS_LEN, 0);
+        for (i, node) in self.board.iter().flatten().enumerate() {
+            let nibble = match node.value {
+                BoxValue::Known(v) => v as u8,
+                BoxValue::Unknown(_) => 0,
+            };
+            bytes[2 + i / 2] |= if i % 2 == 0 { nibble << 4 } else { nibble };
+            if node.given {
+                bytes[2 + VALUES_LEN + i / 8] |= 1 << (i % 8);
+            }
+        }
+
+        if include_candidates {
+            bytes[1] |= FLAG_CANDIDATES;
+            let mut section = vec![0u8; CANDIDATES_LEN];
+            for (i, node) in self.board.iter().flatten().enumerate() {
+                if let BoxValue::Unknown(set) = &node.value {
+                    for v in set {
+                        let bit = i * 9 + (*v as usize - 1);
+                        section[bit / 8] |= 1 << (bit % 8);
+                    }
+                }
+            }
+            bytes.extend(section);
+        }
+        bytes
+    }
+
+    /// Decode a board written by `to_bytes`.  The givens are placed first,
+    /// then the other known values, then the stored candidates (when
+    /// present) narrow the remaining squares.  A digit repeated in a row,
+    /// column, or box is refused: `ConflictingGivens` when both are givens,
+    /// `InvalidEncoding` otherwise.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SudokuBoard, SudokuError> {
+        if bytes.len() < 2 {
+            return Err(SudokuError::InvalidEncoding);
+        }
+        if bytes[0] != BINARY_VERSION {
+            return Err(SudokuError::UnsupportedVersion(bytes[0]));
+        }
+        let flags = bytes[1];
+        let expected_len = if flags & FLAG_CANDIDATES != 0 {
+            2 + VALUES_LEN + GIVENS_LEN + CANDIDATES_LEN
+        } else {
+            2 + VALUES_LEN + GIVENS_LEN
+        };
+        if flags & !FLAG_CANDIDATES != 0 || bytes.len() != expected_len {
+            return Err(SudokuError::InvalidEncoding);
+        }
+
+        let mut values = [0u8; 81];
+        let mut givens = [false; 81];
+        for i in 0..81 {
+            let byte = bytes[2 + i / 2];
+            values[i] = if i % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            givens[i] = bytes[2 + VALUES_LEN + i / 8] & (1 << (i % 8)) != 0;
+            if values[i] > 9 || (givens[i] && values[i] == 0) {
+                return Err(SudokuError::InvalidEncoding);
+            }
+        }
+        // the unused bits after the last square are zero
+        if bytes[2 + VALUES_LEN + GIVENS_LEN - 1] >> (81 % 8) != 0 {
+            return Err(SudokuError::InvalidEncoding);
+        }
+
+        let mut board = SudokuBoard::new();
+        for given in [true, false] {
+            for i in (0..81).filter(|&i| values[i] != 0 && givens[i] == given) {
+                let (row, col, digit) = (i / 9 + 1, i % 9 + 1, values[i] as i32);
+                if given {
+                    board.mark_as_given(row, col, digit)?;
+                } else {
+                    // a placed digit repeating one of its house was never
+                    // written by `to_bytes`
+                    if board.clash(row, col, digit as u8).is_some() {
+                        return Err(SudokuError::InvalidEncoding);
+                    }
+                    board.mark_as_known(row, col, digit)?;
+                }
+            }
+        }
+
+        if flags & FLAG_CANDIDATES != 0 {
+            let section = &bytes[2 + VALUES_LEN + GIVENS_LEN..];
+            for (i, node) in board.board.iter_mut().flatten().enumerate() {
+                if let BoxValue::Unknown(set) = &mut node.value {
+                    let stored: BTreeSet<i32> = (1..=9)
+                        .filter(|v| {
+                            let bit = i * 9 + (*v as usize - 1);
+                            section[bit / 8] & (1 << (bit % 8)) != 0
+                        })
+                        .collect();
+                    set.retain(|v| stored.contains(v));
+                    if set.is_empty() {
+                        return Err(SudokuError::NotSolvable(None));
+                    }
+                }
+            }
+        }
+        Ok(board)
+    }
+}
+
+fn code_value(c: u8) -> Option<u32> {
+    CODE_ALPHABET.iter().position(|a| *a == c).map(|p| p as u32)
+}
+
+impl SudokuBoard {
+    /// Encode the board as a short string that is safe to put in a URL, so
+    /// puzzles can be shared as links.  The string is the `to_bytes` encoding
+    /// written with the URL-safe base64 alphabet and no padding.  The digits
+    /// placed since the givens are kept apart from them, and including the
+    /// candidates also keeps the progress made on the pencil marks.
+    pub fn to_code(&self, include_candidates: bool) -> String {
+        let bytes = self.to_bytes(include_candidates);
+        let mut code = String::with_capacity(bytes.len().div_ceil(3) * 4);
+        for chunk in bytes.chunks(3) {
+            let mut group = 0u32;
+            for (i, b) in chunk.iter().enumerate() {
+                group |= (*b as u32) << (16 - 8 * i);
+            }
+            for i in 0..=chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                code.push(CODE_ALPHABET[index as usize] as char);
+            }
+        }
+        code
+    }
+
+    /// Decode a board written by `to_code`.
+    pub fn from_code(code: &str) -> Result<SudokuBoard, SudokuError> {
+        let mut bytes = Vec::with_capacity(code.len() * 3 / 4);
+        for chunk in code.as_bytes().chunks(4) {
+            if chunk.len() == 1 {
+                return Err(SudokuError::InvalidEncoding);
+            }
+            let mut group = 0u32;
+            for (i, c) in chunk.iter().enumerate() {
+                let value = code_value(*c).ok_or(SudokuError::InvalidEncoding)?;
+                group |= value << (18 - 6 * i);
+            }
+            for i in 0..chunk.len() - 1 {
+                bytes.push((group >> (16 - 8 * i)) as u8);
+            }
+        }
+        SudokuBoard::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::SudokuBoard;
+    use crate::SudokuError;
+
+    fn puzzle() -> SudokuBoard {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        )
+        .to_string();
+        SudokuBoard::fill_board(&s).unwrap()
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let board = puzzle();
+        let bytes = board.to_bytes(false);
+        assert_eq!(bytes.len(), 54);
+        let decoded = SudokuBoard::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.print_board(), board.print_board());
+    }
+
+    #[test]
+    fn test_bytes_candidates() {
+        let mut board = puzzle();
+        // narrow a square further than propagation alone would
+        if let crate::BoxValue::Unknown(set) = &mut board.board[0][1].value {
+            let keep = *set.first().unwrap();
+            set.retain(|v| *v == keep);
+        }
+        let bytes = board.to_bytes(true);
+        assert_eq!(bytes.len(), 146);
+        let decoded = SudokuBoard::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.print_possibility(), board.print_possibility());
+    }
+
+    #[test]
+    fn test_bytes_keep_givens() {
+        let mut board = puzzle();
+        let digit = board.candidates(at(1, 2)).next().unwrap();
+        board.set(at(1, 2), digit).unwrap();
+        for include_candidates in [false, true] {
+            let bytes = board.to_bytes(include_candidates);
+            let mut decoded = SudokuBoard::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.print_board(), board.print_board());
+            assert!(decoded.is_given(at(1, 1)));
+            assert!(!decoded.is_given(at(1, 2)));
+            decoded.reset_to_givens().unwrap();
+            assert_eq!(decoded.print_board(), puzzle().print_board());
+
+            let code = board.to_code(include_candidates);
+            let decoded = SudokuBoard::from_code(&code).unwrap();
+            assert!(!decoded.is_given(at(1, 2)));
+        }
+    }
+
+    #[test]
+    fn test_code_round_trip() {
+        let board = puzzle();
+        let code = board.to_code(false);
+        assert_eq!(code.len(), 72);
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        let decoded = SudokuBoard::from_code(&code).unwrap();
+        assert_eq!(decoded.print_board(), board.print_board());
+
+        let code = board.to_code(true);
+        let decoded = SudokuBoard::from_code(&code).unwrap();
+        assert_eq!(decoded.print_possibility(), board.print_possibility());
+    }
+
+    #[test]
+    fn test_code_invalid() {
+        let code = puzzle().to_code(false);
+        assert!(matches!(
+            SudokuBoard::from_code(&code.replace('A', "=")),
+            Err(SudokuError::InvalidEncoding)
+        ));
+        assert!(matches!(
+            SudokuBoard::from_code(&code[..20]),
+            Err(SudokuError::InvalidEncoding)
+        ));
+    }
+
+    #[test]
+    fn test_bytes_invalid() {
+        let mut bytes = puzzle().to_bytes(false);
+        bytes[0] = 9;
+        assert!(matches!(
+            SudokuBoard::from_bytes(&bytes),
+            Err(SudokuError::UnsupportedVersion(9))
+        ));
+        assert!(matches!(
+            SudokuBoard::from_bytes(&bytes[..10]),
+            Err(SudokuError::UnsupportedVersion(9))
+        ));
+        bytes[0] = super::BINARY_VERSION;
+        // a given with no value
+        bytes[2 + 41] |= 0b10;
+        assert!(matches!(
+            SudokuBoard::from_bytes(&bytes),
+            Err(SudokuError::InvalidEncoding)
+        ));
+        bytes[2 + 41] &= !0b10;
+        bytes[2] = 0xf0;
+        assert!(matches!(
+            SudokuBoard::from_bytes(&bytes),
+            Err(SudokuError::InvalidEncoding)
+        ));
+        assert!(matches!(
+            SudokuBoard::from_bytes(&bytes[..10]),
+            Err(SudokuError::InvalidEncoding)
+        ));
+    }
+
+    #[test]
+    fn test_bytes_clash() {
+        // r1c1 holds the given 4, so a placed 4 at r1c2 repeats it
+        let mut bytes = puzzle().to_bytes(false);
+        assert_eq!(bytes[2], 0x40);
+        bytes[2] = 0x44;
+        assert!(matches!(
+            SudokuBoard::from_bytes(&bytes),
+            Err(SudokuError::InvalidEncoding)
+        ));
+        // marked as a given too, it is a repeated given
+        bytes[2 + 41] |= 0b10;
+        assert!(matches!(
+            SudokuBoard::from_bytes(&bytes),
+            Err(SudokuError::ConflictingGivens {
+                digit: 4,
+                first: (1, 1),
+                second: (1, 2)
+            })
+        ));
+    }
+}
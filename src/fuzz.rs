@@ -0,0 +1,55 @@
+use crate::SudokuBoard;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// Random boards for fuzzing.  Each square takes a digit from the input, or
+/// stays unknown, and a digit that would clash with the givens already
+/// placed, or leave a square with no alternatives, is skipped.  So every
+/// board loads like a puzzle would, though it may still have no solution.
+impl<'a> Arbitrary<'a> for SudokuBoard {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<SudokuBoard> {
+        let mut board = SudokuBoard::new();
+        for i in 0..81 {
+            let digit = u.int_in_range(0..=9)?;
+            if digit == 0 {
+                continue;
+            }
+            let mut next = board.clone();
+            if next.mark_as_given(i / 9 + 1, i % 9 + 1, digit).is_ok() {
+                board = next;
+            }
+        }
+        Ok(board)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(81))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SudokuBoard;
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn test_arbitrary() {
+        let data: Vec<u8> = (0..4000u32).map(|i| (i * 7919 % 251) as u8).collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..40 {
+            let board = SudokuBoard::arbitrary(&mut u).unwrap();
+            assert!(board.is_valid() && board.is_consistent());
+            assert_eq!(
+                board.print_given_mask().matches('G').count(),
+                board.solved_count()
+            );
+            // solving may fail, but never panics, and a solution is valid
+            let mut solved = board.clone();
+            if solved.solve_first().is_ok() {
+                assert!(solved.is_solved() && solved.is_valid());
+            }
+        }
+        // no data left gives an empty board
+        let board = SudokuBoard::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert!(board == SudokuBoard::new());
+    }
+}
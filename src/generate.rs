@@ -0,0 +1,251 @@
+use crate::random::Rng;
+use crate::{Difficulty, Hardness, SudokuBoard, Symmetry};
+
+/// What kind of puzzles the `Generator` makes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorOptions {
+    // Seed of the random numbers, the same seed gives the same puzzles
+    pub seed: u64,
+    pub symmetry: Symmetry,
+    // Stop removing givens once the puzzle has this many or fewer
+    pub clues: Option<usize>,
+    // Only make puzzles graded with this difficulty
+    pub difficulty: Option<Difficulty>,
+    // Give up after trying this many boards for the difficulty
+    pub attempts: usize,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> GeneratorOptions {
+        GeneratorOptions {
+            seed: 0,
+            symmetry: Symmetry::None,
+            clues: None,
+            difficulty: None,
+            attempts: 1000,
+        }
+    }
+}
+
+/// A puzzle kept by `Generator::search_hard`, with how hard it is.
+#[derive(Debug, Clone)]
+pub struct RatedPuzzle {
+    pub puzzle: SudokuBoard,
+    pub hardness: Hardness,
+}
+
+/// Makes new puzzles that have exactly one solution.
+///
+/// A puzzle starts as a random solved board.  Givens are then removed in a
+/// random order, keeping the symmetry, as long as the puzzle still has one
+/// solution and is not harder than the requested difficulty.
+pub struct Generator {
+    options: GeneratorOptions,
+    rng: Rng,
+}
+
+impl Generator {
+    pub fn new(options: GeneratorOptions) -> Generator {
+        let rng = Rng::new(options.seed);
+        Generator { options, rng }
+    }
+
+    /// Make the next puzzle.  When a difficulty was requested this tries up
+    /// to `attempts` new boards until one is graded with exactly that
+    /// difficulty, and returns None if none is.  Some options can never be
+    /// met, such as a diabolical puzzle that keeps 60 clues.
+    pub fn generate(&mut self) -> Option<SudokuBoard> {
+        for _ in 0..self.options.attempts.max(1) {
+            let puzzle = self.try_generate();
+            match self.options.difficulty {
+                Some(difficulty) if puzzle.difficulty() != difficulty => (),
+                _ => return Some(puzzle),
+            }
+        }
+        None
+    }
+
+    /// Hunt for hard puzzles for `rounds` rounds, keeping the `keep` hardest
+    /// found, hardest first.  Each round makes a new puzzle or, every other
+    /// round once some are kept, moves a given of a kept puzzle to another
+    /// square, keeping the puzzle's symmetry and single solution.
+    /// `progress` is called after each round with the rounds done and the
+    /// puzzles kept so far.  A requested difficulty caps how hard the new
+    /// puzzles get, so it is best left out.
+    pub fn search_hard(
+        &mut self,
+        rounds: usize,
+        keep: usize,
+        mut progress: impl FnMut(usize, &[RatedPuzzle]),
+    ) -> Vec<RatedPuzzle> {
+        let mut kept: Vec<RatedPuzzle> = Vec::new();
+        for round in 1..=rounds {
+            let candidate = if round % 2 == 0 && !kept.is_empty() {
+                let parent = self.rng.below(kept.len());
+                self.move_given(&kept[parent].puzzle)
+            } else {
+                Some(self.try_generate())
+            };
+            if let Some(puzzle) = candidate {
+                let known = kept
+                    .iter()
+                    .any(|k| k.puzzle.print_board() == puzzle.print_board());
+                if let (false, Ok(hardness)) = (known, puzzle.hardness()) {
+                    let at = kept.partition_point(|k| k.hardness >= hardness);
+                    kept.insert(at, RatedPuzzle { puzzle, hardness });
+                    kept.truncate(keep);
+                }
+            }
+            progress(round, &kept);
+        }
+        kept
+    }
+
+    /// Move a random given of the puzzle, with the givens of its symmetry,
+    /// to random unknown squares, filled in from the solution.  None when
+    /// the squares do not fit or the puzzle would not have one solution.
+    fn move_given(&mut self, puzzle: &SudokuBoard) -> Option<SudokuBoard> {
+        let mut solution = puzzle.clone();
+        solution.solve_first().ok()?;
+        let solution = solution.to_array();
+        let mut givens = puzzle.to_array();
+        let (filled, unknown): (Vec<usize>, Vec<usize>) =
+            (0..81).partition(|i| givens[i / 9][i % 9] != 0);
+        if filled.is_empty() || unknown.is_empty() {
+            return None;
+        }
+        let from = self
+            .options
+            .symmetry
+            .orbit(filled[self.rng.below(filled.len())]);
+        let to = self
+            .options
+            .symmetry
+            .orbit(unknown[self.rng.below(unknown.len())]);
+        if from.iter().any(|i| givens[i / 9][i % 9] == 0)
+            || to.iter().any(|i| givens[i / 9][i % 9] != 0)
+        {
+            return None;
+        }
+        for i in from {
+            givens[i / 9][i % 9] = 0;
+        }
+        for i in to {
+            givens[i / 9][i % 9] = solution[i / 9][i % 9];
+        }
+        let board = SudokuBoard::try_from(givens).ok()?;
+        (board.count_solutions(2) == 1).then_some(board)
+    }
+
+    fn try_generate(&mut self) -> SudokuBoard {
+        let mut solution = SudokuBoard::new();
+        // an empty board always has a solution
+        solution.solve_random(&mut self.rng).unwrap();
+        let mut givens = solution.to_array();
+
+        let mut order: Vec<usize> = (0..81).collect();
+        self.rng.shuffle(&mut order);
+        let mut clues = 81;
+        for index in order {
+            if self.options.clues.is_some_and(|target| clues <= target) {
+                break;
+            }
+            let orbit = self.options.symmetry.orbit(index);
+            if orbit.iter().any(|i| givens[i / 9][i % 9] == 0) {
+                continue;
+            }
+            let mut candidate = givens;
+            for i in &orbit {
+                candidate[i / 9][i % 9] = 0;
+            }
+            let board = SudokuBoard::try_from(candidate).unwrap();
+            if board.count_solutions(2) != 1 {
+                continue;
+            }
+            if let Some(difficulty) = self.options.difficulty {
+                if board.difficulty() > difficulty {
+                    continue;
+                }
+            }
+            givens = candidate;
+            clues -= orbit.len();
+        }
+        SudokuBoard::try_from(givens).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Generator, GeneratorOptions, Symmetry};
+    use crate::Difficulty;
+
+    #[test]
+    fn test_generate() {
+        let options = GeneratorOptions {
+            seed: 7,
+            symmetry: Symmetry::Rotational,
+            ..Default::default()
+        };
+        let puzzle = Generator::new(options.clone()).generate().unwrap();
+        assert_eq!(puzzle.count_solutions(2), 1);
+        let grid = puzzle.to_array();
+        for r in 0..9 {
+            for c in 0..9 {
+                assert_eq!(grid[r][c] == 0, grid[8 - r][8 - c] == 0);
+            }
+        }
+        // the same seed gives the same puzzle
+        let again = Generator::new(options).generate().unwrap();
+        assert_eq!(puzzle.print_board(), again.print_board());
+    }
+
+    #[test]
+    fn test_generate_clues_and_difficulty() {
+        let options = GeneratorOptions {
+            seed: 3,
+            clues: Some(40),
+            difficulty: Some(Difficulty::Easy),
+            ..Default::default()
+        };
+        let puzzle = Generator::new(options).generate().unwrap();
+        assert!(puzzle.unknown_values >= 41);
+        assert_eq!(puzzle.difficulty(), Difficulty::Easy);
+        assert_eq!(puzzle.count_solutions(2), 1);
+
+        // so many clues leave nothing that needs guessing
+        let options = GeneratorOptions {
+            seed: 3,
+            clues: Some(60),
+            difficulty: Some(Difficulty::Diabolical),
+            attempts: 20,
+            ..Default::default()
+        };
+        assert!(Generator::new(options).generate().is_none());
+    }
+
+    #[test]
+    fn test_search_hard() {
+        let options = GeneratorOptions {
+            seed: 11,
+            ..Default::default()
+        };
+        let mut calls = 0;
+        let kept = Generator::new(options.clone()).search_hard(6, 3, |round, kept| {
+            calls += 1;
+            assert_eq!(round, calls);
+            assert!(kept.len() <= 3);
+        });
+        assert_eq!(calls, 6);
+        assert_eq!(kept.len(), 3);
+        for pair in kept.windows(2) {
+            assert!(pair[0].hardness >= pair[1].hardness);
+        }
+        for rated in &kept {
+            assert_eq!(rated.puzzle.count_solutions(2), 1);
+            assert_eq!(rated.puzzle.hardness().unwrap(), rated.hardness);
+        }
+        // the same seed finds the same puzzles
+        let again = Generator::new(options).search_hard(6, 3, |_, _| ());
+        assert_eq!(kept[0].puzzle.print_board(), again[0].puzzle.print_board());
+    }
+}
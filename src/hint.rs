@@ -0,0 +1,386 @@
+use crate::{HouseId, Step, SudokuBoard, Technique};
+
+/// How much a hint gives away, from the least to the most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HintLevel {
+    // Only the technique to look for
+    Technique,
+    // The technique and the houses to look in
+    House,
+    // The technique, houses, and squares it is about
+    Cells,
+    // Everything, with the digits to place or remove
+    Move,
+}
+
+impl HintLevel {
+    pub const ALL: [HintLevel; 4] = [
+        HintLevel::Technique,
+        HintLevel::House,
+        HintLevel::Cells,
+        HintLevel::Move,
+    ];
+
+    /// The level numbered from 1 for `Technique` to 4 for `Move`.
+    pub fn from_number(n: usize) -> Option<HintLevel> {
+        n.checked_sub(1)
+            .and_then(|i| HintLevel::ALL.get(i))
+            .copied()
+    }
+}
+
+/// The next logical move on a board and why it can be made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    // The move, with the squares and houses that justify it
+    pub step: Step,
+    // The reasoning in one sentence, e.g. "r3c7 can only be 5"
+    pub explanation: String,
+}
+
+impl Hint {
+    pub fn new(step: Step) -> Hint {
+        let explanation = explain(&step);
+        Hint { step, explanation }
+    }
+
+    pub fn technique(&self) -> Technique {
+        self.step.technique
+    }
+
+    /// The hint told only as far as `level`, so as not to give away more
+    /// than asked for, e.g. "Look for a Naked Pair in row 3." at `House`.
+    pub fn at_level(&self, level: HintLevel) -> String {
+        let step = &self.step;
+        let mut text = format!("Look for {}", with_article(step.technique));
+        if level >= HintLevel::House {
+            text += &format!(" in {}", list(&hint_houses(step)));
+        }
+        if level >= HintLevel::Cells {
+            let cells: Vec<String> = step
+                .cells
+                .iter()
+                .map(|(r, c)| format!("r{}c{}", r, c))
+                .collect();
+            text += &format!(", at {}", list(&cells));
+        }
+        text += ".";
+        if level >= HintLevel::Move {
+            for line in moves(step) {
+                text += &format!("  {}", line);
+            }
+        }
+        text
+    }
+
+    /// The move explained for someone learning the technique: why it is
+    /// valid, then one sentence for each square it changes.
+    pub fn lesson(&self) -> Vec<String> {
+        teach(&self.step)
+    }
+}
+
+impl SudokuBoard {
+    /// The easiest logical move on the board, explained.  None when the
+    /// techniques are stuck or the board is solved.  The board is not
+    /// changed.
+    pub fn hint(&self) -> Option<Hint> {
+        self.next_step().map(Hint::new)
+    }
+}
+
+/// Join items as "a", "a and b", or "a, b and c".
+fn list<T: ToString>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+    match items.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => items.concat(),
+    }
+}
+
+pub(crate) fn explain(step: &Step) -> String {
+    let cells: Vec<String> = step
+        .cells
+        .iter()
+        .map(|(r, c)| format!("r{}c{}", r, c))
+        .collect();
+    let cells = list(&cells);
+    let digits = list(&step.digits);
+    let houses = &step.houses;
+    match step.technique {
+        Technique::NakedSingle => format!("{} can only be {}", cells, digits),
+        Technique::HiddenSingle => {
+            format!("{} can only go in {} in {}", digits, cells, houses[0])
+        }
+        Technique::LockedCandidates => format!(
+            "in {}, {} can only go in {}, which are all in {}, so it can be removed from the rest of {}",
+            houses[0], digits, cells, houses[1], houses[1]
+        ),
+        Technique::NakedPair | Technique::NakedTriple => format!(
+            "{} can only hold {} between them, so those digits can be removed from the rest of {}",
+            cells, digits, houses[0]
+        ),
+        Technique::HiddenPair | Technique::HiddenTriple => format!(
+            "in {}, {} can only go in {}, so the other digits can be removed from those squares",
+            houses[0], digits, cells
+        ),
+        Technique::XWing => format!(
+            "in {} and {}, {} can only go in {} and {}, so it can be removed from the rest of those",
+            houses[0], houses[1], digits, houses[2], houses[3]
+        ),
+    }
+}
+
+/// The name of a technique after "Look for": "an X-Wing" as it is said,
+/// and no article before the plural "Locked Candidates".
+fn with_article(technique: Technique) -> String {
+    match technique {
+        Technique::LockedCandidates => technique.name().to_string(),
+        Technique::XWing => format!("an {}", technique.name()),
+        _ => format!("a {}", technique.name()),
+    }
+}
+
+/// "two" or "three" squares or digits of a pair or triple.
+fn count_word(n: usize) -> &'static str {
+    match n {
+        2 => "two",
+        3 => "three",
+        _ => "several",
+    }
+}
+
+/// The houses to look in for a step.  A naked single is about one square,
+/// so its row stands for it.
+fn hint_houses(step: &Step) -> Vec<HouseId> {
+    match step.technique {
+        Technique::NakedSingle => vec![HouseId::Row(step.cells[0].0)],
+        Technique::XWing => step.houses[..2].to_vec(),
+        _ => step.houses[..1].to_vec(),
+    }
+}
+
+/// Why the step is valid, in a few sentences.
+fn reason(step: &Step) -> String {
+    let cells: Vec<String> = step
+        .cells
+        .iter()
+        .map(|(r, c)| format!("r{}c{}", r, c))
+        .collect();
+    let n = count_word(cells.len());
+    let cells = list(&cells);
+    let digits = list(&step.digits);
+    let houses = &step.houses;
+    match step.technique {
+        Technique::NakedSingle => format!(
+            "Every other digit is already in the row, column, or box of {}, \
+             so {} is the only digit left for it.",
+            cells, digits
+        ),
+        Technique::HiddenSingle => format!(
+            "Every other square of {} is filled in or sees a {}, \
+             so {} is the only place left for the {} of {}.",
+            houses[0], digits, cells, digits, houses[0]
+        ),
+        Technique::LockedCandidates => format!(
+            "In {}, {} can only go in {}, which are all in {}.  \
+             Whichever of them holds the {} of {} also holds the {} of {}, \
+             so no other square of {} can be {}.",
+            houses[0],
+            digits,
+            cells,
+            houses[1],
+            digits,
+            houses[0],
+            digits,
+            houses[1],
+            houses[1],
+            digits
+        ),
+        Technique::NakedPair | Technique::NakedTriple => format!(
+            "{} can only hold {} between them.  \
+             The {} squares need {} digits, so they take the {} of {}, \
+             and no other square of {} can hold those digits.",
+            cells, digits, n, n, digits, houses[0], houses[0]
+        ),
+        Technique::HiddenPair | Technique::HiddenTriple => format!(
+            "In {}, digits {} can only go in {}.  \
+             The {} digits need {} squares, so those squares are filled by them \
+             and have no room for any other digit.",
+            houses[0], digits, cells, n, n
+        ),
+        Technique::XWing => format!(
+            "In {} and {}, {} can only go in {} and {}.  \
+             Each has its {} in one of them, so between them they hold the {}s of {} and {}, \
+             and no other square of {} or {} can be {}.",
+            houses[0],
+            houses[1],
+            digits,
+            houses[2],
+            houses[3],
+            digits,
+            digits,
+            houses[2],
+            houses[3],
+            houses[2],
+            houses[3],
+            digits
+        ),
+    }
+}
+
+/// A sentence for each square a step places a digit in or removes
+/// candidates from, in the order of the step.
+fn moves(step: &Step) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (row, col, digit) in &step.placements {
+        lines.push(format!("Place {} in r{}c{}.", digit, row, col));
+    }
+    let mut squares: Vec<(usize, usize)> = Vec::new();
+    for (row, col, _) in &step.eliminations {
+        if !squares.contains(&(*row, *col)) {
+            squares.push((*row, *col));
+        }
+    }
+    for (row, col) in squares {
+        let digits: Vec<i32> = step
+            .eliminations
+            .iter()
+            .filter(|(r, c, _)| (*r, *c) == (row, col))
+            .map(|(_, _, d)| *d)
+            .collect();
+        lines.push(format!("Remove {} from r{}c{}.", list(&digits), row, col));
+    }
+    lines
+}
+
+/// The reason for a step followed by the moves it makes.
+pub(crate) fn teach(step: &Step) -> Vec<String> {
+    let mut lines = vec![reason(step)];
+    lines.extend(moves(step));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list, with_article, Hint, HintLevel};
+    use crate::{HouseId, Step, SudokuBoard, Technique};
+
+    #[test]
+    fn test_list() {
+        assert_eq!(list::<i32>(&[]), "");
+        assert_eq!(list(&[1]), "1");
+        assert_eq!(list(&[1, 2]), "1 and 2");
+        assert_eq!(list(&[1, 2, 3]), "1, 2 and 3");
+    }
+
+    #[test]
+    fn test_hint() {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        let sboard = SudokuBoard::fill_board(s).unwrap();
+        let hint = sboard.hint().unwrap();
+        assert_eq!(hint.step, sboard.next_step().unwrap());
+        assert_eq!(sboard.print_board(), s);
+        assert!(!hint.explanation.is_empty());
+
+        let step = Step {
+            technique: Technique::LockedCandidates,
+            placements: Vec::new(),
+            eliminations: vec![(2, 7, 5)],
+            cells: vec![(2, 1), (2, 3)],
+            houses: vec![HouseId::Box(1), HouseId::Row(2)],
+            digits: vec![5],
+        };
+        let hint = Hint::new(step);
+        assert_eq!(hint.technique(), Technique::LockedCandidates);
+        assert_eq!(
+            hint.explanation,
+            "in box 1, 5 can only go in r2c1 and r2c3, which are all in row 2, \
+             so it can be removed from the rest of row 2"
+        );
+    }
+
+    #[test]
+    fn test_lesson() {
+        let step = Step {
+            technique: Technique::NakedPair,
+            placements: Vec::new(),
+            eliminations: vec![(3, 2, 4), (3, 2, 7), (3, 9, 7)],
+            cells: vec![(3, 1), (3, 5)],
+            houses: vec![HouseId::Row(3)],
+            digits: vec![4, 7],
+        };
+        assert_eq!(
+            Hint::new(step).lesson(),
+            vec![
+                "r3c1 and r3c5 can only hold 4 and 7 between them.  \
+                 The two squares need two digits, so they take the 4 and 7 of row 3, \
+                 and no other square of row 3 can hold those digits.",
+                "Remove 4 and 7 from r3c2.",
+                "Remove 7 from r3c9.",
+            ]
+        );
+
+        // every technique the solver uses gets a lesson
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        while let Some(hint) = sboard.hint() {
+            let lesson = hint.lesson();
+            let moves = hint.step.placements.len() + hint.step.eliminations.len();
+            assert!(lesson.len() > 1 && lesson.len() <= moves + 1);
+            assert!(lesson.iter().all(|line| line.ends_with('.')));
+            sboard.apply_step(&hint.step).unwrap();
+        }
+        assert!(sboard.is_solved());
+    }
+
+    #[test]
+    fn test_at_level() {
+        let step = Step {
+            technique: Technique::NakedPair,
+            placements: Vec::new(),
+            eliminations: vec![(3, 2, 4), (3, 2, 7), (3, 9, 7)],
+            cells: vec![(3, 1), (3, 5)],
+            houses: vec![HouseId::Row(3)],
+            digits: vec![4, 7],
+        };
+        let hint = Hint::new(step);
+        let texts: Vec<String> = HintLevel::ALL.iter().map(|l| hint.at_level(*l)).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Look for a Naked Pair.",
+                "Look for a Naked Pair in row 3.",
+                "Look for a Naked Pair in row 3, at r3c1 and r3c5.",
+                "Look for a Naked Pair in row 3, at r3c1 and r3c5.  \
+                 Remove 4 and 7 from r3c2.  Remove 7 from r3c9.",
+            ]
+        );
+        // no level gives away the digits before the last
+        assert!(texts[..3]
+            .iter()
+            .all(|t| !t.contains('4') && !t.contains('7')));
+
+        assert_eq!(with_article(Technique::XWing), "an X-Wing");
+        assert_eq!(
+            with_article(Technique::LockedCandidates),
+            "Locked Candidates"
+        );
+        assert_eq!(with_article(Technique::HiddenSingle), "a Hidden Single");
+
+        assert_eq!(HintLevel::from_number(1), Some(HintLevel::Technique));
+        assert_eq!(HintLevel::from_number(4), Some(HintLevel::Move));
+        assert_eq!(HintLevel::from_number(0), None);
+        assert_eq!(HintLevel::from_number(5), None);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{BoardState, CellPos, SudokuBoard, SudokuResult};
+
+/// A change made to a board.  Squares are numbered from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    // Place a digit in an unknown square, as `SudokuBoard::set` does
+    Place { row: usize, col: usize, digit: u8 },
+    // Remove an alternative of an unknown square, as `SudokuBoard::eliminate` does
+    Eliminate { row: usize, col: usize, digit: u8 },
+    // Make a known square unknown again, as `SudokuBoard::clear` does
+    Clear { row: usize, col: usize },
+}
+
+/// A board together with the moves played on it, so they can be undone and
+/// redone.  Playing a new move forgets the moves that were undone.
+pub struct History {
+    board: SudokuBoard,
+    // moves played, each with the board as it was before the move
+    done: Vec<(Move, BoardState)>,
+    // moves undone, each with the board as it was after the move
+    undone: Vec<(Move, BoardState)>,
+}
+
+impl History {
+    pub fn new(board: SudokuBoard) -> History {
+        History {
+            board,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// The board after the moves played so far.
+    pub fn board(&self) -> &SudokuBoard {
+        &self.board
+    }
+
+    /// Stop recording moves and give back the board.
+    pub fn into_board(self) -> SudokuBoard {
+        self.board
+    }
+
+    /// Make a move.  A move that fails leaves the board and the history
+    /// unchanged.
+    pub fn play(&mut self, m: Move) -> SudokuResult {
+        let before = self.board.snapshot();
+        let result = match m {
+            Move::Place { row, col, digit } => {
+                CellPos::new(row, col).and_then(|pos| self.board.set(pos, digit))
+            }
+            Move::Eliminate { row, col, digit } => {
+                CellPos::new(row, col).and_then(|pos| self.board.eliminate(pos, digit))
+            }
+            Move::Clear { row, col } => {
+                CellPos::new(row, col).and_then(|pos| self.board.clear(pos))
+            }
+        };
+        match result {
+            Ok(()) => {
+                self.done.push((m, before));
+                self.undone.clear();
+                Ok(())
+            }
+            Err(e) => {
+                self.board.restore(&before);
+                Err(e)
+            }
+        }
+    }
+
+    /// Take back the last move played.  Returns the move, or None when there
+    /// is nothing to undo.
+    pub fn undo(&mut self) -> Option<Move> {
+        let (m, before) = self.done.pop()?;
+        self.undone.push((m, self.board.snapshot()));
+        self.board.restore(&before);
+        Some(m)
+    }
+
+    /// Play the last move undone again.  Returns the move, or None when there
+    /// is nothing to redo.
+    pub fn redo(&mut self) -> Option<Move> {
+        let (m, after) = self.undone.pop()?;
+        self.done.push((m, self.board.snapshot()));
+        self.board.restore(&after);
+        Some(m)
+    }
+
+    /// The moves played so far, oldest first.
+    pub fn moves(&self) -> impl Iterator<Item = &Move> {
+        self.done.iter().map(|(m, _)| m)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, Move};
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_undo_redo() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let start = SudokuBoard::fill_board(&s).unwrap().print_possibility();
+        let mut history = History::new(SudokuBoard::fill_board(&s).unwrap());
+
+        let place = Move::Place {
+            row: 2,
+            col: 2,
+            digit: 7,
+        };
+        let eliminate = Move::Eliminate {
+            row: 9,
+            col: 9,
+            digit: 1,
+        };
+        history.play(place).unwrap();
+        history.play(eliminate).unwrap();
+        // a failed move is not recorded
+        assert!(history.play(place).is_err());
+        assert_eq!(history.moves().count(), 2);
+        let after = history.board().print_possibility();
+
+        assert_eq!(history.undo(), Some(eliminate));
+        assert_eq!(history.undo(), Some(place));
+        assert_eq!(history.undo(), None);
+        assert_eq!(history.board().print_possibility(), start);
+
+        assert_eq!(history.redo(), Some(place));
+        assert_eq!(history.redo(), Some(eliminate));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.board().print_possibility(), after);
+
+        // playing a new move forgets what was undone
+        history.undo();
+        history.play(Move::Clear { row: 2, col: 2 }).unwrap();
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.into_board().get(at(2, 2)), None);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{BoxValue, SudokuBoard};
+
+/// Styles for the tables `to_html_table` writes, scoped to their `sudoku`
+/// class.  `to_html` includes them.
+pub const HTML_STYLE: &str = "<style>
+.sudoku { border-collapse: collapse; border: 3px solid #000; font-family: sans-serif; }
+.sudoku td { width: 2.2em; height: 2.2em; padding: 0; border: 1px solid #999; text-align: center; font-size: 1.4em; }
+.sudoku td:nth-child(3n) { border-right: 3px solid #000; }
+.sudoku tr:nth-child(3n) td { border-bottom: 3px solid #000; }
+.sudoku .given { font-weight: bold; }
+.sudoku .solved { color: #1a5fb4; }
+.sudoku .candidates div { display: grid; grid-template-columns: repeat(3, 1fr); font-size: 0.4em; color: #666; }
+</style>";
+
+/// Escape the characters that have a meaning in HTML text.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl SudokuBoard {
+    /// Write the board as an HTML table with its own styles, to paste into
+    /// a page.  Each square has the class `given`, `solved` for a square
+    /// filled in since, or `candidates` for an unknown one.  When
+    /// `candidates` is true, unknown squares list their candidates as pencil
+    /// marks, otherwise they are left empty.
+    pub fn to_html(&self, candidates: bool) -> String {
+        format!("{}\n{}", HTML_STYLE, self.to_html_table(candidates))
+    }
+
+    /// Write the board as `to_html` does, without the styles, for pages
+    /// showing several boards that include `HTML_STYLE` once.
+    pub fn to_html_table(&self, candidates: bool) -> String {
+        let mut lines = vec!["<table class=\"sudoku\">".to_string()];
+        for row in &self.board {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|node| match &node.value {
+                    BoxValue::Known(v) if node.given => format!("<td class=\"given\">{}</td>", v),
+                    BoxValue::Known(v) => format!("<td class=\"solved\">{}</td>", v),
+                    BoxValue::Unknown(set) if candidates => {
+                        let marks: String = (1..=9)
+                            .map(|d| {
+                                if set.contains(&d) {
+                                    format!("<span>{}</span>", d)
+                                } else {
+                                    "<span></span>".to_string()
+                                }
+                            })
+                            .collect();
+                        format!("<td class=\"candidates\"><div>{}</div></td>", marks)
+                    }
+                    BoxValue::Unknown(_) => "<td class=\"candidates\"></td>".to_string(),
+                })
+                .collect();
+            lines.push(format!("<tr>{}</tr>", cells.concat()));
+        }
+        lines.push("</table>".to_string());
+        lines.join("\n")
+    }
+
+    /// Write a whole HTML page showing the board as `to_html` does, under a
+    /// heading of `title`.
+    pub fn to_html_page(&self, title: &str, candidates: bool) -> String {
+        let title = escape(title);
+        format!(
+            concat!(
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+                "<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n{}\n</body>\n</html>\n"
+            ),
+            title,
+            title,
+            self.to_html(candidates)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_to_html() {
+        let s = "53--7----6--195----98----6-".to_string() + &"-".repeat(54);
+        let mut board = SudokuBoard::fill_board(&s).unwrap();
+        board.set(at(1, 3), 4).unwrap();
+        let html = board.to_html(false);
+        assert!(html.starts_with("<style>"));
+        let rows: Vec<&str> = html.lines().filter(|l| l.starts_with("<tr>")).collect();
+        assert_eq!(rows.len(), 9);
+        assert!(rows[0].starts_with(concat!(
+            "<tr><td class=\"given\">5</td><td class=\"given\">3</td>",
+            "<td class=\"solved\">4</td><td class=\"candidates\"></td>"
+        )));
+        assert_eq!(html.matches("<td").count(), 81);
+
+        // r1c4 can be 2, 6, or 8
+        let html = board.to_html(true);
+        let marks = "<span></span><span>2</span><span></span><span></span><span></span>\
+                     <span>6</span><span></span><span>8</span><span></span>";
+        assert!(html.contains(&format!(
+            "<td class=\"candidates\"><div>{}</div></td>",
+            marks
+        )));
+
+        assert_eq!(
+            board.to_html(false),
+            format!("{}\n{}", super::HTML_STYLE, board.to_html_table(false))
+        );
+        let page = board.to_html_page("Puzzle <1>", false);
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>Puzzle &lt;1&gt;</title>"));
+        assert!(page.contains(&board.to_html(false)));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{BoxValue, SudokuBoard};
+
+/// The digits 1 to 9 drawn on 5x7 dots, one row of dots per byte with the
+/// leftmost dot in bit 4.
+const FONT: [[u8; 7]; 9] = [
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+];
+
+const BLACK: [u8; 3] = [0, 0, 0];
+const BLUE: [u8; 3] = [0x1a, 0x5f, 0xb4];
+const GREY: [u8; 3] = [0x66, 0x66, 0x66];
+
+/// Room around the grid so the thick outer lines are not cut off.
+const MARGIN: u32 = 2;
+
+/// An RGB image being drawn.
+struct Canvas {
+    size: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 3]) {
+        for py in y..(y + height).min(self.size) {
+            for px in x..(x + width).min(self.size) {
+                let at = ((py * self.size + px) * 3) as usize;
+                self.pixels[at..at + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Draw a digit with its dots `dot` pixels square, centered on (x, y).
+    /// A bold digit has wider dots.
+    fn digit(&mut self, digit: i32, x: u32, y: u32, dot: u32, bold: bool, color: [u8; 3]) {
+        let dot = dot.max(1);
+        let (left, top) = (x.saturating_sub(5 * dot / 2), y.saturating_sub(7 * dot / 2));
+        let width = if bold { dot + dot.div_ceil(3) } else { dot };
+        for (r, bits) in FONT[digit as usize - 1].iter().enumerate() {
+            for c in 0..5 {
+                if bits & (0x10 >> c) != 0 {
+                    self.fill(left + c * dot, top + r as u32 * dot, width, dot, color);
+                }
+            }
+        }
+    }
+}
+
+impl SudokuBoard {
+    /// Draw the board as a PNG image, with squares `cell_size` pixels wide,
+    /// the way `to_svg` draws it.  When `candidates` is true, unknown squares
+    /// show their candidates as small digits.  The digits use a built in
+    /// font, so no font files are needed.
+    pub fn to_png(&self, cell_size: u32, candidates: bool) -> Vec<u8> {
+        let cell = cell_size.max(9);
+        let size = 9 * cell + 2 * MARGIN;
+        let mut canvas = Canvas {
+            size,
+            pixels: vec![0xff; (size * size * 3) as usize],
+        };
+        for node in self.board.iter().flatten() {
+            let x = MARGIN + (node.col as u32 - 1) * cell;
+            let y = MARGIN + (node.row as u32 - 1) * cell;
+            match &node.value {
+                BoxValue::Known(v) => {
+                    let color = if node.given { BLACK } else { BLUE };
+                    canvas.digit(
+                        *v,
+                        x + cell / 2,
+                        y + cell / 2,
+                        cell * 3 / 35,
+                        node.given,
+                        color,
+                    );
+                }
+                BoxValue::Unknown(set) if candidates => {
+                    for d in set {
+                        let (r, c) = ((*d as u32 - 1) / 3, (*d as u32 - 1) % 3);
+                        let (dx, dy) = (cell * (2 * c + 1) / 6, cell * (2 * r + 1) / 6);
+                        canvas.digit(*d, x + dx, y + dy, cell / 28, false, GREY);
+                    }
+                }
+                BoxValue::Unknown(_) => (),
+            }
+        }
+        for i in 0..=9 {
+            let width = if i % 3 == 0 { 3 } else { 1 };
+            let at = (MARGIN + i * cell).saturating_sub(width / 2);
+            canvas.fill(at, MARGIN - 1, width, 9 * cell + 2, BLACK);
+            canvas.fill(MARGIN - 1, at, 9 * cell + 2, width, BLACK);
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, size, size);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        // writing to memory cannot fail
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&canvas.pixels))
+            .expect("PNG written to memory");
+        png
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    /// Decode a PNG, returning its width and RGB pixels.
+    fn decode(png: &[u8]) -> (u32, Vec<u8>) {
+        let mut reader = png::Decoder::new(png).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(info.width, info.height);
+        (info.width, pixels)
+    }
+
+    #[test]
+    fn test_to_png() {
+        let s = "5".to_string() + &"-".repeat(80);
+        let mut board = SudokuBoard::fill_board(&s).unwrap();
+        board.set(at(1, 2), 4).unwrap();
+        let (size, pixels) = decode(&board.to_png(40, false));
+        assert_eq!(size, 364);
+        let pixel = |x: u32, y: u32| {
+            let at = ((y * size + x) * 3) as usize;
+            [pixels[at], pixels[at + 1], pixels[at + 2]]
+        };
+        // the thick line between the 3x3 squares, and an empty square
+        assert_eq!(pixel(122, 200), [0, 0, 0]);
+        assert_eq!(pixel(142, 142), [0xff, 0xff, 0xff]);
+        let colors = |col: u32| {
+            let (x, y) = (2 + (col - 1) * 40, 2);
+            (x + 2..x + 38).flat_map(move |px| (y + 2..y + 38).map(move |py| (px, py)))
+        };
+        // the given is black, the square filled in blue
+        assert!(colors(1).any(|(x, y)| pixel(x, y) == [0, 0, 0]));
+        assert!(colors(2).any(|(x, y)| pixel(x, y) == [0x1a, 0x5f, 0xb4]));
+        assert!(colors(3).all(|(x, y)| pixel(x, y) == [0xff, 0xff, 0xff]));
+
+        // r1c3 can be 1, drawn small in the top left corner, but not 4 on
+        // the left of the middle
+        let (_, pixels) = decode(&board.to_png(40, true));
+        let grey = |x: u32, y: u32| {
+            let at = ((y * size + x) * 3) as usize;
+            pixels[at..at + 3] == [0x66, 0x66, 0x66]
+        };
+        assert!((84..92).any(|x| (4..14).any(|y| grey(x, y))));
+        assert!(!(84..92).any(|x| (17..28).any(|y| grey(x, y))));
+    }
+}
@@ -0,0 +1,307 @@
+use crate::SudokuError;
+
+/// Quote and escape a string as a JSON string.
+pub fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// How deeply arrays and objects may nest in a document.
+const MAX_DEPTH: usize = 64;
+
+/// A JSON value, as far as puzzles and requests need one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// The member called `key` of an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Write the value back as JSON text.
+    pub fn write(&self) -> String {
+        match self {
+            Json::Null => "null".to_string(),
+            Json::Bool(b) => b.to_string(),
+            Json::Number(n) => n.to_string(),
+            Json::String(s) => json_string(s),
+            Json::Array(items) => {
+                let items: Vec<String> = items.iter().map(Json::write).collect();
+                format!("[{}]", items.join(","))
+            }
+            Json::Object(members) => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|(k, v)| format!("{}:{}", json_string(k), v.write()))
+                    .collect();
+                format!("{{{}}}", members.join(","))
+            }
+        }
+    }
+}
+
+/// Reads one JSON value from text.
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    // Arrays and objects entered and not yet left
+    depth: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn skip_space(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_space();
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+            None => Err(format!("expected `{}`, found the end", expected)),
+        }
+    }
+
+    fn word(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        for expected in word.chars() {
+            if self.chars.next() != Some(expected) {
+                return Err(format!("expected `{}`", word));
+            }
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_space();
+        match self.chars.peek() {
+            Some('n') => self.word("null", Json::Null),
+            Some('t') => self.word("true", Json::Bool(true)),
+            Some('f') => self.word("false", Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => self.nested(Self::array),
+            Some('{') => self.nested(Self::object),
+            Some(c) if *c == '-' || c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(c) = self
+                    .chars
+                    .next_if(|c| c.is_ascii_digit() || "+-.eE".contains(*c))
+                {
+                    number.push(c);
+                }
+                number
+                    .parse()
+                    .map(Json::Number)
+                    .map_err(|_| format!("invalid number `{}`", number))
+            }
+            Some(c) => Err(format!("unexpected `{}`", c)),
+            None => Err("unexpected end".to_string()),
+        }
+    }
+
+    /// Parse an array or object one level deeper, refusing to go deeper
+    /// than `MAX_DEPTH` so a line of brackets cannot overflow the stack.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json, String>) -> Result<Json, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!("nested deeper than {} levels", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.chars.next();
+        let mut items = Vec::new();
+        self.skip_space();
+        if self.chars.next_if_eq(&']').is_some() {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_space();
+            match self.chars.next() {
+                Some(',') => (),
+                Some(']') => return Ok(Json::Array(items)),
+                _ => return Err("expected `,` or `]`".to_string()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.chars.next();
+        let mut members = Vec::new();
+        self.skip_space();
+        if self.chars.next_if_eq(&'}').is_some() {
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_space();
+            let key = self.string()?;
+            self.expect(':')?;
+            members.push((key, self.value()?));
+            self.skip_space();
+            match self.chars.next() {
+                Some(',') => (),
+                Some('}') => return Ok(Json::Object(members)),
+                _ => return Err("expected `,` or `}`".to_string()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.chars.next() {
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('u') => s.push(self.unicode_escape()?),
+                    Some(c) => s.push(c),
+                    None => return Err("unterminated string".to_string()),
+                },
+                Some(c) => s.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    /// The character of a `\u` escape, its 4 hex digits next.  A high
+    /// surrogate has to be followed by a low one, and the pair makes one
+    /// character.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let unit = self.hex_unit()?;
+        let code = if (0xd800..0xdc00).contains(&unit) {
+            let low = match (self.chars.next(), self.chars.next()) {
+                (Some('\\'), Some('u')) => self.hex_unit()?,
+                _ => return Err(format!("unpaired surrogate `\\u{:04x}`", unit)),
+            };
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(format!("unpaired surrogate `\\u{:04x}`", unit));
+            }
+            0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            unit
+        };
+        char::from_u32(code).ok_or_else(|| format!("invalid escape `\\u{:04x}`", unit))
+    }
+
+    /// The 4 hex digits of a `\u` escape.
+    fn hex_unit(&mut self) -> Result<u32, String> {
+        let hex: String = self.chars.by_ref().take(4).collect();
+        match u32::from_str_radix(&hex, 16) {
+            Ok(unit) if hex.len() == 4 && hex.chars().all(|c| c.is_ascii_hexdigit()) => Ok(unit),
+            _ => Err(format!("invalid escape `\\u{}`", hex)),
+        }
+    }
+}
+
+impl Json {
+    /// Read text holding exactly one JSON value.
+    pub fn parse(s: &str) -> Result<Json, SudokuError> {
+        let mut values = Json::parse_many(s)?;
+        match values.len() {
+            1 => Ok(values.remove(0)),
+            0 => Err(SudokuError::InvalidJson("unexpected end".to_string())),
+            _ => Err(SudokuError::InvalidJson(
+                "unexpected value after the first".to_string(),
+            )),
+        }
+    }
+
+    /// Read text holding any number of JSON values one after the other,
+    /// such as JSON lines.
+    pub fn parse_many(s: &str) -> Result<Vec<Json>, SudokuError> {
+        let mut parser = JsonParser {
+            chars: s.chars().peekable(),
+            depth: 0,
+        };
+        let mut values = Vec::new();
+        loop {
+            parser.skip_space();
+            if parser.chars.peek().is_none() {
+                return Ok(values);
+            }
+            values.push(parser.value().map_err(SudokuError::InvalidJson)?);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{json_string, Json};
+
+    #[test]
+    fn test_parse_json() {
+        let value = Json::parse(r#" {"a": [1, -2.5e1, true, null], "b\nA": {}} "#).unwrap();
+        assert_eq!(
+            value,
+            Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::Number(-25.0),
+                        Json::Bool(true),
+                        Json::Null
+                    ])
+                ),
+                ("b\nA".to_string(), Json::Object(Vec::new())),
+            ])
+        );
+        assert_eq!(value.write(), r#"{"a":[1,-25,true,null],"b\nA":{}}"#);
+        assert!(Json::parse("{\"a\": 1").is_err());
+        assert_eq!(Json::parse_many("{} [1]\n2").unwrap().len(), 3);
+        assert!(Json::parse("[1] 2").is_err());
+        assert!(Json::parse("\"abc").is_err());
+
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Json::parse(&nested(64)).is_ok());
+        assert!(Json::parse(&nested(65)).is_err());
+        assert!(Json::parse(&"[".repeat(200000)).is_err());
+
+        assert_eq!(
+            Json::parse(r#""\ud83d\ude00 \u00e9""#).unwrap(),
+            Json::String("\u{1f600} \u{e9}".to_string())
+        );
+        assert!(Json::parse(r#""\ud83d""#).is_err());
+        assert!(Json::parse(r#""\ud83d\u0041""#).is_err());
+        assert!(Json::parse(r#""\ude00""#).is_err());
+        assert!(Json::parse(r#""\u+041""#).is_err());
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("Grid 01"), "\"Grid 01\"");
+        assert_eq!(json_string("a\"b\\c\n\u{1}"), "\"a\\\"b\\\\c\\n\\u0001\"");
+        assert_eq!(json_string("\r\t"), "\"\\r\\t\"");
+    }
+}
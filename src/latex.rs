@@ -0,0 +1,61 @@
+use crate::{BoxValue, SudokuBoard};
+
+impl SudokuBoard {
+    /// Write the board as a LaTeX `tabular`, ready to `\input` into a
+    /// worksheet.  Unknown squares are left empty and the givens are bold,
+    /// so a solved board prints as its solution with the puzzle standing
+    /// out.  The 3x3 squares are framed with double lines, and no packages
+    /// are needed.
+    pub fn to_latex(&self) -> String {
+        let mut lines = vec![
+            "\\begin{tabular}{|c|c|c||c|c|c||c|c|c|}".to_string(),
+            "\\hline".to_string(),
+        ];
+        for (r, row) in self.board.iter().enumerate() {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|node| match node.value {
+                    BoxValue::Known(v) if node.given => format!("\\textbf{{{}}}", v),
+                    BoxValue::Known(v) => v.to_string(),
+                    BoxValue::Unknown(_) => "\\phantom{0}".to_string(),
+                })
+                .collect();
+            lines.push(format!("{} \\\\", cells.join(" & ")));
+            lines.push(match r {
+                2 | 5 => "\\hline\\hline".to_string(),
+                _ => "\\hline".to_string(),
+            });
+        }
+        lines.push("\\end{tabular}".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_to_latex() {
+        let s = "53--7----".to_string() + &"-".repeat(72);
+        let puzzle = SudokuBoard::fill_board(&s).unwrap();
+        let latex = puzzle.to_latex();
+        let lines: Vec<&str> = latex.lines().collect();
+        assert_eq!(lines.len(), 21);
+        assert_eq!(lines[0], "\\begin{tabular}{|c|c|c||c|c|c||c|c|c|}");
+        assert_eq!(
+            lines[2],
+            "\\textbf{5} & \\textbf{3} & \\phantom{0} & \\phantom{0} & \\textbf{7} & \
+             \\phantom{0} & \\phantom{0} & \\phantom{0} & \\phantom{0} \\\\"
+        );
+        assert_eq!(lines[7], "\\hline\\hline");
+        assert_eq!(lines[20], "\\end{tabular}");
+
+        // the solution shows the squares filled in without bold
+        let mut solved = puzzle.clone();
+        solved.solve_first().unwrap();
+        let latex = solved.to_latex();
+        assert!(!latex.contains("phantom"));
+        assert_eq!(latex.matches("textbf").count(), 3);
+    }
+}
@@ -1,4 +1,115 @@
 use std::collections::BTreeSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use observe::Observer;
+use random::Rng;
+
+#[cfg(feature = "analysis")]
+mod backdoor;
+#[cfg(feature = "analysis")]
+mod backjump;
+#[cfg(feature = "rayon")]
+mod batch;
+mod builder;
+#[cfg(feature = "analysis")]
+mod certificate;
+#[cfg(feature = "color")]
+mod color;
+mod convert;
+mod detect;
+#[cfg(feature = "variants")]
+mod dlx;
+mod edit;
+mod encode;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+#[cfg(feature = "generator")]
+mod generate;
+mod hint;
+mod history;
+#[cfg(feature = "render")]
+mod html;
+#[cfg(feature = "image")]
+mod image;
+mod json;
+#[cfg(feature = "render")]
+mod latex;
+mod logic;
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "analysis")]
+mod mutate;
+mod observe;
+mod order;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod parse;
+mod path;
+mod pos;
+mod random;
+#[cfg(feature = "analysis")]
+mod rating;
+mod render;
+#[cfg(feature = "puzzles")]
+mod samples;
+#[cfg(feature = "serde")]
+mod serialize;
+mod snapshot;
+mod solution;
+mod solver;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+mod stream;
+#[cfg(feature = "render")]
+mod svg;
+mod symmetry;
+#[cfg(feature = "async")]
+mod task;
+mod trace;
+#[cfg(feature = "analysis")]
+mod unavoidable;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "rayon")]
+pub use batch::solve_batch;
+pub use builder::SudokuBoardBuilder;
+#[cfg(feature = "analysis")]
+pub use certificate::Certificate;
+#[cfg(feature = "color")]
+pub use color::color_enabled;
+pub use detect::InputFormat;
+#[cfg(feature = "variants")]
+pub use dlx::Variant;
+pub use edit::ClueEditor;
+pub use encode::BINARY_VERSION;
+#[cfg(feature = "generator")]
+pub use generate::{Generator, GeneratorOptions, RatedPuzzle};
+pub use hint::{Hint, HintLevel};
+pub use history::{History, Move};
+#[cfg(feature = "render")]
+pub use html::HTML_STYLE;
+pub use json::{json_string, Json};
+pub use logic::{Difficulty, Hardness, HouseId, Step, Technique, TechniqueReport, GRADE_REPEATS};
+#[cfg(feature = "mmap")]
+pub use mapped::{MappedPuzzles, SolvedRecord};
+pub use observe::{Cause, Event};
+pub use order::SolveOrder;
+pub use parse::{is_blank, ParseError, ParseErrorKind};
+pub use path::{PathStep, Replay, SolvePath};
+pub use pos::{CellPos, Col, Row};
+#[cfg(feature = "analysis")]
+pub use rating::SeRating;
+#[cfg(feature = "puzzles")]
+pub use samples::{samples, Sample};
+pub use snapshot::BoardState;
+pub use solution::{Solution, SolveOutcome};
+pub use solver::{Solver, SolverOptions};
+pub use stream::{solve_stream, StreamStats};
+#[cfg(feature = "render")]
+pub use svg::SvgOptions;
+pub use symmetry::Symmetry;
 
 pub type SudokuResult = Result<(), SudokuError>;
 
@@ -34,23 +145,171 @@ fn char_from32(v: i32) -> Option<char> {
     }
 }
 
+/// The squares sharing a row, column, or box with each square.
+#[cfg(feature = "analysis")]
+pub(crate) fn peer_table() -> Vec<Vec<usize>> {
+    (0..81)
+        .map(|i| {
+            (0..81)
+                .filter(|&j| {
+                    j != i
+                        && (i / 9 == j / 9
+                            || i % 9 == j % 9
+                            || (i / 27 == j / 27 && i % 9 / 3 == j % 9 / 3))
+                })
+                .collect()
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub enum SudokuError {
     // A value specified is outside the valid range
     InvalidRange,
-    // The solver found this board in not solvable
-    NotSolvable,
+    // The solver found this board in not solvable.  Holds the square that
+    // ran out of alternatives when that is how it was found
+    NotSolvable(Option<Wipeout>),
     // The solver has many options and does not know which one to choose
-    TooManyOptions,
+    TooManyOptions {
+        row: usize,
+        col: usize,
+    },
     // This option is already known, but we are trying to mark it again
-    AlreadyKnown,
+    AlreadyKnown {
+        row: usize,
+        col: usize,
+    },
+    // The digit is not an alternative of the square.  Holds the known peer,
+    // (row, col), that rules it out, or None when it was eliminated
+    Conflict {
+        row: usize,
+        col: usize,
+        digit: u8,
+        with: Option<(usize, usize)>,
+    },
     // The board is not fully solved.  It branches and needs help
     NoFullySolved,
+    // No solution was found without nesting more than `depth` guesses.
+    // Holds the board as far as it could be solved without guessing
+    DepthLimit {
+        depth: usize,
+        board: Box<SudokuBoard>,
+    },
+    // The same digit is given in two squares, (row, col), of one house
+    ConflictingGivens {
+        digit: u8,
+        first: (usize, usize),
+        second: (usize, usize),
+    },
+    // The input did not describe exactly 81 cells.  Holds the number of cells found
+    InvalidCellCount(usize),
+    // The input contains a character that is neither a cell nor decoration
+    InvalidCharacter(char),
+    // Strict parsing found a problem at a known position in the input
+    Parse(ParseError),
+    // The text is not valid JSON, or holds no puzzle where one was expected
+    InvalidJson(String),
+    // The binary encoding is truncated or holds values outside the valid range
+    InvalidEncoding,
+    // The binary encoding was written by a newer, unknown format version
+    UnsupportedVersion(u8),
+    // The square is not one of the givens of the puzzle
+    NotGiven {
+        row: usize,
+        col: usize,
+    },
+    // The puzzle has more than one solution
+    MultipleSolutions,
     // unknown error
     Unknown,
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Display for SudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SudokuError::InvalidRange => write!(f, "value out of range"),
+            SudokuError::NotSolvable(None) => write!(f, "the board has no solution"),
+            SudokuError::NotSolvable(Some(w)) => write!(f, "the board has no solution: {}", w),
+            SudokuError::TooManyOptions { row, col } => {
+                write!(f, "r{}c{} has more than one alternative", row, col)
+            }
+            SudokuError::AlreadyKnown { row, col } => {
+                write!(f, "r{}c{} is already known", row, col)
+            }
+            SudokuError::Conflict {
+                row,
+                col,
+                digit,
+                with: Some((r, c)),
+            } => write!(f, "{} in r{}c{} clashes with r{}c{}", digit, row, col, r, c),
+            SudokuError::Conflict {
+                row,
+                col,
+                digit,
+                with: None,
+            } => write!(f, "{} is not an alternative of r{}c{}", digit, row, col),
+            SudokuError::NoFullySolved => write!(f, "the board is not fully solved"),
+            SudokuError::DepthLimit { depth, .. } => {
+                write!(f, "no solution found within {} nested guesses", depth)
+            }
+            SudokuError::ConflictingGivens {
+                digit,
+                first,
+                second,
+            } => write!(
+                f,
+                "{} is given in both r{}c{} and r{}c{}",
+                digit, first.0, first.1, second.0, second.1
+            ),
+            SudokuError::InvalidCellCount(n) => write!(f, "expected 81 squares, found {}", n),
+            SudokuError::InvalidCharacter(c) => write!(f, "unexpected character {:?}", c),
+            SudokuError::Parse(e) => write!(f, "{}", e),
+            SudokuError::InvalidJson(e) => write!(f, "invalid JSON: {}", e),
+            SudokuError::InvalidEncoding => write!(f, "the encoding is truncated or invalid"),
+            SudokuError::UnsupportedVersion(v) => write!(f, "unsupported encoding version {}", v),
+            SudokuError::NotGiven { row, col } => write!(f, "r{}c{} is not a given", row, col),
+            SudokuError::MultipleSolutions => write!(f, "the puzzle has more than one solution"),
+            SudokuError::Unknown => write!(f, "unknown error"),
+        }
+    }
+}
+
+impl std::error::Error for SudokuError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SudokuError::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A square left without alternatives, and what removed the last one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wipeout {
+    pub row: usize,
+    pub col: usize,
+    // The last alternative the square lost
+    pub digit: u8,
+    // A digit placed in the same house, or the technique of a step
+    pub cause: Cause,
+}
+
+impl fmt::Display for Wipeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "r{}c{} has no alternatives left once {} is ",
+            self.row, self.col, self.digit
+        )?;
+        match self.cause {
+            Cause::Peer { row, col } => write!(f, "placed in r{}c{}", row, col),
+            Cause::Technique(t) => write!(f, "removed by {}", t.name()),
+            Cause::Single | Cause::Guess => write!(f, "removed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BoxValue {
     Known(i32),
     Unknown(BTreeSet<i32>),
@@ -68,9 +327,22 @@ pub struct Node {
     pub row: usize,
     pub col: usize,
     pub value: BoxValue,
+    // The value was given when the board was created, not found by the solver
+    given: bool,
 }
 
 impl Node {
+    /// Whether the value was given when the board was created, rather than
+    /// found by the solver.
+    pub fn is_given(&self) -> bool {
+        self.given
+    }
+
+    /// The position of the square.
+    pub fn pos(&self) -> CellPos {
+        CellPos::new(self.row, self.col).expect("nodes are created on the board")
+    }
+
     /// Get the square number in the sudoku grid.
     ///
     /// The sudoku grid is split into 3x3 grids:
@@ -109,6 +381,19 @@ impl Node {
     }
 }
 
+/// Solutions `sample_solution` counts under each alternative of a guess.
+/// Alternatives with more are weighted as if they had this many.
+const SAMPLE_LIMIT: usize = 64;
+
+/// Counters collected while solving a board.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SolveStats {
+    // Number of values tried in squares that had more than one alternative
+    pub guesses: usize,
+    // Number of those guesses that turned out to be wrong
+    pub backtracks: usize,
+}
+
 #[derive(Clone)]
 pub struct SudokuBoard {
     board: Vec<Vec<Node>>,
@@ -127,6 +412,7 @@ impl SudokuBoard {
                     row: row + 1,
                     col: col + 1,
                     value: BoxValue::init_unknown(),
+                    given: false,
                 };
                 board.get_mut(row).unwrap().push(node);
             }
@@ -140,7 +426,8 @@ impl SudokuBoard {
 
     /// Initialize the board given a string.  The string is a sequence of numeric characters.
     /// Non-numeric characters are ignored.  It is filled from top to bottom left to right.
-    pub fn fill_board(s: &String) -> Result<SudokuBoard, SudokuError> {
+    pub fn fill_board(s: &str) -> Result<SudokuBoard, SudokuError> {
+        let _span = trace::parse_span("fill");
         let mut board = SudokuBoard::new();
 
         for (i, c) in s
@@ -163,9 +450,8 @@ impl SudokuBoard {
             let row = i / 9 + 1;
             let col = i % 9 + 1;
             let value = i32_from_char(c);
-            match value {
-                Some(know_value) => board.mark_as_known(row, col, know_value)?,
-                None => (),
+            if let Some(know_value) = value {
+                board.mark_as_given(row, col, know_value)?;
             }
         }
         Ok(board)
@@ -182,43 +468,332 @@ impl SudokuBoard {
             .collect::<String>()
     }
 
+    /// Mark which squares were given when the board was created.  Givens are
+    /// `G`, squares filled in since then are `S`, and unknown squares are `-`.
+    pub fn print_given_mask(&self) -> String {
+        self.board
+            .iter()
+            .flatten()
+            .map(|v| match v.value {
+                BoxValue::Known(_) if v.given => 'G',
+                BoxValue::Known(_) => 'S',
+                BoxValue::Unknown(_) => '-',
+            })
+            .collect::<String>()
+    }
+
+    /// Get the board as `print_board` does together with the matching
+    /// `print_given_mask`, so renderers can style the clues differently.
+    pub fn print_board_with_mask(&self) -> (String, String) {
+        (self.print_board(), self.print_given_mask())
+    }
+
     pub fn print_possibility(&self) -> String {
         self.board
             .iter()
             .flatten()
             .map(|v| match &v.value {
                 BoxValue::Known(_) => 'K',
-                BoxValue::Unknown(v) => {
-                    char_from32((v.len() as usize).try_into().unwrap()).unwrap()
-                }
+                BoxValue::Unknown(v) => char_from32(v.len().try_into().unwrap()).unwrap(),
             })
             .collect::<String>()
     }
 
-    // if this value has a single item it will mark the known value.
-    fn mark_single_option(&mut self, row: usize, col: usize) -> SudokuResult {
-        if row > 9 {
-            return SudokuResult::Err(SudokuError::InvalidRange);
+    /// Write the candidates of every square, row by row, as 81 groups of
+    /// digits separated by spaces.  A known square is its digit alone, so
+    /// `parse_pencil_marks` reads the board back.
+    pub fn print_pencil_marks(&self) -> String {
+        self.board
+            .iter()
+            .flatten()
+            .map(|node| match &node.value {
+                BoxValue::Known(v) => v.to_string(),
+                BoxValue::Unknown(set) => set.iter().map(|d| d.to_string()).collect(),
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Get the digit in a square, or None when it is not known yet.
+    pub fn get(&self, pos: CellPos) -> Option<u8> {
+        match self.cell(pos).value {
+            BoxValue::Known(v) => Some(v as u8),
+            BoxValue::Unknown(_) => None,
         }
-        if col > 9 {
-            return SudokuResult::Err(SudokuError::InvalidRange);
+    }
+
+    /// The square holds one of the givens the board was created with, as
+    /// opposed to a digit placed later by the solver or with `set`.
+    pub fn is_given(&self, pos: CellPos) -> bool {
+        self.cell(pos).given
+    }
+
+    /// The digits still possible in a square, smallest first.  A known
+    /// square has none.
+    pub fn candidates(&self, pos: CellPos) -> impl Iterator<Item = u8> {
+        let digits: Vec<u8> = match &self.cell(pos).value {
+            BoxValue::Known(_) => Vec::new(),
+            BoxValue::Unknown(v) => v.iter().map(|d| *d as u8).collect(),
+        };
+        digits.into_iter()
+    }
+
+    /// Every square is known.
+    pub fn is_solved(&self) -> bool {
+        self.unknown_values == 0
+    }
+
+    /// The number of known squares, givens included.
+    pub fn solved_count(&self) -> usize {
+        81 - self.unknown_count()
+    }
+
+    /// The number of squares not known yet.
+    pub fn unknown_count(&self) -> usize {
+        self.unknown_values as usize
+    }
+
+    /// The share of the squares that are known, from 0.0 for an empty board
+    /// to 1.0 for a solved one.
+    pub fn progress(&self) -> f32 {
+        self.solved_count() as f32 / 81.0
+    }
+
+    /// No row, column, or box holds the same known digit twice.
+    pub fn is_valid(&self) -> bool {
+        HouseId::all().all(|house| {
+            let mut seen = BTreeSet::new();
+            self.house(house).iter().all(|node| match node.value {
+                BoxValue::Known(v) => seen.insert(v),
+                BoxValue::Unknown(_) => true,
+            })
+        })
+    }
+
+    /// Every unknown square has at least one alternative left.
+    pub fn is_consistent(&self) -> bool {
+        self.unsolved_cells().all(|(_, _, n)| n > 0)
+    }
+
+    /// The row, column, and number of alternatives of every unknown square,
+    /// from left to right and top to bottom.
+    pub fn unsolved_cells(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.board.iter().flatten().filter_map(|v| match &v.value {
+            BoxValue::Unknown(set) => Some((v.row, v.col, set.len())),
+            BoxValue::Known(_) => None,
+        })
+    }
+
+    /// The squares of a house, in the order `HouseId::cells` lists them.
+    pub fn house(&self, house: HouseId) -> [&Node; 9] {
+        house.cells().map(|i| &self.board[i / 9][i % 9])
+    }
+
+    /// The squares of each row, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = [&Node; 9]> {
+        (1..=9).map(|r| self.house(HouseId::Row(r)))
+    }
+
+    /// The squares of each column, from left to right.
+    pub fn cols(&self) -> impl Iterator<Item = [&Node; 9]> {
+        (1..=9).map(|c| self.house(HouseId::Col(c)))
+    }
+
+    /// The squares of each 3x3 box, numbered as `Node::get_square` does.
+    pub fn boxes(&self) -> impl Iterator<Item = [&Node; 9]> {
+        (1..=9).map(|b| self.house(HouseId::Box(b)))
+    }
+
+    /// The square at a position.
+    pub fn cell(&self, pos: CellPos) -> &Node {
+        &self.board[pos.row.index()][pos.col.index()]
+    }
+
+    /// The digit can be placed in the square: the square is unknown and no
+    /// known square in its row, column, or box holds the digit.  Alternatives
+    /// already ruled out by the solver are not considered, and nothing is
+    /// changed.
+    pub fn is_legal(&self, pos: CellPos, digit: u8) -> bool {
+        matches!(self.cell(pos).value, BoxValue::Unknown(_))
+            && matches!(self.conflict(pos, digit), Ok(None))
+    }
+
+    /// The first square in the row, column, or box of a square that already
+    /// holds the digit, or None when the digit does not clash with anything
+    /// known.
+    pub fn conflict(&self, pos: CellPos, digit: u8) -> Result<Option<CellPos>, SudokuError> {
+        if !(1..=9).contains(&digit) {
+            return Err(SudokuError::InvalidRange);
         }
+        let square = self.clash(pos.row.get(), pos.col.get(), digit);
+        Ok(square.map(|(row, col)| CellPos::new(row, col).expect("peers are on the board")))
+    }
+
+    /// `conflict` by row and column, with the digit known to be in range.
+    pub(crate) fn clash(&self, row: usize, col: usize, digit: u8) -> Option<(usize, usize)> {
+        self.peers(row, col).into_iter().find(|(r, c)| {
+            matches!(self.board[r - 1][c - 1].value, BoxValue::Known(v) if v == digit as i32)
+        })
+    }
+
+    /// Place a digit in an unknown square, removing it from the alternatives
+    /// of the row, column, and box.  The board is left unchanged when the
+    /// digit is out of range, the square is known, or the digit is not one of
+    /// the square's alternatives; the last is a `Conflict` naming the known
+    /// peer that holds the digit, if any.
+    pub fn set(&mut self, pos: CellPos, value: u8) -> SudokuResult {
+        if !(1..=9).contains(&value) {
+            return Err(SudokuError::InvalidRange);
+        }
+        let (row, col) = (pos.row.get(), pos.col.get());
+        match &self.cell(pos).value {
+            BoxValue::Known(_) => Err(SudokuError::AlreadyKnown { row, col }),
+            BoxValue::Unknown(v) if !v.contains(&(value as i32)) => Err(SudokuError::Conflict {
+                row,
+                col,
+                digit: value,
+                with: self.clash(row, col, value),
+            }),
+            BoxValue::Unknown(_) => {
+                // marking can fail half way through when a peer runs out of
+                // alternatives, so work on a copy
+                let mut board = self.clone();
+                board.mark_as_known(row, col, value as i32)?;
+                *self = board;
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove a digit from the alternatives of an unknown square.  Removing
+    /// a digit that is not an alternative does nothing, and the last
+    /// alternative of a square cannot be removed.
+    pub fn eliminate(&mut self, pos: CellPos, value: u8) -> SudokuResult {
+        if !(1..=9).contains(&value) {
+            return Err(SudokuError::InvalidRange);
+        }
+        let (row, col) = (pos.row.get(), pos.col.get());
+        match &mut self.board[row - 1][col - 1].value {
+            BoxValue::Known(_) => Err(SudokuError::AlreadyKnown { row, col }),
+            BoxValue::Unknown(v) if v.len() == 1 && v.contains(&(value as i32)) => {
+                Err(SudokuError::NotSolvable(None))
+            }
+            BoxValue::Unknown(v) => {
+                v.remove(&(value as i32));
+                Ok(())
+            }
+        }
+    }
+
+    /// Make a known square unknown again.  Its alternatives are the digits
+    /// not known elsewhere in its row, column, and box, and its digit becomes
+    /// an alternative again in the peers it no longer conflicts with.
+    /// Clearing an unknown square does nothing.
+    pub fn clear(&mut self, pos: CellPos) -> SudokuResult {
+        let (row, col) = (pos.row.get(), pos.col.get());
+        let digit = match self.cell(pos).value {
+            BoxValue::Known(v) => v,
+            BoxValue::Unknown(_) => return Ok(()),
+        };
+        let known = self.peer_digits(row, col);
+        let node = &mut self.board[row - 1][col - 1];
+        node.value = BoxValue::Unknown((1..=9).filter(|d| !known.contains(d)).collect());
+        node.given = false;
+        self.unknown_values += 1;
+
+        for (r, c) in self.peers(row, col) {
+            if self.peer_digits(r, c).contains(&digit) {
+                continue;
+            }
+            if let BoxValue::Unknown(v) = &mut self.board[r - 1][c - 1].value {
+                v.insert(digit);
+            }
+        }
+        Ok(())
+    }
+
+    /// Clear every square that is not a given and work out the alternatives
+    /// again from the givens alone, as if the puzzle had just been loaded.
+    /// The board is left unchanged when the givens contradict each other.
+    pub fn reset_to_givens(&mut self) -> SudokuResult {
+        let mut board = SudokuBoard::new();
+        for node in self.board.iter().flatten().filter(|node| node.given) {
+            if let BoxValue::Known(v) = node.value {
+                board.mark_as_given(node.row, node.col, v)?;
+            }
+        }
+        *self = board;
+        Ok(())
+    }
+
+    /// The squares sharing a row, column, or box with a square, not counting
+    /// the square itself.
+    fn peers(&self, row: usize, col: usize) -> BTreeSet<(usize, usize)> {
+        let square = self.board[row - 1][col - 1].get_square();
+        [HouseId::Row(row), HouseId::Col(col), HouseId::Box(square)]
+            .iter()
+            .flat_map(|house| house.cells())
+            .map(|i| (i / 9 + 1, i % 9 + 1))
+            .filter(|cell| *cell != (row, col))
+            .collect()
+    }
+
+    /// The digits known in the peers of a square.
+    fn peer_digits(&self, row: usize, col: usize) -> BTreeSet<i32> {
+        self.peers(row, col)
+            .into_iter()
+            .filter_map(|(r, c)| match self.board[r - 1][c - 1].value {
+                BoxValue::Known(v) => Some(v),
+                BoxValue::Unknown(_) => None,
+            })
+            .collect()
+    }
+
+    // if this value has a single item it will mark the known value.
+    fn mark_single_option(&mut self, row: usize, col: usize, observer: Observer) -> SudokuResult {
+        CellPos::new(row, col)?;
 
         // get the value we will mark it as known
         let known_value = match &self.board.get(row - 1).unwrap().get(col - 1).unwrap().value {
-            BoxValue::Known(_) => return SudokuResult::Err(SudokuError::AlreadyKnown),
+            BoxValue::Known(_) => return SudokuResult::Err(SudokuError::AlreadyKnown { row, col }),
             BoxValue::Unknown(v) => {
                 if v.is_empty() {
-                    return SudokuResult::Err(SudokuError::NotSolvable);
+                    return SudokuResult::Err(SudokuError::NotSolvable(None));
                 }
                 if v.len() != 1 {
-                    return SudokuResult::Err(SudokuError::TooManyOptions);
+                    return SudokuResult::Err(SudokuError::TooManyOptions { row, col });
                 }
                 // We have checked that there will be exactly one item in the set
-                v.first().unwrap().clone()
+                *v.first().unwrap()
             }
         };
-        self.mark_as_known(row, col, known_value)
+        self.place(row, col, known_value, Cause::Single, observer)
+    }
+
+    /// Mark an item as known and remember it was one of the givens the board
+    /// was created with.  A given that repeats a digit already known in its
+    /// row, column, or box is refused, so contradictory puzzles are caught
+    /// when they are loaded rather than part way through a solve.
+    fn mark_as_given(&mut self, row: usize, col: usize, known_value: i32) -> SudokuResult {
+        CellPos::new(row, col)?;
+        if !(1..=9).contains(&known_value) {
+            return Err(SudokuError::InvalidRange);
+        }
+        if let Some(first) = self.clash(row, col, known_value as u8) {
+            return Err(SudokuError::ConflictingGivens {
+                digit: known_value as u8,
+                first,
+                second: (row, col),
+            });
+        }
+        self.mark_as_known(row, col, known_value)?;
+        self.board
+            .get_mut(row - 1)
+            .unwrap()
+            .get_mut(col - 1)
+            .unwrap()
+            .given = true;
+        Ok(())
     }
 
     /// When marking an item as known, we first change the state of unknown
@@ -227,12 +802,7 @@ impl SudokuBoard {
     ///
     ///
     fn mark_as_known(&mut self, row: usize, col: usize, known_value: i32) -> SudokuResult {
-        if row > 9 {
-            return SudokuResult::Err(SudokuError::InvalidRange);
-        }
-        if col > 9 {
-            return SudokuResult::Err(SudokuError::InvalidRange);
-        }
+        CellPos::new(row, col)?;
 
         self.board
             .get_mut(row - 1)
@@ -249,6 +819,14 @@ impl SudokuBoard {
             .unwrap()
             .get_square();
 
+        let wipeout = |r: usize, c: usize| {
+            SudokuError::NotSolvable(Some(Wipeout {
+                row: r,
+                col: c,
+                digit: known_value as u8,
+                cause: Cause::Peer { row, col },
+            }))
+        };
         // scan the row, column, and square.  Remove the known value as a possibility.
         for i in 0..9 {
             match &mut self
@@ -263,7 +841,7 @@ impl SudokuBoard {
                 BoxValue::Unknown(v) => {
                     v.remove(&known_value);
                     if v.is_empty() {
-                        return SudokuResult::Err(SudokuError::NotSolvable);
+                        return SudokuResult::Err(wipeout(row, i + 1));
                     }
                 }
             }
@@ -279,7 +857,7 @@ impl SudokuBoard {
                 BoxValue::Unknown(v) => {
                     v.remove(&known_value);
                     if v.is_empty() {
-                        return SudokuResult::Err(SudokuError::NotSolvable);
+                        return SudokuResult::Err(wipeout(i + 1, col));
                     }
                 }
             }
@@ -296,7 +874,7 @@ impl SudokuBoard {
                 BoxValue::Unknown(v) => {
                     v.remove(&known_value);
                     if v.is_empty() {
-                        return SudokuResult::Err(SudokuError::NotSolvable);
+                        return SudokuResult::Err(wipeout(r, c));
                     }
                 }
             }
@@ -308,81 +886,293 @@ impl SudokuBoard {
 
     /// Attempt to solve the sudoku as much as possible by finding
     /// a square that only has one alternative and marking it as known.
+    #[deprecated(note = "use `solve_outcome_first`, which tells no solution apart from errors")]
     pub fn solve(&mut self) -> Result<(), SudokuError> {
-        // find a node that has unknown value but only has one alternative
-        while self.unknown_values > 0 {
-            let n = self.board.iter().flatten().find(|v| match &v.value {
-                BoxValue::Unknown(v) if v.len() == 1 => true,
-                _ => false,
-            });
-            match n {
-                Some(nv) => {
-                    self.mark_single_option(nv.row, nv.col)?;
-                }
-                // This will happen if it can't find an option that has only one option.
-                None => {
-                    // find the minimum number of alternatives to try
-                    let min_alternatives = self
-                        .board
-                        .iter()
-                        .flatten()
-                        .map(|v| match &v.value {
-                            BoxValue::Unknown(v) => v.len(),
-                            _ => 0,
-                        })
-                        .filter(|v| v > &0)
-                        .min()
-                        .unwrap();
-                    // find a node that has that many alternatives.
-                    let n = self.board.iter().flatten().find(|v| match &v.value {
-                        BoxValue::Unknown(v) if v.len() == min_alternatives => true,
-                        _ => false,
-                    });
-                    let alt_node = match n {
-                        Some(n) => n,
-                        None => return Err(SudokuError::Unknown),
-                    };
-                    let alt_set = match &alt_node.value {
-                        BoxValue::Known(_) => return Err(SudokuError::Unknown),
-                        BoxValue::Unknown(s) => s,
-                    };
-                    // we look at each alternative.  Run solve on each alternative until we find a match.
-                    for alt_item in alt_set {
-                        let mut alt_board = self.clone();
-                        let _ = alt_board.mark_as_known(alt_node.row, alt_node.col, *alt_item);
-                        match alt_board.solve() {
-                            // we found a solution in one of the alternatives.  Return this
-                            // alternative right away.
-                            Ok(_) => {
-                                self.board = alt_board.board;
-                                self.unknown_values = alt_board.unknown_values;
-                                return Ok(());
-                            }
-                            // if a solution could not be found, try another alternative
-                            Err(_) => (),
-                        };
-                        // if nothing could be found, report so
+        self.solve_first().map(|_| ())
+    }
+
+    /// Fill in the first solution the search comes to, the one
+    /// `solve_outcome_first` finds, counting how much guessing was needed.
+    pub(crate) fn solve_first(&mut self) -> Result<SolveStats, SudokuError> {
+        let mut stats = SolveStats::default();
+        self.search(&mut stats, None, None)?;
+        Ok(stats)
+    }
+
+    /// Fill in a solution like `solve_outcome_first`, but try the
+    /// alternatives of each guess in an order shuffled from `seed`.  The same
+    /// seed gives the same solution on every run and platform, different
+    /// seeds can give different solutions of a puzzle with several.
+    pub fn solve_seeded(&mut self, seed: u64) -> Result<SolveStats, SudokuError> {
+        let mut stats = SolveStats::default();
+        self.search(&mut stats, Some(&mut Rng::new(seed)), None)?;
+        Ok(stats)
+    }
+
+    /// A solution of the board chosen at random from `seed`, for boards with
+    /// several.  Unlike `solve_seeded`, which favours the solutions down
+    /// branches with few others, each guess picks an alternative weighted
+    /// by the number of solutions under it, counted up to `SAMPLE_LIMIT`.
+    /// This is exactly uniform when no alternative has more than that, and
+    /// close to it otherwise.  The board is not changed.
+    pub fn sample_solution(&self, seed: u64) -> Result<SudokuBoard, SudokuError> {
+        let mut rng = Rng::new(seed);
+        let mut board = self.clone();
+        board.fill_singles(None)?;
+        while let Some((row, col, alternatives)) = board.guess_square() {
+            let mut branches = Vec::new();
+            for digit in alternatives {
+                let mut alt_board = board.clone();
+                if alt_board.mark_as_known(row, col, digit).is_ok() {
+                    let count = alt_board.count_solutions(SAMPLE_LIMIT);
+                    if count > 0 {
+                        branches.push((alt_board, count));
                     }
-                    return Err(SudokuError::NotSolvable);
                 }
             }
+            let total: usize = branches.iter().map(|(_, count)| count).sum();
+            if total == 0 {
+                return Err(SudokuError::NotSolvable(None));
+            }
+            let mut pick = rng.below(total);
+            let index = branches
+                .iter()
+                .position(|(_, count)| {
+                    let here = pick < *count;
+                    pick = pick.saturating_sub(*count);
+                    here
+                })
+                .unwrap();
+            board = branches.swap_remove(index).0;
+            board.fill_singles(None)?;
         }
+        Ok(board)
+    }
 
-        Ok(())
+    fn search(
+        &mut self,
+        stats: &mut SolveStats,
+        mut rng: Option<&mut Rng>,
+        mut observer: Observer,
+    ) -> SudokuResult {
+        self.fill_singles(observer.as_deref_mut())?;
+        let (row, col, mut alternatives) = match self.guess_square() {
+            Some(guess) => guess,
+            None => return Ok(()),
+        };
+        if let Some(rng) = rng.as_deref_mut() {
+            rng.shuffle(&mut alternatives);
+        }
+        // we look at each alternative.  Run solve on each alternative until we find a match.
+        for alt_item in alternatives {
+            stats.guesses += 1;
+            let _span = trace::branch_span(row, col, alt_item);
+            let mut alt_board = self.clone();
+            // we found a solution in one of the alternatives.  Return this
+            // alternative right away.  If a solution could not be found, try
+            // another alternative.
+            if alt_board
+                .place(row, col, alt_item, Cause::Guess, observer.as_deref_mut())
+                .and_then(|_| alt_board.search(stats, rng.as_deref_mut(), observer.as_deref_mut()))
+                .is_ok()
+            {
+                self.board = alt_board.board;
+                self.unknown_values = alt_board.unknown_values;
+                return Ok(());
+            }
+            stats.backtracks += 1;
+            if let Some(observer) = observer.as_deref_mut() {
+                observer(&Event::Backtracked {
+                    row,
+                    col,
+                    digit: alt_item as u8,
+                });
+            }
+        }
+        // if nothing could be found, report so
+        Err(SudokuError::NotSolvable(None))
+    }
+
+    /// Mark every square that only has one alternative left as known, until
+    /// there are no such squares.
+    fn fill_singles(&mut self, mut observer: Observer) -> SudokuResult {
+        let _span = trace::propagate_span();
+        loop {
+            // find a node that has unknown value but only has one alternative
+            let single = self.unsolved_cells().find(|(_, _, n)| *n == 1);
+            match single {
+                Some((row, col, _)) => {
+                    self.mark_single_option(row, col, observer.as_deref_mut())?
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Find the unknown square with the fewest alternatives to guess next.
+    /// Returns its row, column, and alternatives, or None when every square
+    /// is known.
+    fn guess_square(&self) -> Option<(usize, usize, Vec<i32>)> {
+        let (row, col, _) = self.unsolved_cells().min_by_key(|(_, _, n)| *n)?;
+        let alternatives = self
+            .candidates(CellPos::new(row, col).ok()?)
+            .map(i32::from)
+            .collect();
+        Some((row, col, alternatives))
+    }
+
+    /// Count the solutions of the board, stopping once `limit` are found,
+    /// so the count is never more than `limit`.  A limit of 2 is enough to
+    /// tell whether a puzzle has exactly one.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let mut count = 0;
+        if limit > 0 {
+            self.clone().count_into(
+                limit,
+                usize::MAX,
+                &mut count,
+                &mut None,
+                &mut false,
+                &mut SolveStats::default(),
+            );
+        }
+        count
+    }
+
+    /// Whether the board has exactly one solution, stopping the search as
+    /// soon as a second one turns up.  Fails with `NotSolvable` when the
+    /// board has no solution at all.
+    pub fn has_unique_solution(&self) -> Result<bool, SudokuError> {
+        match self.count_solutions(2) {
+            0 => Err(SudokuError::NotSolvable(None)),
+            n => Ok(n == 1),
+        }
+    }
+
+    /// The givens that could each be removed on its own with the puzzle
+    /// keeping exactly one solution.  Only the givens are looked at, not
+    /// squares filled in since.  A puzzle that does not have exactly one
+    /// solution to begin with has none.
+    pub fn redundant_clues(&self) -> Vec<CellPos> {
+        let mut puzzle = self.clone();
+        if puzzle.reset_to_givens().is_err() || puzzle.count_solutions(2) != 1 {
+            return Vec::new();
+        }
+        let givens = puzzle.board.iter().flatten().filter(|node| node.given);
+        givens
+            .filter(|node| {
+                let mut fewer = puzzle.clone();
+                fewer.clear(node.pos()).is_ok() && fewer.count_solutions(2) == 1
+            })
+            .map(Node::pos)
+            .collect()
+    }
+
+    /// Whether the givens have exactly one solution, and every one of them
+    /// is needed for that.
+    pub fn is_minimal(&self) -> bool {
+        let mut puzzle = self.clone();
+        puzzle.reset_to_givens().is_ok()
+            && puzzle.count_solutions(2) == 1
+            && puzzle.redundant_clues().is_empty()
+    }
+
+    /// Count solutions like `count_solutions` with at most `depth` more
+    /// nested guesses, keeping the first solution found in `first`.  Sets
+    /// `cut` when a line of guesses had to stop at the limit.  A guess that
+    /// led to no new solution counts as a backtrack.
+    fn count_into(
+        &mut self,
+        limit: usize,
+        depth: usize,
+        count: &mut usize,
+        first: &mut Option<SudokuBoard>,
+        cut: &mut bool,
+        stats: &mut SolveStats,
+    ) {
+        if self.fill_singles(None).is_err() {
+            return;
+        }
+        let (row, col, alternatives) = match self.guess_square() {
+            Some(guess) => guess,
+            None => {
+                *count += 1;
+                first.get_or_insert_with(|| self.clone());
+                return;
+            }
+        };
+        if depth == 0 {
+            *cut = true;
+            return;
+        }
+        for alt_item in alternatives {
+            if *count >= limit {
+                return;
+            }
+            stats.guesses += 1;
+            let before = *count;
+            let mut alt_board = self.clone();
+            if alt_board.mark_as_known(row, col, alt_item).is_ok() {
+                alt_board.count_into(limit, depth - 1, count, first, cut, stats);
+            }
+            if *count == before {
+                stats.backtracks += 1;
+            }
+        }
+    }
+
+    /// Solve the board trying the alternatives of each guess in a random
+    /// order, so boards with many solutions end up with a random one.
+    #[cfg(feature = "generator")]
+    pub(crate) fn solve_random(&mut self, rng: &mut Rng) -> SudokuResult {
+        self.search(&mut SolveStats::default(), Some(rng), None)
+    }
+}
+
+/// Boards are equal when every square has the same value, or the same
+/// alternatives when unknown.  Whether a value was given is not compared.
+impl PartialEq for SudokuBoard {
+    fn eq(&self, other: &SudokuBoard) -> bool {
+        let values = self.board.iter().flatten().map(|v| &v.value);
+        values.eq(other.board.iter().flatten().map(|v| &v.value))
+    }
+}
+
+impl Eq for SudokuBoard {}
+
+impl Hash for SudokuBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for node in self.board.iter().flatten() {
+            node.value.hash(state);
+        }
+    }
+}
+
+impl Default for SudokuBoard {
+    fn default() -> SudokuBoard {
+        SudokuBoard::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::pos::at;
     use crate::BoxValue;
+    use crate::CellPos;
+    use crate::HouseId;
     use crate::Node;
+    use crate::SolveOutcome;
+    use crate::SolveStats;
     use crate::SudokuBoard;
+    use crate::SudokuError;
+    use std::collections::HashSet;
     #[test]
     fn test_square() {
         let mut n = Node {
             row: 1,
             col: 1,
             value: BoxValue::Known(1),
+            given: true,
         };
         assert_eq!(n.get_square(), 1);
         n.row = 2;
@@ -504,6 +1294,13 @@ mod tests {
         let _ = sboard.mark_as_known(9, 9, 9);
     }
 
+    #[test]
+    fn test_default() {
+        assert_eq!(SudokuBoard::default().print_board(), "-".repeat(81));
+        let empty = SudokuBoard::fill_board(&"-".repeat(81)).unwrap();
+        assert_eq!(empty.print_board(), SudokuBoard::new().print_board());
+    }
+
     #[test]
     fn test_fill_board() {
         let s = concat!(
@@ -518,13 +1315,508 @@ mod tests {
             "2--93--7-"
         )
         .to_string();
-        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
         let result = sboard.print_board();
         assert_eq!(s, result);
         print!("{}", result);
     }
 
     #[test]
+    fn test_get() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(sboard.get(at(1, 1)), Some(4));
+        assert_eq!(sboard.get(at(1, 6)), Some(8));
+        assert_eq!(sboard.get(at(9, 9)), None);
+        // squares off the board cannot be named
+        assert!(CellPos::new(0, 1).is_err() && CellPos::new(1, 10).is_err());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        let candidates = |row, col| sboard.candidates(at(row, col)).collect::<Vec<u8>>();
+        assert_eq!(candidates(1, 1), vec![]);
+        assert_eq!(candidates(1, 2), vec![1, 2, 3, 5, 6, 7, 9]);
+        assert_eq!(candidates(9, 9), (1..=9).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_cell() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        let pos = CellPos::new(1, 6).unwrap();
+        assert!(sboard.cell(pos).is_given());
+        assert_eq!(sboard.cell(pos).pos(), pos);
+        assert!(matches!(
+            sboard.mark_as_known(0, 1, 1),
+            Err(SudokuError::InvalidRange)
+        ));
+    }
+
+    #[test]
+    fn test_eq() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        let mut other = SudokuBoard::new();
+        other.set(at(1, 1), 4).unwrap();
+        other.set(at(1, 6), 8).unwrap();
+        assert!(sboard == other);
+
+        other.eliminate(at(9, 9), 1).unwrap();
+        assert!(sboard != other);
+        let mut solved = sboard.clone();
+        solved.solve_first().unwrap();
+        assert!(sboard != solved);
+
+        let boards: HashSet<SudokuBoard> = [sboard.clone(), solved, sboard].into();
+        assert_eq!(boards.len(), 2);
+    }
+
+    #[test]
+    fn test_houses() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        let position = |node: &Node| (node.row, node.col);
+
+        let row: Vec<_> = sboard.rows().next().unwrap().map(position).into();
+        assert_eq!(row, (1..=9).map(|c| (1, c)).collect::<Vec<_>>());
+        let col = sboard.cols().nth(8).unwrap().map(position);
+        assert_eq!(col[0], (1, 9));
+        assert_eq!(col[8], (9, 9));
+        let square = sboard.boxes().nth(4).unwrap().map(position);
+        assert_eq!(square[0], (4, 4));
+        assert_eq!(square[8], (6, 6));
+        assert_eq!(sboard.boxes().count(), 9);
+
+        let givens = sboard
+            .house(HouseId::Box(2))
+            .iter()
+            .filter(|node| node.given)
+            .count();
+        assert_eq!(givens, 1);
+    }
+
+    #[test]
+    fn test_unsolved_cells() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(sboard.unsolved_cells().count(), 79);
+        assert_eq!(sboard.unsolved_cells().next(), Some((1, 2, 7)));
+        assert_eq!(sboard.unsolved_cells().last(), Some((9, 9, 9)));
+        sboard.solve_first().unwrap();
+        assert_eq!(sboard.unsolved_cells().next(), None);
+    }
+
+    #[test]
+    fn test_predicates() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert!(!sboard.is_solved());
+        assert!(sboard.is_valid());
+        assert!(sboard.is_consistent());
+
+        let mut conflicting = sboard.clone();
+        let _ = conflicting.mark_as_known(1, 2, 4);
+        assert!(!conflicting.is_valid());
+
+        let mut stuck = sboard.clone();
+        stuck.board[8][8].value = BoxValue::Unknown(Default::default());
+        assert!(!stuck.is_consistent());
+
+        sboard.solve_first().unwrap();
+        assert!(sboard.is_solved());
+        assert!(sboard.is_valid());
+        assert!(sboard.is_consistent());
+    }
+
+    #[test]
+    fn test_eliminate() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        sboard.eliminate(at(1, 2), 1).unwrap();
+        sboard.eliminate(at(1, 2), 4).unwrap();
+        let candidates: Vec<u8> = sboard.candidates(at(1, 2)).collect();
+        assert_eq!(candidates, vec![2, 3, 5, 6, 7, 9]);
+        assert!(matches!(
+            sboard.eliminate(at(1, 1), 4),
+            Err(SudokuError::AlreadyKnown { .. })
+        ));
+        for digit in [2, 3, 5, 6, 7] {
+            sboard.eliminate(at(1, 2), digit).unwrap();
+        }
+        assert!(matches!(
+            sboard.eliminate(at(1, 2), 9),
+            Err(SudokuError::NotSolvable(None))
+        ));
+    }
+
+    #[test]
+    fn test_wipeout() {
+        // r1c1 and r1c2 can only be 1 or 2, then only 2
+        let s = "--3456789".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        sboard.set(at(2, 1), 1).unwrap();
+        let e = sboard.set(at(2, 3), 2).err().unwrap();
+        match e {
+            SudokuError::NotSolvable(Some(w)) => {
+                assert_eq!((w.row, w.col, w.digit), (1, 1, 2));
+            }
+            e => panic!("unexpected {:?}", e),
+        }
+        assert_eq!(
+            e.to_string(),
+            "the board has no solution: r1c1 has no alternatives left once 2 is placed in r2c3"
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        let e = sboard.set(at(1, 6), 1).unwrap_err();
+        assert_eq!(e.to_string(), "r1c6 is already known");
+        let e = sboard.set(at(1, 2), 8).unwrap_err();
+        assert_eq!(e.to_string(), "8 in r1c2 clashes with r1c6");
+        sboard.eliminate(at(2, 2), 5).unwrap();
+        let e = sboard.set(at(2, 2), 5).unwrap_err();
+        assert_eq!(e.to_string(), "5 is not an alternative of r2c2");
+        assert_eq!(
+            SudokuError::InvalidCellCount(80).to_string(),
+            "expected 81 squares, found 80"
+        );
+        assert_eq!(
+            SudokuError::NotGiven { row: 2, col: 3 }.to_string(),
+            "r2c3 is not a given"
+        );
+        let e = SudokuBoard::parse_strict("12x").err().unwrap();
+        assert_eq!(e.to_string(), "line 1, column 3: unexpected character 'x'");
+        assert!(std::error::Error::source(&e).is_some());
+    }
+
+    #[test]
+    fn test_set_and_clear() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        let before = sboard.print_possibility();
+
+        assert!(matches!(
+            sboard.set(at(1, 1), 5),
+            Err(SudokuError::AlreadyKnown { row: 1, col: 1 })
+        ));
+        assert!(matches!(
+            sboard.set(at(1, 2), 0),
+            Err(SudokuError::InvalidRange)
+        ));
+        assert!(matches!(
+            sboard.set(at(1, 2), 4),
+            Err(SudokuError::Conflict {
+                row: 1,
+                col: 2,
+                digit: 4,
+                with: Some((1, 1)),
+            })
+        ));
+        assert!(matches!(
+            sboard.set(at(2, 2), 10),
+            Err(SudokuError::InvalidRange)
+        ));
+        assert_eq!(sboard.print_possibility(), before);
+
+        sboard.set(at(2, 2), 7).unwrap();
+        assert_eq!(sboard.get(at(2, 2)), Some(7));
+        assert!(!sboard.is_given(at(2, 2)));
+        assert!(sboard.is_given(at(1, 1)));
+        assert!(matches!(
+            sboard.set(at(1, 3), 7),
+            Err(SudokuError::Conflict {
+                with: Some((2, 2)),
+                ..
+            })
+        ));
+        sboard.clear(at(2, 2)).unwrap();
+        assert_eq!(sboard.get(at(2, 2)), None);
+        assert_eq!(sboard.print_possibility(), before);
+
+        // a cleared given no longer rules out its digit
+        sboard.clear(at(1, 1)).unwrap();
+        sboard.set(at(1, 2), 4).unwrap();
+        assert_eq!(sboard.print_given_mask()[..6], *"-S---G");
+    }
+
+    #[test]
+    fn test_is_legal() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        sboard.eliminate(at(2, 2), 7).unwrap();
+        let before = sboard.print_possibility();
+        // ruled-out alternatives do not make a move illegal
+        assert!(sboard.is_legal(at(2, 2), 7));
+        assert!(!sboard.is_legal(at(2, 2), 4));
+        assert!(!sboard.is_legal(at(9, 6), 8));
+        assert!(!sboard.is_legal(at(1, 1), 5));
+        assert!(!sboard.is_legal(at(1, 2), 0));
+        assert_eq!(sboard.conflict(at(2, 2), 4).unwrap(), Some(at(1, 1)));
+        assert_eq!(sboard.conflict(at(9, 6), 8).unwrap(), Some(at(1, 6)));
+        assert_eq!(sboard.conflict(at(2, 2), 7).unwrap(), None);
+        assert!(matches!(
+            sboard.conflict(at(2, 2), 10),
+            Err(SudokuError::InvalidRange)
+        ));
+        assert_eq!(sboard.print_possibility(), before);
+    }
+
+    #[test]
+    fn test_solve_order() {
+        let mut first = SudokuBoard::new();
+        first.solve_first().unwrap();
+        let mut again = SudokuBoard::new();
+        again.solve_first().unwrap();
+        assert!(first == again);
+        assert_eq!(first.print_board()[..9], *"123456789");
+
+        let seeded = |seed| {
+            let mut sboard = SudokuBoard::new();
+            sboard.solve_seeded(seed).unwrap();
+            assert!(sboard.is_solved() && sboard.is_valid());
+            sboard
+        };
+        assert!(seeded(7) == seeded(7));
+        assert!(seeded(7) != seeded(8));
+    }
+
+    #[test]
+    #[cfg(feature = "analysis")]
+    fn test_sample_solution() {
+        let mut grid = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        grid.solve_first().unwrap();
+        // clearing two unavoidable sets that share no square leaves a
+        // puzzle with four solutions
+        let sets = grid.unavoidable_sets(4).unwrap();
+        let first = &sets[0];
+        let second = sets[1..]
+            .iter()
+            .find(|set| set.iter().all(|pos| !first.contains(pos)))
+            .unwrap();
+        let mut puzzle = grid.clone();
+        for pos in first.iter().chain(second) {
+            puzzle.clear(at(pos.row.get(), pos.col.get())).unwrap();
+        }
+        assert_eq!(puzzle.count_solutions(5), 4);
+
+        let mut seen: Vec<(String, usize)> = Vec::new();
+        for seed in 0..200 {
+            let solution = puzzle.sample_solution(seed).unwrap();
+            assert!(solution.is_solved() && solution.is_valid());
+            let text = solution.print_board();
+            match seen.iter_mut().find(|(s, _)| *s == text) {
+                Some((_, n)) => *n += 1,
+                None => seen.push((text, 1)),
+            }
+        }
+        assert_eq!(seen.len(), 4);
+        assert!(
+            seen.iter().all(|(_, n)| (25..=75).contains(n)),
+            "{:?}",
+            seen
+        );
+
+        assert!(puzzle.sample_solution(3).unwrap() == puzzle.sample_solution(3).unwrap());
+        assert!(SudokuBoard::new().sample_solution(1).unwrap().is_solved());
+    }
+
+    #[test]
+    fn test_depth_limit() {
+        let s = concat!(
+            "---------",
+            "-----3-85",
+            "--1-2----",
+            "---5-7---",
+            "--4---1--",
+            "-9-------",
+            "5------73",
+            "--2-1----",
+            "----4---9"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        let mut singles = puzzle.clone();
+        singles.fill_singles(None).unwrap();
+
+        match puzzle.solve_outcome_within(1) {
+            SolveOutcome::Incomplete(SudokuError::DepthLimit { depth: 1, board }) => {
+                assert!(*board == singles)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let mut expected = puzzle.clone();
+        expected.solve_first().unwrap();
+        match puzzle.solve_outcome_within(81) {
+            SolveOutcome::Unique(solution) => {
+                assert_eq!(solution.to_line(), expected.print_board())
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // a board the singles solve needs no guessing
+        let easy = SudokuBoard::fill_board(&expected.print_board()[..80]).unwrap();
+        assert!(matches!(
+            easy.solve_outcome_within(0),
+            SolveOutcome::Unique(_)
+        ));
+
+        // r1c8 and r1c9 can both only be 9
+        let mut broken =
+            SudokuBoard::fill_board(&("1234567--".to_string() + &"-".repeat(72))).unwrap();
+        broken.mark_as_given(4, 8, 8).unwrap();
+        broken.mark_as_given(7, 9, 8).unwrap();
+        assert!(matches!(
+            broken.solve_outcome_within(0),
+            SolveOutcome::Unsolvable
+        ));
+    }
+
+    #[test]
+    fn test_reset_to_givens() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let puzzle = SudokuBoard::fill_board(&s).unwrap();
+        let mut sboard = puzzle.clone();
+        sboard.set(at(2, 2), 7).unwrap();
+        sboard.eliminate(at(9, 9), 1).unwrap();
+        sboard.reset_to_givens().unwrap();
+        assert_eq!(sboard.print_possibility(), puzzle.print_possibility());
+        assert_eq!(sboard.print_given_mask(), puzzle.print_given_mask());
+
+        sboard.solve_first().unwrap();
+        assert_eq!(sboard.progress(), 1.0);
+        sboard.reset_to_givens().unwrap();
+        assert!(sboard == puzzle);
+        assert_eq!(sboard.unsolved_cells().count(), 79);
+        assert_eq!(sboard.unknown_count(), 79);
+        assert_eq!(sboard.solved_count(), 2);
+        assert_eq!(SudokuBoard::new().progress(), 0.0);
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        let empty = SudokuBoard::new();
+        assert_eq!(empty.count_solutions(0), 0);
+        assert_eq!(empty.count_solutions(1), 1);
+        assert_eq!(empty.count_solutions(5), 5);
+
+        let mut solved = SudokuBoard::new();
+        solved.solve_first().unwrap();
+        assert_eq!(solved.count_solutions(0), 0);
+        assert_eq!(solved.count_solutions(10), 1);
+
+        let mut stuck = SudokuBoard::new();
+        stuck.board[0][0].value = BoxValue::Unknown(Default::default());
+        assert_eq!(stuck.count_solutions(10), 0);
+    }
+
+    #[test]
+    fn test_has_unique_solution() {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        assert!(sboard.has_unique_solution().unwrap());
+        sboard.clear(at(1, 1)).unwrap();
+        assert!(!sboard.has_unique_solution().unwrap());
+        let mut stuck = SudokuBoard::new();
+        stuck.board[0][0].value = BoxValue::Unknown(Default::default());
+        assert!(matches!(
+            stuck.has_unique_solution(),
+            Err(SudokuError::NotSolvable(None))
+        ));
+    }
+
+    #[test]
+    fn test_minimal() {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        assert!(!sboard.is_minimal());
+        // take out clues that are not needed until none are left
+        while let Some(pos) = sboard.redundant_clues().first() {
+            sboard.clear(at(pos.row.get(), pos.col.get())).unwrap();
+            assert!(sboard.has_unique_solution().unwrap());
+        }
+        assert!(sboard.is_minimal());
+
+        // squares filled in are not clues
+        let mut partly = sboard.clone();
+        partly.solve_first().unwrap();
+        assert!(partly.is_minimal());
+        assert!(partly.redundant_clues().is_empty());
+
+        assert!(!SudokuBoard::new().is_minimal());
+        assert!(SudokuBoard::new().redundant_clues().is_empty());
+    }
+
+    #[test]
+    fn test_conflicting_givens() {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        // a second 4 in the first row
+        let e = SudokuBoard::fill_board(&s.replacen('-', "4", 1))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            e,
+            SudokuError::ConflictingGivens {
+                digit: 4,
+                first: (1, 1),
+                second: (1, 2)
+            }
+        ));
+        assert_eq!(e.to_string(), "4 is given in both r1c1 and r1c2");
+        let e = SudokuBoard::parse_grid(&s.replace("2--93--7-", "2--93--74"))
+            .err()
+            .unwrap();
+        assert!(matches!(e, SudokuError::ConflictingGivens { digit: 4, .. }));
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn test_solve() {
         let s = concat!(
             "500300600",
@@ -551,8 +1843,56 @@ mod tests {
         )
         .to_string();
         let mut sboard = SudokuBoard::fill_board(&s).unwrap();
-        sboard.solve();
+        sboard.solve().unwrap();
         let result = sboard.print_board();
         assert_eq!(result, solution);
     }
+
+    #[test]
+    fn test_solve_with_stats() {
+        // needs guessing: a wrong guess is backtracked, the last is kept
+        let mut sboard = SudokuBoard::fill_board(concat!(
+            "500300600",
+            "004001750",
+            "000059100",
+            "403200070",
+            "006000000",
+            "000000904",
+            "700090315",
+            "035000806",
+            "619080000"
+        ))
+        .unwrap();
+        let stats = sboard.solve_first().unwrap();
+        assert!(stats.guesses > stats.backtracks);
+
+        // a solved board needs no guessing at all
+        let stats = sboard.solve_first().unwrap();
+        assert_eq!(stats, SolveStats::default());
+    }
+
+    #[test]
+    fn test_given_mask() {
+        let s = concat!(
+            "500300600",
+            "004001750",
+            "000059100",
+            "403200070",
+            "006000000",
+            "000000904",
+            "700090315",
+            "035000806",
+            "619080000"
+        );
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let (_, mask) = sboard.print_board_with_mask();
+        assert_eq!(&mask[..9], "G--G--G--");
+        sboard.solve_first().unwrap();
+        let (result, mask) = sboard.print_board_with_mask();
+        assert_eq!(result, sboard.print_board());
+        assert_eq!(&mask[..9], "GSSGSSGSS");
+        assert_eq!(mask.chars().filter(|c| *c == 'G').count(), 30);
+        assert!(sboard.cell(CellPos::new(1, 1).unwrap()).is_given());
+        assert!(!sboard.cell(CellPos::new(1, 2).unwrap()).is_given());
+    }
 }
@@ -0,0 +1,910 @@
+use crate::observe::Observer;
+use crate::trace;
+use crate::{BoxValue, Cause, Event, SolveStats, SudokuBoard, SudokuError, SudokuResult, Wipeout};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A way of making progress on a board the way a person would, without
+/// guessing.  Techniques are ordered from the easiest to the hardest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    // A square with only one candidate left
+    NakedSingle,
+    // A digit with only one place left in a row, column, or box
+    HiddenSingle,
+    // A digit in a box limited to one row or column, or the other way around
+    LockedCandidates,
+    // Two squares of a house sharing the same two candidates
+    NakedPair,
+    // Two digits limited to the same two squares of a house
+    HiddenPair,
+    // Three squares of a house sharing three candidates
+    NakedTriple,
+    // Three digits limited to the same three squares of a house
+    HiddenTriple,
+    // A digit limited to the same two columns in two rows, or the other way around
+    XWing,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 8] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::LockedCandidates,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::XWing,
+    ];
+
+    /// The name people use for the technique.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "Naked Single",
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::LockedCandidates => "Locked Candidates",
+            Technique::NakedPair => "Naked Pair",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::XWing => "X-Wing",
+        }
+    }
+
+    /// The difficulty of a puzzle that needs this technique.
+    pub fn difficulty(&self) -> Difficulty {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+            Technique::LockedCandidates => Difficulty::Medium,
+            Technique::NakedPair
+            | Technique::HiddenPair
+            | Technique::NakedTriple
+            | Technique::HiddenTriple => Difficulty::Hard,
+            Technique::XWing => Difficulty::Expert,
+        }
+    }
+}
+
+/// How hard a puzzle is for a person to solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    // Singles are enough
+    Easy,
+    // Needs locked candidates
+    Medium,
+    // Needs naked or hidden pairs and triples
+    Hard,
+    // Needs fish such as the X-Wing
+    Expert,
+    // The techniques above get stuck, it needs guessing
+    Diabolical,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+        Difficulty::Diabolical,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+            Difficulty::Diabolical => "diabolical",
+        }
+    }
+}
+
+/// A row, column, or 3x3 box of the board, numbered from 1 to 9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HouseId {
+    Row(usize),
+    Col(usize),
+    Box(usize),
+}
+
+impl HouseId {
+    /// Every house: the rows, then the columns, then the boxes.
+    pub fn all() -> impl Iterator<Item = HouseId> {
+        (1..=9)
+            .map(HouseId::Row)
+            .chain((1..=9).map(HouseId::Col))
+            .chain((1..=9).map(HouseId::Box))
+    }
+
+    /// Indexes (row * 9 + col, from 0) of the squares in the house.
+    pub fn cells(&self) -> [usize; 9] {
+        let mut cells = [0; 9];
+        for (i, cell) in cells.iter_mut().enumerate() {
+            *cell = match self {
+                HouseId::Row(r) => (r - 1) * 9 + i,
+                HouseId::Col(c) => i * 9 + (c - 1),
+                HouseId::Box(b) => {
+                    let row = ((b - 1) / 3) * 3 + i / 3;
+                    let col = ((b - 1) % 3) * 3 + i % 3;
+                    row * 9 + col
+                }
+            };
+        }
+        cells
+    }
+}
+
+impl fmt::Display for HouseId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HouseId::Row(n) => write!(f, "row {}", n),
+            HouseId::Col(n) => write!(f, "column {}", n),
+            HouseId::Box(n) => write!(f, "box {}", n),
+        }
+    }
+}
+
+/// How many steps of its hardest technique push a board up a grade.
+/// Singles never do, as every board is full of them.
+pub const GRADE_REPEATS: usize = 4;
+
+/// One deduction made by a technique.  Squares are (row, col) and digits
+/// are placed or eliminated as (row, col, digit), all numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    // Values the step places
+    pub placements: Vec<(usize, usize, i32)>,
+    // Candidates the step removes
+    pub eliminations: Vec<(usize, usize, i32)>,
+    // Squares whose candidates justify the step
+    pub cells: Vec<(usize, usize)>,
+    // Houses the technique looked at
+    pub houses: Vec<HouseId>,
+    // Digits the technique is about
+    pub digits: Vec<i32>,
+}
+
+/// How often each technique was used by a logical solve.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TechniqueReport {
+    // Steps taken with each technique.  Techniques never used are left out
+    pub counts: BTreeMap<Technique, usize>,
+    // The techniques were enough to solve the board
+    pub solved: bool,
+}
+
+impl TechniqueReport {
+    /// The number of steps taken.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+/// The counts from the easiest technique to the hardest, e.g.
+/// "31 Naked Single, 4 Hidden Pair, 1 X-Wing".
+impl fmt::Display for TechniqueReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (technique, count)) in self.counts.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} {}", count, technique.name())?;
+        }
+        Ok(())
+    }
+}
+
+/// How hard a puzzle is, finer than its grade: puzzles of the same grade
+/// are told apart by the guessing left after the techniques, then by the
+/// number of logical steps.  Harder puzzles compare greater.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hardness {
+    pub grade: Difficulty,
+    // Guesses made when the techniques are stuck
+    pub guesses: usize,
+    // Logical steps taken, on guesses taken back too
+    pub steps: usize,
+}
+
+fn position(index: usize) -> (usize, usize) {
+    (index / 9 + 1, index % 9 + 1)
+}
+
+fn box_of(index: usize) -> usize {
+    (index / 27) * 3 + (index % 9) / 3 + 1
+}
+
+fn digits_of(mask: u16) -> impl Iterator<Item = i32> {
+    (1..=9).filter(move |d| mask & (1 << d) != 0)
+}
+
+/// Every combination of `size` items, in order.
+fn combinations(items: &[usize], size: usize) -> Vec<Vec<usize>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut all = Vec::new();
+    for (i, first) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, *first);
+            all.push(rest);
+        }
+    }
+    all
+}
+
+/// The candidates of every square as bit masks, bit `d` set when `d` is
+/// still possible.  Known squares have no candidates.
+struct Grid {
+    candidates: [u16; 81],
+}
+
+impl Grid {
+    fn new(board: &SudokuBoard) -> Grid {
+        let mut candidates = [0u16; 81];
+        for (mask, node) in candidates.iter_mut().zip(board.board.iter().flatten()) {
+            if let BoxValue::Unknown(set) = &node.value {
+                *mask = set.iter().fold(0, |m, d| m | (1 << d));
+            }
+        }
+        Grid { candidates }
+    }
+
+    /// Squares of the house where `digit` is still a candidate.
+    fn places(&self, house: HouseId, digit: i32) -> Vec<usize> {
+        house
+            .cells()
+            .into_iter()
+            .filter(|i| self.candidates[*i] & (1 << digit) != 0)
+            .collect()
+    }
+
+    fn naked_single(&self) -> Option<Step> {
+        let index = (0..81).find(|i| self.candidates[*i].count_ones() == 1)?;
+        let digit = digits_of(self.candidates[index]).next()?;
+        let (row, col) = position(index);
+        Some(Step {
+            technique: Technique::NakedSingle,
+            placements: vec![(row, col, digit)],
+            eliminations: Vec::new(),
+            cells: vec![(row, col)],
+            houses: Vec::new(),
+            digits: vec![digit],
+        })
+    }
+
+    fn hidden_single(&self) -> Option<Step> {
+        for house in HouseId::all() {
+            for digit in 1..=9 {
+                if let [index] = self.places(house, digit)[..] {
+                    let (row, col) = position(index);
+                    return Some(Step {
+                        technique: Technique::HiddenSingle,
+                        placements: vec![(row, col, digit)],
+                        eliminations: Vec::new(),
+                        cells: vec![(row, col)],
+                        houses: vec![house],
+                        digits: vec![digit],
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Remove `digit` from the squares of `house` that are not in `keep`.
+    fn eliminate_outside(
+        &self,
+        house: HouseId,
+        keep: &[usize],
+        digits: u16,
+    ) -> Vec<(usize, usize, i32)> {
+        let mut eliminations = Vec::new();
+        for index in house.cells() {
+            if keep.contains(&index) {
+                continue;
+            }
+            for digit in digits_of(self.candidates[index] & digits) {
+                let (row, col) = position(index);
+                eliminations.push((row, col, digit));
+            }
+        }
+        eliminations
+    }
+
+    fn locked_candidates(&self) -> Option<Step> {
+        for house in HouseId::all() {
+            for digit in 1..=9 {
+                let places = self.places(house, digit);
+                if places.len() < 2 {
+                    continue;
+                }
+                // pointing: the digit of a box is in one row or column,
+                // claiming: the digit of a row or column is in one box
+                let targets = match house {
+                    HouseId::Box(_) => {
+                        let mut targets = Vec::new();
+                        if places.iter().all(|i| i / 9 == places[0] / 9) {
+                            targets.push(HouseId::Row(places[0] / 9 + 1));
+                        }
+                        if places.iter().all(|i| i % 9 == places[0] % 9) {
+                            targets.push(HouseId::Col(places[0] % 9 + 1));
+                        }
+                        targets
+                    }
+                    _ if places.iter().all(|i| box_of(*i) == box_of(places[0])) => {
+                        vec![HouseId::Box(box_of(places[0]))]
+                    }
+                    _ => Vec::new(),
+                };
+                for target in targets {
+                    let eliminations = self.eliminate_outside(target, &places, 1 << digit);
+                    if !eliminations.is_empty() {
+                        return Some(Step {
+                            technique: Technique::LockedCandidates,
+                            placements: Vec::new(),
+                            eliminations,
+                            cells: places.iter().map(|i| position(*i)).collect(),
+                            houses: vec![house, target],
+                            digits: vec![digit],
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn naked_subset(&self, size: usize, technique: Technique) -> Option<Step> {
+        for house in HouseId::all() {
+            let open: Vec<usize> = house
+                .cells()
+                .into_iter()
+                .filter(|i| (2..=size as u32).contains(&self.candidates[*i].count_ones()))
+                .collect();
+            for subset in combinations(&open, size) {
+                let digits = subset.iter().fold(0, |m, i| m | self.candidates[*i]);
+                if digits.count_ones() as usize != size {
+                    continue;
+                }
+                let eliminations = self.eliminate_outside(house, &subset, digits);
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique,
+                        placements: Vec::new(),
+                        eliminations,
+                        cells: subset.iter().map(|i| position(*i)).collect(),
+                        houses: vec![house],
+                        digits: digits_of(digits).collect(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn hidden_subset(&self, size: usize, technique: Technique) -> Option<Step> {
+        for house in HouseId::all() {
+            let open: Vec<usize> = (1..=9)
+                .filter(|d| (2..=size).contains(&self.places(house, *d as i32).len()))
+                .collect();
+            for subset in combinations(&open, size) {
+                let mut cells: Vec<usize> = subset
+                    .iter()
+                    .flat_map(|d| self.places(house, *d as i32))
+                    .collect();
+                cells.sort();
+                cells.dedup();
+                if cells.len() != size {
+                    continue;
+                }
+                let digits = subset.iter().fold(0u16, |m, d| m | (1 << d));
+                let mut eliminations = Vec::new();
+                for index in &cells {
+                    for digit in digits_of(self.candidates[*index] & !digits) {
+                        let (row, col) = position(*index);
+                        eliminations.push((row, col, digit));
+                    }
+                }
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique,
+                        placements: Vec::new(),
+                        eliminations,
+                        cells: cells.iter().map(|i| position(*i)).collect(),
+                        houses: vec![house],
+                        digits: digits_of(digits).collect(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn x_wing(&self) -> Option<Step> {
+        type Line = fn(usize) -> HouseId;
+        type Cover = fn(usize) -> usize;
+        let orientations: [(Line, Line, Cover); 2] = [
+            (HouseId::Row, HouseId::Col, |i| i % 9),
+            (HouseId::Col, HouseId::Row, |i| i / 9),
+        ];
+        for (base, cover, cover_of) in orientations {
+            for digit in 1..=9 {
+                let lines: Vec<(usize, Vec<usize>)> = (1..=9)
+                    .map(|n| (n, self.places(base(n), digit)))
+                    .filter(|(_, places)| places.len() == 2)
+                    .collect();
+                for (a, (first, first_places)) in lines.iter().enumerate() {
+                    for (second, second_places) in &lines[a + 1..] {
+                        let covers: Vec<usize> =
+                            first_places.iter().map(|i| cover_of(*i)).collect();
+                        if covers
+                            != second_places
+                                .iter()
+                                .map(|i| cover_of(*i))
+                                .collect::<Vec<usize>>()
+                        {
+                            continue;
+                        }
+                        let corners: Vec<usize> =
+                            first_places.iter().chain(second_places).copied().collect();
+                        let mut eliminations = Vec::new();
+                        for c in &covers {
+                            eliminations.extend(self.eliminate_outside(
+                                cover(c + 1),
+                                &corners,
+                                1 << digit,
+                            ));
+                        }
+                        if !eliminations.is_empty() {
+                            return Some(Step {
+                                technique: Technique::XWing,
+                                placements: Vec::new(),
+                                eliminations,
+                                cells: corners.iter().map(|i| position(*i)).collect(),
+                                houses: vec![
+                                    base(*first),
+                                    base(*second),
+                                    cover(covers[0] + 1),
+                                    cover(covers[1] + 1),
+                                ],
+                                digits: vec![digit],
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn find_step(&self, technique: Technique) -> Option<Step> {
+        let _span = trace::strategy_span(technique);
+        match technique {
+            Technique::NakedSingle => self.naked_single(),
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::LockedCandidates => self.locked_candidates(),
+            Technique::NakedPair => self.naked_subset(2, technique),
+            Technique::HiddenPair => self.hidden_subset(2, technique),
+            Technique::NakedTriple => self.naked_subset(3, technique),
+            Technique::HiddenTriple => self.hidden_subset(3, technique),
+            Technique::XWing => self.x_wing(),
+        }
+    }
+}
+
+impl SudokuBoard {
+    /// Find the easiest logical step that makes progress on the board,
+    /// without changing the board.  None when the techniques are stuck or
+    /// the board is solved.
+    pub fn next_step(&self) -> Option<Step> {
+        self.next_step_with(&Technique::ALL)
+    }
+
+    /// Find a step like `next_step` using only `techniques`, tried in the
+    /// order given.
+    pub fn next_step_with(&self, techniques: &[Technique]) -> Option<Step> {
+        let grid = Grid::new(self);
+        techniques.iter().find_map(|t| grid.find_step(*t))
+    }
+
+    /// Place the values and remove the candidates of a step.
+    pub fn apply_step(&mut self, step: &Step) -> SudokuResult {
+        self.apply_step_observed(step, None)
+    }
+
+    pub(crate) fn apply_step_observed(
+        &mut self,
+        step: &Step,
+        mut observer: Observer,
+    ) -> SudokuResult {
+        let cause = Cause::Technique(step.technique);
+        for (row, col, digit) in &step.placements {
+            self.place(*row, *col, *digit, cause, observer.as_deref_mut())?;
+        }
+        for (row, col, digit) in &step.eliminations {
+            if let BoxValue::Unknown(set) = &mut self.board[row - 1][col - 1].value {
+                if set.remove(digit) {
+                    trace::eliminated(*row, *col, *digit, cause);
+                    if let Some(observer) = observer.as_deref_mut() {
+                        observer(&Event::Eliminated {
+                            row: *row,
+                            col: *col,
+                            digit: *digit as u8,
+                            cause,
+                        });
+                    }
+                }
+                if set.is_empty() {
+                    return Err(SudokuError::NotSolvable(Some(Wipeout {
+                        row: *row,
+                        col: *col,
+                        digit: *digit as u8,
+                        cause,
+                    })));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Solve as far as the techniques go.  Returns the steps taken, the board
+    /// is solved when the last step leaves no unknown squares.
+    pub(crate) fn solve_logically(&mut self) -> Result<Vec<Step>, SudokuError> {
+        let mut steps = Vec::new();
+        while !self.is_solved() {
+            match self.next_step() {
+                Some(step) => {
+                    self.apply_step(&step)?;
+                    steps.push(step);
+                }
+                None => break,
+            }
+        }
+        Ok(steps)
+    }
+
+    /// Solve the board with the logical techniques alone, counting the steps
+    /// taken with each.  The board is left as far as the techniques get it,
+    /// and the report tells whether that is solved.
+    pub fn solve_with_report(&mut self) -> Result<TechniqueReport, SudokuError> {
+        let mut report = TechniqueReport::default();
+        for step in self.solve_logically()? {
+            *report.counts.entry(step.technique).or_insert(0) += 1;
+        }
+        report.solved = self.is_solved();
+        Ok(report)
+    }
+
+    /// Solve the board with only `techniques`, guessing when they are stuck,
+    /// to compare sets of techniques.  The report counts every step taken,
+    /// also those on guesses that were taken back, and is only `solved`
+    /// when no guessing was needed.  A square with a single candidate left
+    /// is filled in without counting a guess.
+    pub fn solve_with_techniques(
+        &mut self,
+        techniques: &[Technique],
+    ) -> Result<(TechniqueReport, SolveStats), SudokuError> {
+        let mut report = TechniqueReport::default();
+        let mut stats = SolveStats::default();
+        self.search_with(techniques, &mut report, &mut stats)?;
+        report.solved = stats.guesses == 0;
+        Ok((report, stats))
+    }
+
+    fn search_with(
+        &mut self,
+        techniques: &[Technique],
+        report: &mut TechniqueReport,
+        stats: &mut SolveStats,
+    ) -> SudokuResult {
+        while let Some(step) = self.next_step_with(techniques) {
+            *report.counts.entry(step.technique).or_insert(0) += 1;
+            self.apply_step(&step)?;
+        }
+        let (row, col, alternatives) = match self.guess_square() {
+            Some(guess) => guess,
+            None => return Ok(()),
+        };
+        let guessing = alternatives.len() > 1;
+        for digit in alternatives {
+            if guessing {
+                stats.guesses += 1;
+            }
+            let mut alt_board = self.clone();
+            if alt_board
+                .mark_as_known(row, col, digit)
+                .and_then(|_| alt_board.search_with(techniques, report, stats))
+                .is_ok()
+            {
+                *self = alt_board;
+                return Ok(());
+            }
+            if guessing {
+                stats.backtracks += 1;
+            }
+        }
+        Err(SudokuError::NotSolvable(None))
+    }
+
+    /// Measure how hard the board is, for ranking puzzles of the same grade.
+    /// Fails with `NotSolvable` when the board has no solution.
+    pub fn hardness(&self) -> Result<Hardness, SudokuError> {
+        let (report, stats) = self.clone().solve_with_techniques(&Technique::ALL)?;
+        Ok(Hardness {
+            grade: self.grade(),
+            guesses: stats.guesses,
+            steps: report.total(),
+        })
+    }
+
+    /// Find the step of the hardest technique a logical solve takes, the
+    /// first one when the technique is used more than once.  Its cells and
+    /// digits show what makes the board hard.  None when the board needs no
+    /// steps at all.  On a board the techniques cannot finish, this is the
+    /// hardest step taken before they got stuck.
+    pub fn hardest_step(&self) -> Result<Option<Step>, SudokuError> {
+        let mut board = self.clone();
+        let mut hardest: Option<Step> = None;
+        for step in board.solve_logically()? {
+            if hardest
+                .as_ref()
+                .is_none_or(|h| step.technique > h.technique)
+            {
+                hardest = Some(step);
+            }
+        }
+        Ok(hardest)
+    }
+
+    /// Grade the board from the hardest technique a logical solve needs.
+    /// Boards the techniques cannot finish are graded `Diabolical`.
+    pub fn difficulty(&self) -> Difficulty {
+        let mut board = self.clone();
+        match board.solve_logically() {
+            Ok(steps) if board.is_solved() => steps
+                .iter()
+                .map(|s| s.technique.difficulty())
+                .max()
+                .unwrap_or(Difficulty::Easy),
+            _ => Difficulty::Diabolical,
+        }
+    }
+
+    /// Grade the board like `difficulty`, also counting the work: a board
+    /// that needs the hardest technique it uses over and over, at least
+    /// `GRADE_REPEATS` times, is graded one level up.  Only boards the
+    /// techniques cannot finish are graded `Diabolical`.
+    pub fn grade(&self) -> Difficulty {
+        let mut board = self.clone();
+        let steps = match board.solve_logically() {
+            Ok(steps) if board.is_solved() => steps,
+            _ => return Difficulty::Diabolical,
+        };
+        let hardest = steps
+            .iter()
+            .map(|s| s.technique.difficulty())
+            .max()
+            .unwrap_or(Difficulty::Easy);
+        let repeats = steps
+            .iter()
+            .filter(|s| s.technique.difficulty() == hardest)
+            .count();
+        match hardest {
+            Difficulty::Medium if repeats >= GRADE_REPEATS => Difficulty::Hard,
+            Difficulty::Hard if repeats >= GRADE_REPEATS => Difficulty::Expert,
+            _ => hardest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Difficulty, HouseId, Technique, GRADE_REPEATS};
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_house_cells() {
+        assert_eq!(HouseId::Row(2).cells(), [9, 10, 11, 12, 13, 14, 15, 16, 17]);
+        assert_eq!(HouseId::Col(9).cells(), [8, 17, 26, 35, 44, 53, 62, 71, 80]);
+        assert_eq!(
+            HouseId::Box(5).cells(),
+            [30, 31, 32, 39, 40, 41, 48, 49, 50]
+        );
+        assert_eq!(HouseId::all().count(), 27);
+    }
+
+    #[test]
+    fn test_easy_puzzle() {
+        let s = concat!(
+            "500300600",
+            "004001750",
+            "000059100",
+            "403200070",
+            "006000000",
+            "000000904",
+            "700090315",
+            "035000806",
+            "619080000"
+        );
+        let board = SudokuBoard::parse_strict(s).unwrap();
+        let step = board.next_step().unwrap();
+        assert!(step.technique <= Technique::HiddenSingle);
+        assert_eq!(step.placements.len(), 1);
+
+        let mut solved = board.clone();
+        let steps = solved.solve_logically().unwrap();
+        assert_eq!(solved.unknown_values, 0);
+        assert_eq!(steps.len(), 51);
+        let mut expected = board.clone();
+        expected.solve_first().unwrap();
+        assert_eq!(solved.print_board(), expected.print_board());
+    }
+
+    #[test]
+    fn test_x_wing() {
+        // a well known X-Wing example, the 7s of rows 2 and 8 are in columns 2 and 8
+        let s = concat!(
+            "100000569",
+            "492056108",
+            "056109240",
+            "009640801",
+            "064010000",
+            "218035604",
+            "040500016",
+            "905061402",
+            "621000005"
+        );
+        let board = SudokuBoard::parse_strict(s).unwrap();
+        let mut solved = board.clone();
+        let steps = solved.solve_logically().unwrap();
+        assert_eq!(solved.unknown_values, 0);
+        let x_wing = steps
+            .iter()
+            .find(|s| s.technique == Technique::XWing)
+            .unwrap();
+        assert_eq!(x_wing.digits, vec![7]);
+        assert_eq!(board.difficulty(), Difficulty::Expert);
+        assert_eq!(board.grade(), Difficulty::Expert);
+    }
+
+    #[test]
+    fn test_grade() {
+        let easy = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        assert_eq!(easy.grade(), easy.difficulty());
+        assert_eq!(SudokuBoard::new().grade(), Difficulty::Diabolical);
+
+        // locked candidates over and over
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let busy = SudokuBoard::fill_board(s).unwrap();
+        assert_eq!(busy.difficulty(), Difficulty::Medium);
+        assert_eq!(busy.grade(), Difficulty::Hard);
+    }
+
+    #[test]
+    fn test_solve_with_report() {
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let report = sboard.solve_with_report().unwrap();
+        assert!(report.solved && sboard.is_solved());
+        let steps = SudokuBoard::fill_board(s)
+            .unwrap()
+            .solve_logically()
+            .unwrap();
+        assert_eq!(report.total(), steps.len());
+        assert!(report.counts[&Technique::LockedCandidates] >= GRADE_REPEATS);
+        assert!(!report.counts.contains_key(&Technique::XWing));
+        let text = report.to_string();
+        assert!(text.contains(" Locked Candidates"));
+        assert_eq!(text.split(", ").count(), report.counts.len());
+
+        let report = SudokuBoard::new().solve_with_report().unwrap();
+        assert!(!report.solved);
+        assert_eq!(report.total(), 0);
+        assert_eq!(report.to_string(), "");
+    }
+
+    #[test]
+    fn test_solve_with_techniques() {
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let mut expected = SudokuBoard::fill_board(s).unwrap();
+        expected.solve_first().unwrap();
+
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let (report, stats) = sboard.solve_with_techniques(&Technique::ALL).unwrap();
+        assert_eq!(sboard.print_board(), expected.print_board());
+        assert!(report.solved);
+        assert_eq!((stats.guesses, stats.backtracks), (0, 0));
+        assert!(report.counts.contains_key(&Technique::LockedCandidates));
+
+        // singles alone get stuck on the locked candidates
+        let singles = [Technique::NakedSingle, Technique::HiddenSingle];
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let (report, stats) = sboard.solve_with_techniques(&singles).unwrap();
+        assert_eq!(sboard.print_board(), expected.print_board());
+        assert!(!report.solved);
+        assert!(stats.guesses > 0);
+        assert!(report.counts.keys().all(|t| singles.contains(t)));
+
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let (report, _) = sboard.solve_with_techniques(&[]).unwrap();
+        assert!(sboard.is_solved());
+        assert_eq!(report.total(), 0);
+        let board = SudokuBoard::fill_board(s).unwrap();
+        assert_eq!(board.next_step_with(&[Technique::XWing]), None);
+    }
+
+    #[test]
+    fn test_hardness() {
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let busy = SudokuBoard::fill_board(s).unwrap();
+        let hardness = busy.hardness().unwrap();
+        assert_eq!(hardness.grade, busy.grade());
+        assert_eq!(hardness.guesses, 0);
+        let easy = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        assert!(easy.hardness().unwrap() < hardness);
+        let empty = SudokuBoard::new().hardness().unwrap();
+        assert!(empty > hardness && empty.guesses > 0);
+    }
+
+    #[test]
+    fn test_hardest_step() {
+        let s = concat!(
+            "100000569",
+            "492056108",
+            "056109240",
+            "009640801",
+            "064010000",
+            "218035604",
+            "040500016",
+            "905061402",
+            "621000005"
+        );
+        let board = SudokuBoard::parse_strict(s).unwrap();
+        let hardest = board.hardest_step().unwrap().unwrap();
+        assert_eq!(hardest.technique, Technique::XWing);
+        assert_eq!(hardest.digits, vec![7]);
+        assert_eq!(hardest.cells.len(), 4);
+        assert!(!hardest.eliminations.is_empty());
+
+        // the first of the many locked candidates
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let busy = SudokuBoard::fill_board(s).unwrap();
+        let hardest = busy.hardest_step().unwrap().unwrap();
+        assert_eq!(hardest.technique, Technique::LockedCandidates);
+        let steps = busy.clone().solve_logically().unwrap();
+        let first = steps
+            .iter()
+            .find(|s| s.technique == Technique::LockedCandidates);
+        assert_eq!(Some(&hardest), first);
+
+        let mut solved = busy.clone();
+        solved.solve_first().unwrap();
+        assert_eq!(solved.hardest_step().unwrap(), None);
+    }
+}
@@ -0,0 +1,201 @@
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::parse::is_blank;
+use crate::trace;
+use crate::{i32_from_char, SolveStats, SudokuBoard, SudokuError};
+
+/// Bytes of the file handed to a thread at a time.  Chunks end at a line
+/// break, so each holds whole records.
+const CHUNK_SIZE: usize = 1 << 16;
+
+/// The lines of `bytes` holding a record, with the whitespace and line
+/// ending around it cut off, each with its number counted from 0.
+fn records(bytes: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    bytes
+        .split(|b| *b == b'\n')
+        .map(|line| line.trim_ascii())
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn solve_record(record: &[u8]) -> SolvedRecord {
+    let mut board = SudokuBoard::from_record(record)?;
+    let stats = board.solve_first()?;
+    Ok((board.as_bytes(), stats))
+}
+
+/// The solution of a record of a `MappedPuzzles`, as 81 digits, with how
+/// much guessing it needed.
+pub type SolvedRecord = Result<([u8; 81], SolveStats), SudokuError>;
+
+/// A file of puzzles, one on each line as 81 squares, mapped into memory so
+/// a collection far larger than it is worth reading into a `String` is
+/// solved without copying it: each record is read where it lies.  Blank
+/// lines are skipped, and line endings may be CRLF.
+///
+/// The file must not be changed while it is mapped.
+pub struct MappedPuzzles {
+    map: Mmap,
+}
+
+impl MappedPuzzles {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<MappedPuzzles> {
+        let file = File::open(path)?;
+        // Safety: the map is only read, and the file is documented to stay
+        // unchanged while it is mapped
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(MappedPuzzles { map })
+    }
+
+    /// The records of the file in order, each with its line number.
+    pub fn records(&self) -> impl Iterator<Item = (usize, &[u8])> {
+        records(&self.map).map(|(i, record)| (i + 1, record))
+    }
+
+    /// The file split into chunks of about `CHUNK_SIZE` bytes, each ending
+    /// just after a line break or at the end of the file.
+    fn chunks(&self) -> Vec<&[u8]> {
+        let mut chunks = Vec::new();
+        let mut rest: &[u8] = &self.map;
+        while !rest.is_empty() {
+            let end = match rest.iter().skip(CHUNK_SIZE).position(|b| *b == b'\n') {
+                Some(at) => CHUNK_SIZE + at + 1,
+                None => rest.len(),
+            };
+            let (chunk, tail) = rest.split_at(end);
+            chunks.push(chunk);
+            rest = tail;
+        }
+        chunks
+    }
+
+    /// Solve every record, spreading the chunks of the file over `jobs`
+    /// threads.  The solutions come back in the order of the file, with
+    /// the line of each record, as 81 digits like `as_bytes` with the
+    /// statistics of the solve.  A record that cannot be read fails like
+    /// `parse_grid`.
+    pub fn solve_all(&self, jobs: usize) -> Vec<(usize, SolvedRecord)> {
+        let chunks = self.chunks();
+        let solve_chunk = |chunk: &[u8]| -> Vec<_> {
+            records(chunk)
+                .map(|(i, record)| (i, solve_record(record)))
+                .collect()
+        };
+        let next = AtomicUsize::new(0);
+        let mut solved: Vec<Vec<_>> = chunks.iter().map(|_| Vec::new()).collect();
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..jobs.max(1))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let k = next.fetch_add(1, Ordering::Relaxed);
+                            match chunks.get(k) {
+                                Some(chunk) => done.push((k, solve_chunk(chunk))),
+                                None => return done,
+                            }
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                for (k, outcomes) in worker.join().unwrap() {
+                    solved[k] = outcomes;
+                }
+            }
+        });
+        // lines are numbered within their chunk, count on from the chunks
+        // before it
+        let mut outcomes = Vec::new();
+        let mut first_line = 1;
+        for (chunk, solved) in chunks.iter().zip(solved) {
+            outcomes.extend(
+                solved
+                    .into_iter()
+                    .map(|(i, result)| (first_line + i, result)),
+            );
+            first_line += chunk.iter().filter(|b| **b == b'\n').count();
+        }
+        outcomes
+    }
+}
+
+impl SudokuBoard {
+    /// Read a record of exactly 81 squares, as `parse_grid` reads a single
+    /// line, from bytes such as a line of a `MappedPuzzles`.  Unknown
+    /// squares are `0`, `.`, `-`, or `_`.
+    pub fn from_record(record: &[u8]) -> Result<SudokuBoard, SudokuError> {
+        let _span = trace::parse_span("record");
+        let mut cells = Vec::with_capacity(81);
+        for &b in record.trim_ascii() {
+            let c = b as char;
+            if is_blank(c) {
+                cells.push(None);
+            } else {
+                match i32_from_char(c) {
+                    Some(v) => cells.push(Some(v)),
+                    None => return Err(SudokuError::InvalidCharacter(c)),
+                }
+            }
+        }
+        if cells.len() != 81 {
+            return Err(SudokuError::InvalidCellCount(cells.len()));
+        }
+        SudokuBoard::from_cells(cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MappedPuzzles;
+    use crate::{SudokuBoard, SudokuError};
+    use std::fs;
+
+    const PUZZLE: &str =
+        "4....8.......91.8..865.2.3..2.4..9...1.2....6367.59........5...7..8...242..93..7.";
+
+    #[test]
+    fn test_mapped_puzzles() {
+        // enough records for several chunks, with a blank line and bad
+        // records among them
+        let mut contents = String::new();
+        for i in 1..=1000 {
+            match i {
+                3 => contents.push_str("\r\n"),
+                900 => contents.push_str("123\n"),
+                950 => contents.push_str(&format!("{}x\n", &PUZZLE[..80])),
+                _ => contents.push_str(&format!("{}\r\n", PUZZLE)),
+            }
+        }
+        let path = std::env::temp_dir().join(format!("sudoku_mapped_{}.txt", std::process::id()));
+        fs::write(&path, &contents).unwrap();
+        let puzzles = MappedPuzzles::open(&path).unwrap();
+        assert!(puzzles.chunks().len() > 1);
+        assert_eq!(puzzles.records().count(), 999);
+        assert_eq!(puzzles.records().nth(2).unwrap().0, 4);
+
+        let mut solution = SudokuBoard::from_record(PUZZLE.as_bytes()).unwrap();
+        solution.solve_first().unwrap();
+        let outcomes = puzzles.solve_all(3);
+        assert_eq!(outcomes.len(), 999);
+        for (line, outcome) in &outcomes {
+            match line {
+                900 => assert!(matches!(outcome, Err(SudokuError::InvalidCellCount(3)))),
+                950 => assert!(matches!(outcome, Err(SudokuError::InvalidCharacter('x')))),
+                _ => assert_eq!(outcome.as_ref().unwrap().0, solution.as_bytes()),
+            }
+        }
+        let lines: Vec<usize> = outcomes.iter().map(|(line, _)| *line).collect();
+        let expected: Vec<usize> = (1..=1000).filter(|&i| i != 3).collect();
+        assert_eq!(lines, expected);
+        drop(puzzles);
+        fs::remove_file(&path).unwrap();
+
+        assert!(SudokuBoard::from_record(&PUZZLE.as_bytes()[..80]).is_err());
+    }
+}
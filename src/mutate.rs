@@ -0,0 +1,180 @@
+use crate::{BoxValue, CellPos, SudokuBoard, SudokuError};
+
+/// The puzzle when it has exactly one solution.
+fn unique(puzzle: SudokuBoard) -> Result<SudokuBoard, SudokuError> {
+    match puzzle.count_solutions(2) {
+        0 => Err(SudokuError::NotSolvable(None)),
+        1 => Ok(puzzle),
+        _ => Err(SudokuError::MultipleSolutions),
+    }
+}
+
+fn not_given(pos: CellPos) -> SudokuError {
+    SudokuError::NotGiven {
+        row: pos.row.get(),
+        col: pos.col.get(),
+    }
+}
+
+fn already_known(pos: CellPos) -> SudokuError {
+    SudokuError::AlreadyKnown {
+        row: pos.row.get(),
+        col: pos.col.get(),
+    }
+}
+
+/// Small changes to the givens of a puzzle, each checked to leave exactly
+/// one solution, for tuning a puzzle one step at a time.  They work on the
+/// givens alone, squares filled in since are left out of the new puzzle,
+/// and the board itself is not changed.
+impl SudokuBoard {
+    fn givens(&self) -> Result<SudokuBoard, SudokuError> {
+        let mut puzzle = self.clone();
+        puzzle.reset_to_givens()?;
+        Ok(puzzle)
+    }
+
+    /// The digit of a square in the first solution of the puzzle.
+    fn solution_digit(puzzle: &SudokuBoard, pos: CellPos) -> Result<i32, SudokuError> {
+        let mut solution = puzzle.clone();
+        solution.solve_first()?;
+        match solution.cell(pos).value {
+            BoxValue::Known(v) => Ok(v),
+            BoxValue::Unknown(_) => Err(SudokuError::NoFullySolved),
+        }
+    }
+
+    /// The puzzle without the given of a square.  Fails with `NotGiven`
+    /// when the square is not a given, and with `MultipleSolutions` when
+    /// the given was needed.
+    pub fn remove_clue(&self, pos: CellPos) -> Result<SudokuBoard, SudokuError> {
+        let mut puzzle = self.givens()?;
+        if !puzzle.is_given(pos) {
+            return Err(not_given(pos));
+        }
+        puzzle.clear(pos)?;
+        unique(puzzle)
+    }
+
+    /// The puzzle with a new given in an unknown square, its digit taken
+    /// from the solution.  Fails with `AlreadyKnown` when the square is a
+    /// given, with `NotSolvable` when the puzzle has no solution, and with
+    /// `MultipleSolutions` when the new puzzle still has several.
+    pub fn add_clue(&self, pos: CellPos) -> Result<SudokuBoard, SudokuError> {
+        let mut puzzle = self.givens()?;
+        if puzzle.is_given(pos) {
+            return Err(already_known(pos));
+        }
+        let digit = SudokuBoard::solution_digit(&puzzle, pos)?;
+        puzzle.mark_as_given(pos.row.get(), pos.col.get(), digit)?;
+        unique(puzzle)
+    }
+
+    /// The puzzle with the given of `from` moved to the unknown square
+    /// `to`, filled in from the solution, so the solution stays the same.
+    /// Fails like `remove_clue` and `add_clue`, with `MultipleSolutions`
+    /// when the moved given no longer pins the solution down.
+    pub fn swap_clue(&self, from: CellPos, to: CellPos) -> Result<SudokuBoard, SudokuError> {
+        let mut puzzle = self.givens()?;
+        if !puzzle.is_given(from) {
+            return Err(not_given(from));
+        }
+        if puzzle.is_given(to) {
+            return Err(already_known(to));
+        }
+        let digit = SudokuBoard::solution_digit(&puzzle, to)?;
+        puzzle.clear(from)?;
+        puzzle.mark_as_given(to.row.get(), to.col.get(), digit)?;
+        unique(puzzle)
+    }
+
+    /// The squares the given of a square could be moved to with
+    /// `swap_clue`, keeping a single solution.
+    pub fn clue_swaps(&self, pos: CellPos) -> Result<Vec<CellPos>, SudokuError> {
+        let puzzle = self.givens()?;
+        if !puzzle.is_given(pos) {
+            return Err(not_given(pos));
+        }
+        let mut squares = Vec::new();
+        for node in puzzle.board.iter().flatten().filter(|node| !node.given) {
+            if puzzle.swap_clue(pos, node.pos()).is_ok() {
+                squares.push(node.pos());
+            }
+        }
+        Ok(squares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::{CellPos, SudokuBoard, SudokuError};
+
+    #[test]
+    fn test_mutations() {
+        let puzzle = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        let mut solution = puzzle.clone();
+        solution.solve_first().unwrap();
+
+        // adding a clue keeps the solution
+        let more = puzzle.add_clue(at(1, 2)).unwrap();
+        assert!(more.is_given(at(1, 2)));
+        assert_eq!(more.get(at(1, 2)), solution.get(at(1, 2)));
+        assert_eq!(more.unknown_count(), puzzle.unknown_count() - 1);
+        assert!(matches!(
+            puzzle.add_clue(at(1, 1)),
+            Err(SudokuError::AlreadyKnown { row: 1, col: 1 })
+        ));
+
+        // and removing it again gives back the puzzle
+        assert!(more.remove_clue(at(1, 2)).unwrap() == puzzle);
+        assert!(matches!(
+            puzzle.remove_clue(at(1, 2)),
+            Err(SudokuError::NotGiven { row: 1, col: 2 })
+        ));
+        for pos in puzzle.redundant_clues() {
+            assert!(puzzle.remove_clue(pos).is_ok());
+        }
+
+        // the given of r1c1 is needed where it is, the one of r1c6 can move
+        assert!(puzzle.clue_swaps(at(1, 1)).unwrap().is_empty());
+        let targets = puzzle.clue_swaps(at(1, 6)).unwrap();
+        assert!(!targets.is_empty());
+        let to = targets[0];
+        let moved = puzzle.swap_clue(at(1, 6), to).unwrap();
+        assert!(!moved.is_given(at(1, 6)) && moved.is_given(to));
+        let mut moved_solution = moved.clone();
+        moved_solution.solve_first().unwrap();
+        assert!(moved_solution == solution);
+
+        let elsewhere = (0..81)
+            .map(|i| CellPos::from_index(i).unwrap())
+            .find(|pos| !puzzle.is_given(*pos) && !targets.contains(pos))
+            .unwrap();
+        assert!(matches!(
+            puzzle.swap_clue(at(1, 6), elsewhere),
+            Err(SudokuError::MultipleSolutions)
+        ));
+
+        // squares filled in by solving are not givens
+        assert!(matches!(
+            solution.remove_clue(at(1, 2)),
+            Err(SudokuError::NotGiven { row: 1, col: 2 })
+        ));
+        assert!(matches!(
+            SudokuBoard::new().add_clue(at(1, 1)),
+            Err(SudokuError::MultipleSolutions)
+        ));
+    }
+}
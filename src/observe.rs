@@ -0,0 +1,187 @@
+use crate::trace;
+use crate::{BoxValue, SolveStats, SudokuBoard, SudokuError, SudokuResult, Technique};
+
+/// Why a square became known or lost an alternative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cause {
+    // The square had only one alternative left
+    Single,
+    // The solver tried the digit, and takes it back if it leads nowhere
+    Guess,
+    // The digit became known in the square at (row, col) of the same house
+    Peer { row: usize, col: usize },
+    // A logical step of the technique found it
+    Technique(Technique),
+}
+
+/// A change to a square, reported to an observer as it happens.  Squares
+/// are numbered from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    // The square became known
+    Known {
+        row: usize,
+        col: usize,
+        digit: u8,
+        cause: Cause,
+    },
+    // The digit was removed from the alternatives of the square
+    Eliminated {
+        row: usize,
+        col: usize,
+        digit: u8,
+        cause: Cause,
+    },
+    // The guess led to a contradiction.  It is taken back together with
+    // every change reported after it
+    Backtracked {
+        row: usize,
+        col: usize,
+        digit: u8,
+    },
+}
+
+/// Called with each change while solving.  None when nobody is listening,
+/// so the solver does not spend time describing its changes.
+pub(crate) type Observer<'a, 'b> = Option<&'a mut (dyn FnMut(&Event) + 'b)>;
+
+impl SudokuBoard {
+    /// Fill in the solution `solve_outcome_first` finds, calling `observer`
+    /// whenever a square becomes known or loses an alternative.
+    pub fn solve_with_observer(
+        &mut self,
+        mut observer: impl FnMut(&Event),
+    ) -> Result<SolveStats, SudokuError> {
+        let mut stats = SolveStats::default();
+        self.search(&mut stats, None, Some(&mut observer))?;
+        Ok(stats)
+    }
+
+    /// Apply a step like `apply_step`, calling `observer` for each change
+    /// the step makes.
+    pub fn apply_step_with_observer(
+        &mut self,
+        step: &crate::Step,
+        mut observer: impl FnMut(&Event),
+    ) -> SudokuResult {
+        self.apply_step_observed(step, Some(&mut observer))
+    }
+
+    /// Mark a square as known, first telling the observer about it and
+    /// about the alternatives it removes from the squares of its houses.
+    pub(crate) fn place(
+        &mut self,
+        row: usize,
+        col: usize,
+        digit: i32,
+        cause: Cause,
+        observer: Observer,
+    ) -> SudokuResult {
+        if let Some(observer) = observer {
+            observer(&Event::Known {
+                row,
+                col,
+                digit: digit as u8,
+                cause,
+            });
+            for (r, c) in self.peers(row, col) {
+                if matches!(&self.board[r - 1][c - 1].value, BoxValue::Unknown(v) if v.contains(&digit))
+                {
+                    observer(&Event::Eliminated {
+                        row: r,
+                        col: c,
+                        digit: digit as u8,
+                        cause: Cause::Peer { row, col },
+                    });
+                }
+            }
+        }
+        trace::placed(self, row, col, digit, cause);
+        self.mark_as_known(row, col, digit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cause, Event};
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_solve_with_observer() {
+        let s = concat!(
+            "500300600",
+            "004001750",
+            "000059100",
+            "403200070",
+            "006000000",
+            "000000904",
+            "700090315",
+            "035000806",
+            "619080000"
+        );
+        let mut events = Vec::new();
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let stats = sboard.solve_with_observer(|e| events.push(*e)).unwrap();
+
+        let guesses = events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    Event::Known {
+                        cause: Cause::Guess,
+                        ..
+                    }
+                )
+            })
+            .count();
+        let backtracks = events
+            .iter()
+            .filter(|e| matches!(e, Event::Backtracked { .. }))
+            .count();
+        assert_eq!(guesses, stats.guesses);
+        assert_eq!(backtracks, stats.backtracks);
+        assert!(events.contains(&Event::Known {
+            row: 9,
+            col: 9,
+            digit: 7,
+            cause: Cause::Single
+        }));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            Event::Eliminated {
+                cause: Cause::Peer { .. },
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_apply_step_with_observer() {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let step = sboard.next_step().unwrap();
+        let mut events = Vec::new();
+        sboard
+            .apply_step_with_observer(&step, |e| events.push(*e))
+            .unwrap();
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|e| match e {
+            Event::Known { cause, .. } | Event::Eliminated { cause, .. } => {
+                matches!(cause, Cause::Technique(t) if *t == step.technique)
+                    || matches!(cause, Cause::Peer { .. })
+            }
+            Event::Backtracked { .. } => false,
+        }));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{Cause, CellPos, Event, SudokuBoard, SudokuError};
+use std::fmt;
+
+/// The order the solver filled in the squares of a board.  Squares filled
+/// in by a guess that was taken back do not count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveOrder {
+    numbers: [[Option<usize>; 9]; 9],
+}
+
+impl SolveOrder {
+    /// The number of the square in the order it was filled in, counting
+    /// from 1, or None for a given.
+    pub fn get(&self, pos: CellPos) -> Option<usize> {
+        self.numbers[pos.row.index()][pos.col.index()]
+    }
+
+    /// The number of squares the solver filled in.
+    pub fn len(&self) -> usize {
+        self.numbers.iter().flatten().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 9 lines of 9 numbers, with givens shown as `.` and the 3x3 squares
+/// separated, to print beside the solution.
+impl fmt::Display for SolveOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut lines = Vec::new();
+        for (r, row) in self.numbers.iter().enumerate() {
+            if r == 3 || r == 6 {
+                lines.push(["--------"; 3].join("-+-"));
+            }
+            let boxes: Vec<String> = row
+                .chunks(3)
+                .map(|squares| {
+                    squares
+                        .iter()
+                        .map(|n| match n {
+                            Some(n) => format!("{:>2}", n),
+                            None => " .".to_string(),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect();
+            lines.push(boxes.join(" | "));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl SudokuBoard {
+    /// Fill in the solution `solve_outcome_first` finds, keeping the order
+    /// the squares were filled in.
+    pub fn solve_with_order(&mut self) -> Result<SolveOrder, SudokuError> {
+        let mut placed = Vec::new();
+        // how many squares were placed before each guess not taken back
+        let mut guesses = Vec::new();
+        self.solve_with_observer(|event| match *event {
+            Event::Known {
+                row, col, cause, ..
+            } => {
+                if cause == Cause::Guess {
+                    guesses.push(placed.len());
+                }
+                placed.push((row, col));
+            }
+            Event::Backtracked { .. } => {
+                if let Some(len) = guesses.pop() {
+                    placed.truncate(len);
+                }
+            }
+            Event::Eliminated { .. } => (),
+        })?;
+        let mut numbers = [[None; 9]; 9];
+        for (i, (row, col)) in placed.into_iter().enumerate() {
+            numbers[row - 1][col - 1] = Some(i + 1);
+        }
+        Ok(SolveOrder { numbers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_solve_with_order() {
+        let s = concat!(
+            "500300600",
+            "004001750",
+            "000059100",
+            "403200070",
+            "006000000",
+            "000000904",
+            "700090315",
+            "035000806",
+            "619080000"
+        );
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let unknown = sboard.unknown_count();
+        let order = sboard.solve_with_order().unwrap();
+        assert!(sboard.is_solved());
+        assert_eq!(order.len(), unknown);
+        assert_eq!(order.get(at(1, 1)), None);
+        // every square filled in has its own number, with no gaps left by
+        // guesses taken back
+        let mut numbers: Vec<usize> = (1..=9)
+            .flat_map(|r| (1..=9).map(move |c| (r, c)))
+            .filter_map(|(r, c)| order.get(at(r, c)))
+            .collect();
+        numbers.sort();
+        assert_eq!(numbers, (1..=unknown).collect::<Vec<usize>>());
+
+        let text = order.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert!(lines[0].starts_with(" . "));
+        assert_eq!(lines[3], "---------+----------+---------");
+        assert_eq!(lines[0].len(), lines[3].len());
+
+        // an empty board is all guessing, starting at the top left
+        let mut sboard = SudokuBoard::new();
+        let order = sboard.solve_with_order().unwrap();
+        assert_eq!(order.get(at(1, 1)), Some(1));
+        assert_eq!(order.len(), 81);
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::{SolveStats, Solver, SolverOptions, SudokuBoard, SudokuError};
+
+/// A board waiting to be searched, after its guess and singles, with the
+/// Zobrist hash of its state.
+struct Task {
+    board: SudokuBoard,
+    hash: u64,
+    parent: Option<Arc<Node>>,
+}
+
+/// A board whose alternatives were queued.  When the last of them runs
+/// into a dead end, the board has no solution either.
+struct Node {
+    hash: u64,
+    open: AtomicUsize,
+    parent: Option<Arc<Node>>,
+}
+
+/// What the threads of a parallel solve share.
+struct Shared<'a> {
+    // The boards each thread has still to search.  A thread takes the
+    // newest of its own, and steals the oldest of another's when it runs out
+    queues: Vec<Mutex<VecDeque<Task>>>,
+    // Holds the states found to lead nowhere, so no thread searches them
+    // twice, and keeps them for the next solve
+    solver: Mutex<&'a mut Solver>,
+    // Boards queued or being searched
+    pending: AtomicUsize,
+    guesses: AtomicUsize,
+    backtracks: AtomicUsize,
+    solution: Mutex<Option<SudokuBoard>>,
+    done: AtomicBool,
+}
+
+impl Shared<'_> {
+    fn take(&self, worker: usize) -> Option<Task> {
+        if let Some(task) = self.queues[worker].lock().unwrap().pop_back() {
+            return Some(task);
+        }
+        let n = self.queues.len();
+        (1..n).find_map(|k| self.queues[(worker + k) % n].lock().unwrap().pop_front())
+    }
+
+    /// Search boards until one is solved, or there are none left anywhere.
+    fn work(&self, worker: usize) {
+        while !self.done.load(Ordering::Acquire) {
+            match self.take(worker) {
+                Some(task) => {
+                    self.expand(worker, task);
+                    self.pending.fetch_sub(1, Ordering::AcqRel);
+                }
+                None if self.pending.load(Ordering::Acquire) == 0 => return,
+                None => thread::yield_now(),
+            }
+        }
+    }
+
+    /// Back out of a guess that led nowhere.  A board left with no open
+    /// alternatives is dead, and so is the guess that led to it.
+    fn dead_end(&self, mut parent: Option<Arc<Node>>) {
+        while let Some(node) = parent {
+            self.backtracks.fetch_add(1, Ordering::Relaxed);
+            if node.open.fetch_sub(1, Ordering::AcqRel) != 1 {
+                return;
+            }
+            self.solver.lock().unwrap().remember_dead(node.hash);
+            parent = node.parent.clone();
+        }
+    }
+
+    /// Queue a board for each alternative of the square with the fewest.
+    fn expand(&self, worker: usize, task: Task) {
+        let Task {
+            board,
+            hash,
+            parent,
+        } = task;
+        let (row, col, alternatives) = match board.guess_square() {
+            Some(guess) => guess,
+            None => {
+                self.solution.lock().unwrap().get_or_insert(board);
+                self.done.store(true, Ordering::Release);
+                return;
+            }
+        };
+        if alternatives.is_empty() || self.solver.lock().unwrap().is_dead(hash) {
+            self.dead_end(parent);
+            return;
+        }
+        let node = Arc::new(Node {
+            hash,
+            open: AtomicUsize::new(alternatives.len()),
+            parent,
+        });
+        let mut next = Vec::with_capacity(alternatives.len());
+        // queued in reverse so the smallest alternative is taken first
+        for digit in alternatives.into_iter().rev() {
+            self.guesses.fetch_add(1, Ordering::Relaxed);
+            match Solver::guess(&board, hash, row, col, digit) {
+                Some((board, hash)) => next.push(Task {
+                    board,
+                    hash,
+                    parent: Some(Arc::clone(&node)),
+                }),
+                None => self.dead_end(Some(Arc::clone(&node))),
+            }
+        }
+        self.pending.fetch_add(next.len(), Ordering::AcqRel);
+        self.queues[worker].lock().unwrap().extend(next);
+    }
+}
+
+impl Solver {
+    /// Solve the board like `solve`, searching on `threads` threads at
+    /// once, or one per core when 0.  Each guess queues its alternatives
+    /// for any idle thread to take.  A state is remembered as dead once
+    /// every one of its alternatives has been, and all threads skip it,
+    /// in this solve and the next ones.
+    ///
+    /// Which thread finishes first is up to the scheduler, so on more than
+    /// one thread a puzzle with several solutions can end up with a
+    /// different one on each run, and the counts vary too.
+    pub fn solve_parallel(
+        &mut self,
+        board: &mut SudokuBoard,
+        threads: usize,
+    ) -> Result<SolveStats, SudokuError> {
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let mut root = board.clone();
+        let hash = root.fill_singles_hashed(board.zobrist_hash())?;
+        let shared = Shared {
+            queues: (0..threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+            solver: Mutex::new(self),
+            pending: AtomicUsize::new(1),
+            guesses: AtomicUsize::new(0),
+            backtracks: AtomicUsize::new(0),
+            solution: Mutex::new(None),
+            done: AtomicBool::new(false),
+        };
+        shared.queues[0].lock().unwrap().push_back(Task {
+            board: root,
+            hash,
+            parent: None,
+        });
+        thread::scope(|scope| {
+            for worker in 0..threads {
+                let shared = &shared;
+                scope.spawn(move || shared.work(worker));
+            }
+        });
+        *board = shared
+            .solution
+            .into_inner()
+            .unwrap()
+            .ok_or(SudokuError::NotSolvable(None))?;
+        Ok(SolveStats {
+            guesses: shared.guesses.into_inner(),
+            backtracks: shared.backtracks.into_inner(),
+        })
+    }
+}
+
+impl SudokuBoard {
+    /// Fill in the solution `solve_outcome_first` finds, searching on
+    /// `threads` threads at once, or one per core when 0, with a `Solver` of
+    /// its own.  No state comes up twice in one solve, so to skip the dead
+    /// ends of earlier boards use `Solver::solve_parallel` instead.
+    pub fn solve_parallel(&mut self, threads: usize) -> Result<SolveStats, SudokuError> {
+        Solver::new(SolverOptions::default()).solve_parallel(self, threads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Solver, SolverOptions, SudokuBoard, SudokuError};
+
+    #[test]
+    fn test_solve_parallel() {
+        let s = concat!(
+            "---------",
+            "-----3-85",
+            "--1-2----",
+            "---5-7---",
+            "--4---1--",
+            "-9-------",
+            "5------73",
+            "--2-1----",
+            "----4---9"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        let mut expected = puzzle.clone();
+        expected.solve_first().unwrap();
+        for threads in [0, 1, 4] {
+            let mut sboard = puzzle.clone();
+            let stats = sboard.solve_parallel(threads).unwrap();
+            assert!(sboard == expected && sboard.is_solved());
+            assert!(stats.guesses > 0);
+        }
+
+        let mut empty = SudokuBoard::new();
+        empty.solve_parallel(4).unwrap();
+        assert!(empty.is_solved() && empty.is_valid());
+
+        // 9 cannot go anywhere in row 1
+        let s = "---123456--9".to_string() + &"-".repeat(69);
+        let mut unsolvable = SudokuBoard::fill_board(&s).unwrap();
+        let before = unsolvable.clone();
+        assert!(matches!(
+            unsolvable.solve_parallel(4),
+            Err(SudokuError::NotSolvable(None))
+        ));
+        assert!(unsolvable == before);
+    }
+
+    #[test]
+    fn test_solver_parallel() {
+        let s = concat!(
+            "---------",
+            "-----3-85",
+            "--1-2----",
+            "---5-7---",
+            "--4---1--",
+            "-9-------",
+            "5------73",
+            "--2-1----",
+            "----4---9"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        let mut expected = puzzle.clone();
+        expected.solve_first().unwrap();
+
+        // no state comes up twice in one solve
+        let mut solver = Solver::new(SolverOptions::default());
+        let mut first = puzzle.clone();
+        let first_stats = solver.solve_parallel(&mut first, 1).unwrap();
+        assert!(first == expected);
+        assert_eq!(solver.skipped(), 0);
+
+        // solving again skips the states found dead the first time
+        let mut again = puzzle.clone();
+        let stats = solver.solve_parallel(&mut again, 1).unwrap();
+        assert!(again == expected);
+        assert!(solver.skipped() > 0);
+        assert!(stats.backtracks < first_stats.backtracks);
+
+        let mut again = puzzle.clone();
+        solver.solve_parallel(&mut again, 4).unwrap();
+        assert!(again == expected);
+    }
+}
@@ -0,0 +1,453 @@
+use crate::trace;
+use crate::{i32_from_char, BoxValue, SudokuBoard, SudokuError};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// What strict parsing found wrong with the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    // A character that is neither a cell nor whitespace
+    UnexpectedCharacter,
+    // The input ended before 81 cells were read.  Holds the number of cells found
+    TooFewCells(usize),
+    // A cell was found after the 81st
+    TooManyCells,
+}
+
+/// A strict parsing error and where in the input it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Byte offset into the input
+    pub offset: usize,
+    /// Line in the input, starting at 1
+    pub line: usize,
+    /// Character column in the line, starting at 1
+    pub column: usize,
+    /// The offending character, `None` when the input ended early
+    pub found: Option<char>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match (&self.kind, self.found) {
+            (ParseErrorKind::UnexpectedCharacter, Some(c)) => {
+                write!(f, "unexpected character {:?}", c)
+            }
+            (ParseErrorKind::UnexpectedCharacter, None) => write!(f, "unexpected character"),
+            (ParseErrorKind::TooFewCells(n), _) => write!(f, "expected 81 squares, found {}", n),
+            (ParseErrorKind::TooManyCells, _) => write!(f, "more than 81 squares"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Characters used to draw the frame of a pretty printed grid.  Box-drawing
+/// characters are accepted too so grids copied from other tools load as well.
+fn is_decoration(c: char) -> bool {
+    matches!(c, '|' | '+' | '=') || c.is_whitespace() || ('\u{2500}'..='\u{257f}').contains(&c)
+}
+
+/// Characters used to mark a square whose value is not known.
+pub fn is_blank(c: char) -> bool {
+    matches!(c, '0' | '.' | '-' | '_')
+}
+
+/// A separator line only draws the frame between rows of boxes.
+///
+/// Dashes are both a frame character and a blank square, so a line without
+/// any other cell characters is a row of blank squares only when it has
+/// exactly 9 dashes and no joints, e.g. `--- | --- | ---`, or a whole empty
+/// grid written on one line when it has exactly 81.
+pub(crate) fn is_separator(line: &str) -> bool {
+    let mut dashes = 0;
+    let mut joints = false;
+    for c in line.chars() {
+        match c {
+            '-' => dashes += 1,
+            '+' | '=' => joints = true,
+            c if is_decoration(c) => (),
+            _ => return false,
+        }
+    }
+    joints || (dashes != 9 && dashes != 81)
+}
+
+impl SudokuBoard {
+    /// Initialize the board from a grid written over several lines, such as
+    ///
+    /// ```text
+    /// 5 3 . | . 7 . | . . .
+    /// 6 . . | 1 9 5 | . . .
+    /// . 9 8 | . . . | . 6 .
+    /// ------+-------+------
+    /// ...
+    /// ```
+    ///
+    /// Pipes, plus signs, spaces, and separator lines are stripped.  Unknown
+    /// squares can be marked with `0`, `.`, `-`, or `_`.  Exactly 81 cells
+    /// must be found, otherwise `InvalidCellCount` is returned.
+    pub fn parse_grid(s: &str) -> Result<SudokuBoard, SudokuError> {
+        let _span = trace::parse_span("grid");
+        let mut cells: Vec<Option<i32>> = Vec::with_capacity(81);
+        for line in s.lines().filter(|line| !is_separator(line)) {
+            for c in line.chars().filter(|c| !is_decoration(*c)) {
+                if is_blank(c) {
+                    cells.push(None);
+                } else {
+                    match i32_from_char(c) {
+                        Some(v) => cells.push(Some(v)),
+                        None => return Err(SudokuError::InvalidCharacter(c)),
+                    }
+                }
+            }
+        }
+        if cells.len() != 81 {
+            return Err(SudokuError::InvalidCellCount(cells.len()));
+        }
+
+        SudokuBoard::from_cells(cells)
+    }
+
+    /// Initialize the board from exactly 81 cells separated by nothing but
+    /// whitespace.  Unknown squares can be marked with `0`, `.`, `-`, or `_`.
+    ///
+    /// Unlike `fill_board`, nothing is silently skipped: an unexpected
+    /// character, a missing cell, or an extra cell is reported as a
+    /// `SudokuError::Parse` holding the position of the problem.
+    pub fn parse_strict(s: &str) -> Result<SudokuBoard, SudokuError> {
+        let _span = trace::parse_span("strict");
+        let mut cells: Vec<Option<i32>> = Vec::with_capacity(81);
+        let mut line = 1;
+        let mut column = 0;
+        for (offset, c) in s.char_indices() {
+            column += 1;
+            let error = |kind| {
+                SudokuError::Parse(ParseError {
+                    kind,
+                    offset,
+                    line,
+                    column,
+                    found: Some(c),
+                })
+            };
+            if c == '\n' {
+                line += 1;
+                column = 0;
+            } else if c.is_whitespace() {
+                continue;
+            } else if is_blank(c) || i32_from_char(c).is_some() {
+                if cells.len() == 81 {
+                    return Err(error(ParseErrorKind::TooManyCells));
+                }
+                cells.push(i32_from_char(c));
+            } else {
+                return Err(error(ParseErrorKind::UnexpectedCharacter));
+            }
+        }
+        if cells.len() != 81 {
+            return Err(SudokuError::Parse(ParseError {
+                kind: ParseErrorKind::TooFewCells(cells.len()),
+                offset: s.len(),
+                line,
+                column: column + 1,
+                found: None,
+            }));
+        }
+
+        SudokuBoard::from_cells(cells)
+    }
+
+    /// Initialize the board from a grid of pencil marks, 81 cells separated
+    /// by whitespace where each cell lists the candidates left for it:
+    ///
+    /// ```text
+    /// 5   3   124 | 26  7   ...
+    /// ```
+    ///
+    /// A cell with a single digit is a known value.  Frame characters and
+    /// separator lines are stripped as in `parse_grid`.
+    pub fn parse_pencil_marks(s: &str) -> Result<SudokuBoard, SudokuError> {
+        let mut cells: Vec<&str> = Vec::with_capacity(81);
+        for line in s.lines().filter(|line| !is_separator(line)) {
+            cells.extend(
+                line.split(|c: char| is_decoration(c))
+                    .filter(|t| !t.is_empty()),
+            );
+        }
+        if cells.len() != 81 {
+            return Err(SudokuError::InvalidCellCount(cells.len()));
+        }
+        let mut marks = Vec::with_capacity(81);
+        for cell in &cells {
+            let mut digits = Vec::new();
+            for c in cell.chars() {
+                match i32_from_char(c) {
+                    Some(v) => digits.push(v as u8),
+                    None => return Err(SudokuError::InvalidCharacter(c)),
+                };
+            }
+            marks.push(digits);
+        }
+        SudokuBoard::from_candidates(&marks)
+    }
+
+    /// Initialize the board from the candidates of all 81 squares, row by
+    /// row, such as pencil marks taken from another program or an earlier
+    /// session.  A square with a single candidate is a known value, and
+    /// candidates already known in a square's row, column, or box are
+    /// dropped.  Nothing else is worked out again, so solving goes on from
+    /// where the candidates left off.
+    ///
+    /// Fails with `InvalidCellCount` unless there are 81 squares, with
+    /// `InvalidRange` for a digit outside 1 to 9, with `ConflictingGivens`
+    /// when two squares of one house have the same single candidate, and
+    /// with `NotSolvable` when a square is left without candidates.
+    pub fn from_candidates<D: AsRef<[u8]>>(cells: &[D]) -> Result<SudokuBoard, SudokuError> {
+        if cells.len() != 81 {
+            return Err(SudokuError::InvalidCellCount(cells.len()));
+        }
+        let mut marks = Vec::with_capacity(81);
+        for cell in cells {
+            let mut set = BTreeSet::new();
+            for d in cell.as_ref() {
+                if !(1..=9).contains(d) {
+                    return Err(SudokuError::InvalidRange);
+                }
+                set.insert(i32::from(*d));
+            }
+            marks.push(set);
+        }
+
+        let mut board = SudokuBoard::new();
+        for (i, set) in marks.iter().enumerate() {
+            if set.len() == 1 {
+                let (row, col, digit) = (i / 9 + 1, i % 9 + 1, *set.first().unwrap());
+                if let Some(first) = board.clash(row, col, digit as u8) {
+                    return Err(SudokuError::ConflictingGivens {
+                        digit: digit as u8,
+                        first,
+                        second: (row, col),
+                    });
+                }
+                board.mark_as_known(row, col, digit)?;
+            }
+        }
+        for (node, set) in board.board.iter_mut().flatten().zip(&marks) {
+            if let BoxValue::Unknown(candidates) = &mut node.value {
+                candidates.retain(|v| set.contains(v));
+                if candidates.is_empty() {
+                    return Err(SudokuError::NotSolvable(None));
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    /// Place the known values of 81 parsed cells on a new board.
+    pub(crate) fn from_cells(cells: Vec<Option<i32>>) -> Result<SudokuBoard, SudokuError> {
+        let mut board = SudokuBoard::new();
+        for (i, value) in cells.into_iter().enumerate() {
+            if let Some(known_value) = value {
+                board.mark_as_given(i / 9 + 1, i % 9 + 1, known_value)?;
+            }
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::ParseError;
+    use crate::ParseErrorKind;
+    use crate::SudokuBoard;
+    use crate::SudokuError;
+
+    #[test]
+    fn test_parse_grid_decorated() {
+        let s = concat!(
+            "+-------+-------+-------+\n",
+            "| 5 3 . | . 7 . | . . . |\n",
+            "| 6 . . | 1 9 5 | . . . |\n",
+            "| . 9 8 | . . . | . 6 . |\n",
+            "+-------+-------+-------+\n",
+            "| 8 . . | . 6 . | . . 3 |\n",
+            "| 4 . . | 8 . 3 | . . 1 |\n",
+            "| 7 . . | . 2 . | . . 6 |\n",
+            "+-------+-------+-------+\n",
+            "| . 6 . | . . . | 2 8 . |\n",
+            "| . . . | 4 1 9 | . . 5 |\n",
+            "| . . . | . 8 . | . 7 9 |\n",
+            "+-------+-------+-------+\n",
+        );
+        let board = SudokuBoard::parse_grid(s).unwrap();
+        assert_eq!(
+            board.print_board(),
+            concat!(
+                "53--7----",
+                "6--195---",
+                "-98----6-",
+                "8---6---3",
+                "4--8-3--1",
+                "7---2---6",
+                "-6----28-",
+                "---419--5",
+                "----8--79"
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_grid_dash_rows() {
+        let s = concat!(
+            "--- | --- | ---\n",
+            "---------------\n",
+            "4-- | --8 | ---\n",
+            "--- | -91 | ---\n",
+            "-86 | 5-2 | -3-\n",
+            "--- | --- | ---\n",
+            "--- | --- | ---\n",
+            "--- | --- | ---\n",
+            "--- | --- | ---\n",
+            "--- | --- | ---\n",
+        );
+        let board = SudokuBoard::parse_grid(s).unwrap();
+        assert_eq!(&board.print_board()[..18], "---------4----8---");
+
+        // an empty grid on one line
+        let empty = SudokuBoard::parse_grid(&"-".repeat(81)).unwrap();
+        assert_eq!(empty.print_board(), "-".repeat(81));
+    }
+
+    #[test]
+    fn test_parse_grid_cell_count() {
+        match SudokuBoard::parse_grid("123 | 456 | 78\n") {
+            Err(SudokuError::InvalidCellCount(8)) => (),
+            _ => panic!("expected an invalid cell count"),
+        }
+        match SudokuBoard::parse_grid("12x | 456 | 789\n") {
+            Err(SudokuError::InvalidCharacter('x')) => (),
+            _ => panic!("expected an invalid character"),
+        }
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        let s = concat!(
+            "4....8...\n",
+            "----91-8-\n",
+            "-865-2-3-\n",
+            "020400900\n",
+            "-1-2----6\n",
+            "367-59---\n",
+            "-----5---\n",
+            "7--8---24\n",
+            "2--93--7-\n",
+        );
+        let board = SudokuBoard::parse_strict(s).unwrap();
+        assert_eq!(&board.print_board()[..9], "4----8---");
+
+        match SudokuBoard::parse_strict(&s.replace("91-8", "91x8")) {
+            Err(SudokuError::Parse(e)) => assert_eq!(
+                e,
+                ParseError {
+                    kind: ParseErrorKind::UnexpectedCharacter,
+                    offset: 16,
+                    line: 2,
+                    column: 7,
+                    found: Some('x'),
+                }
+            ),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_pencil_marks() {
+        let mut s = String::from("4 12 123 | 5 6 7 | 8 9 123\n");
+        s += "------+-------+------\n";
+        s += &"123456789 ".repeat(72);
+        let board = SudokuBoard::parse_pencil_marks(&s).unwrap();
+        assert_eq!(&board.print_board()[..9], "4--56789-");
+        assert_eq!(&board.print_possibility()[..9], "K23KKKKK3");
+        let marks = board.print_pencil_marks();
+        assert!(marks.starts_with("4 12 123 5 6 7 8 9 123 "));
+        let read_back = SudokuBoard::parse_pencil_marks(&marks).unwrap();
+        assert_eq!(read_back.print_pencil_marks(), marks);
+
+        match SudokuBoard::parse_pencil_marks("1 2 3") {
+            Err(SudokuError::InvalidCellCount(3)) => (),
+            _ => panic!("expected an invalid cell count"),
+        }
+        match SudokuBoard::parse_pencil_marks(&s.replacen("12", "1x", 1)) {
+            Err(SudokuError::InvalidCharacter('x')) => (),
+            _ => panic!("expected an invalid character"),
+        }
+    }
+
+    #[test]
+    fn test_from_candidates() {
+        let mut marks = vec![vec![1, 2, 3, 4, 5, 6, 7, 8, 9]; 81];
+        marks[0] = vec![8, 9];
+        marks[1] = vec![9];
+        let board = SudokuBoard::from_candidates(&marks).unwrap();
+        assert_eq!(&board.print_board()[..9], "-9-------");
+        // 9 is known in the row, so only 8 is left
+        assert_eq!(&board.print_possibility()[..2], "1K");
+
+        marks[1] = vec![1, 2, 3, 4, 5, 6, 7];
+        let mut board = SudokuBoard::from_candidates(&marks).unwrap();
+        board.solve_first().unwrap();
+        assert_eq!(board.get(at(1, 1)), Some(8));
+        assert!(board.is_valid() && board.is_solved());
+
+        match SudokuBoard::from_candidates(&marks[..80]) {
+            Err(SudokuError::InvalidCellCount(80)) => (),
+            _ => panic!("expected an invalid cell count"),
+        }
+        marks[2] = vec![0];
+        assert!(matches!(
+            SudokuBoard::from_candidates(&marks),
+            Err(SudokuError::InvalidRange)
+        ));
+        marks[2] = vec![];
+        assert!(matches!(
+            SudokuBoard::from_candidates(&marks),
+            Err(SudokuError::NotSolvable(None))
+        ));
+
+        // two squares of row 1 holding only 9
+        marks[2] = vec![9];
+        marks[5] = vec![9];
+        assert!(matches!(
+            SudokuBoard::from_candidates(&marks),
+            Err(SudokuError::ConflictingGivens {
+                digit: 9,
+                first: (1, 3),
+                second: (1, 6),
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_strict_cell_count() {
+        let s = "4----8---".repeat(9);
+        match SudokuBoard::parse_strict(&s[..80]) {
+            Err(SudokuError::Parse(e)) => {
+                assert_eq!(e.kind, ParseErrorKind::TooFewCells(80));
+                assert_eq!((e.offset, e.line, e.column, e.found), (80, 1, 81, None));
+            }
+            _ => panic!("expected a parse error"),
+        }
+        match SudokuBoard::parse_strict(&(s + "\n1")) {
+            Err(SudokuError::Parse(e)) => {
+                assert_eq!(e.kind, ParseErrorKind::TooManyCells);
+                assert_eq!((e.offset, e.line, e.column, e.found), (82, 2, 1, Some('1')));
+            }
+            _ => panic!("expected a parse error"),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::hint::explain;
+use crate::{BoardState, CellPos, Step, SudokuBoard, SudokuError, SudokuResult};
+use std::fmt;
+
+/// One entry of a `SolvePath`.  Squares are numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathStep {
+    // A step found by one of the techniques
+    Deduction(Step),
+    // The techniques were stuck, so the digit was tried in a square with
+    // several alternatives
+    Guess { row: usize, col: usize, digit: u8 },
+    // The guess led to a contradiction.  Everything since the guess is taken
+    // back and the digit is ruled out of the square
+    Backtrack { row: usize, col: usize, digit: u8 },
+}
+
+/// Everything the solver did to solve a board, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolvePath {
+    pub steps: Vec<PathStep>,
+}
+
+impl SolvePath {
+    /// The number of guesses the solve needed.
+    pub fn guesses(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|s| matches!(s, PathStep::Guess { .. }))
+            .count()
+    }
+
+    /// Play the path back from the puzzle it was recorded on.  The board
+    /// after every step is worked out up front, so seeking is cheap.
+    pub fn replay(&self, puzzle: &SudokuBoard) -> Replay {
+        let mut board = puzzle.clone();
+        let mut states = vec![board.snapshot()];
+        // the board before each guess not yet backtracked
+        let mut guesses = Vec::new();
+        for step in &self.steps {
+            // steps that fail halfway were recorded that way by the solver,
+            // so their errors are part of the path
+            match step {
+                PathStep::Deduction(step) => {
+                    let _ = board.apply_step(step);
+                }
+                PathStep::Guess { row, col, digit } => {
+                    guesses.push(board.snapshot());
+                    let _ = CellPos::new(*row, *col).and_then(|pos| board.set(pos, *digit));
+                }
+                PathStep::Backtrack { row, col, digit } => {
+                    if let Some(before) = guesses.pop() {
+                        board.restore(&before);
+                    }
+                    let _ = CellPos::new(*row, *col).and_then(|pos| board.eliminate(pos, *digit));
+                }
+            }
+            states.push(board.snapshot());
+        }
+        board.restore(&states[0]);
+        Replay {
+            board,
+            states,
+            position: 0,
+        }
+    }
+}
+
+/// A `SolvePath` being played back.  Position `k` is the board after the
+/// first `k` steps, so position 0 is the puzzle.
+pub struct Replay {
+    board: SudokuBoard,
+    states: Vec<BoardState>,
+    position: usize,
+}
+
+impl Replay {
+    /// The board at the current position.
+    pub fn board(&self) -> &SudokuBoard {
+        &self.board
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The number of steps, which is also the last position.
+    pub fn len(&self) -> usize {
+        self.states.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Move to the board after the first `position` steps, or to the end
+    /// when there are fewer steps.
+    pub fn seek(&mut self, position: usize) -> &SudokuBoard {
+        self.position = position.min(self.len());
+        self.board.restore(&self.states[self.position]);
+        &self.board
+    }
+
+    /// Take one step forward.  False when already at the end.
+    pub fn forward(&mut self) -> bool {
+        if self.position == self.len() {
+            return false;
+        }
+        self.seek(self.position + 1);
+        true
+    }
+
+    /// Take one step back.  False when already at the puzzle.
+    pub fn backward(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.seek(self.position - 1);
+        true
+    }
+}
+
+/// One numbered line per step.
+impl fmt::Display for SolvePath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            match step {
+                PathStep::Deduction(step) => {
+                    writeln!(f, "{}. {}: {}", i + 1, step.technique.name(), explain(step))?
+                }
+                PathStep::Guess { row, col, digit } => {
+                    writeln!(f, "{}. guess {} in r{}c{}", i + 1, digit, row, col)?
+                }
+                PathStep::Backtrack { row, col, digit } => writeln!(
+                    f,
+                    "{}. backtrack: {} in r{}c{} leads to a contradiction",
+                    i + 1,
+                    digit,
+                    row,
+                    col
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SudokuBoard {
+    /// Solve the board with the logical techniques, guessing only when they
+    /// are stuck, and record every step taken on the way.
+    pub fn solve_with_path(&mut self) -> Result<SolvePath, SudokuError> {
+        let mut path = SolvePath::default();
+        self.search_path(&mut path.steps)?;
+        Ok(path)
+    }
+
+    fn search_path(&mut self, steps: &mut Vec<PathStep>) -> SudokuResult {
+        while let Some(step) = self.next_step() {
+            steps.push(PathStep::Deduction(step.clone()));
+            self.apply_step(&step)?;
+        }
+        let (row, col, alternatives) = match self.guess_square() {
+            Some(guess) => guess,
+            None => return Ok(()),
+        };
+        for alt_item in alternatives {
+            let digit = alt_item as u8;
+            steps.push(PathStep::Guess { row, col, digit });
+            let mut alt_board = self.clone();
+            if alt_board
+                .mark_as_known(row, col, alt_item)
+                .and_then(|_| alt_board.search_path(steps))
+                .is_ok()
+            {
+                *self = alt_board;
+                return Ok(());
+            }
+            steps.push(PathStep::Backtrack { row, col, digit });
+        }
+        Err(SudokuError::NotSolvable(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathStep;
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_solve_with_path() {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        let mut sboard = SudokuBoard::fill_board(s).unwrap();
+        let path = sboard.solve_with_path().unwrap();
+        assert!(sboard.is_solved());
+        let mut expected = SudokuBoard::fill_board(s).unwrap();
+        expected.solve_first().unwrap();
+        assert!(sboard == expected);
+
+        assert!(matches!(path.steps[0], PathStep::Deduction(_)));
+        let text = path.to_string();
+        assert_eq!(text.lines().count(), path.steps.len());
+        assert!(text.starts_with("1. "));
+    }
+
+    #[test]
+    fn test_path_with_guesses() {
+        // an empty board has nothing to deduce, so the solve starts guessing
+        let mut sboard = SudokuBoard::new();
+        let path = sboard.solve_with_path().unwrap();
+        assert!(sboard.is_solved());
+        assert_eq!(
+            path.steps[0],
+            PathStep::Guess {
+                row: 1,
+                col: 1,
+                digit: 1
+            }
+        );
+        assert!(path.guesses() > 0);
+        assert!(path.to_string().starts_with("1. guess 1 in r1c1\n"));
+    }
+
+    #[test]
+    fn test_replay() {
+        let puzzle = SudokuBoard::fill_board(&"-".repeat(81)).unwrap();
+        let mut solved = puzzle.clone();
+        let path = solved.solve_with_path().unwrap();
+        let mut replay = path.replay(&puzzle);
+        assert_eq!(replay.len(), path.steps.len());
+        assert!(*replay.board() == puzzle);
+        assert!(!replay.backward());
+
+        assert!(replay.forward());
+        assert_eq!(replay.board().get(at(1, 1)), Some(1));
+        assert!(*replay.seek(usize::MAX) == solved);
+        assert_eq!(replay.position(), replay.len());
+        assert!(!replay.forward());
+        assert!(replay.backward());
+        assert!(!replay.board().is_solved());
+        assert!(*replay.seek(0) == puzzle);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::SudokuError;
+
+/// A row of the board.  Rows are numbered 1 to 9 from the top, and the
+/// constructors reject anything else, so a `Row` is always on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Row(usize);
+
+impl Row {
+    /// The row numbered `row`, counting from 1.
+    pub fn new(row: usize) -> Result<Row, SudokuError> {
+        match row {
+            1..=9 => Ok(Row(row)),
+            _ => Err(SudokuError::InvalidRange),
+        }
+    }
+
+    /// The row at index `row`, counting from 0.
+    pub fn from_zero_based(row: usize) -> Result<Row, SudokuError> {
+        Row::new(row.wrapping_add(1))
+    }
+
+    /// The row number, from 1.
+    pub fn get(self) -> usize {
+        self.0
+    }
+
+    /// The row index, from 0.
+    pub fn index(self) -> usize {
+        self.0 - 1
+    }
+}
+
+/// A column of the board.  Columns are numbered 1 to 9 from the left, and
+/// the constructors reject anything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Col(usize);
+
+impl Col {
+    /// The column numbered `col`, counting from 1.
+    pub fn new(col: usize) -> Result<Col, SudokuError> {
+        match col {
+            1..=9 => Ok(Col(col)),
+            _ => Err(SudokuError::InvalidRange),
+        }
+    }
+
+    /// The column at index `col`, counting from 0.
+    pub fn from_zero_based(col: usize) -> Result<Col, SudokuError> {
+        Col::new(col.wrapping_add(1))
+    }
+
+    /// The column number, from 1.
+    pub fn get(self) -> usize {
+        self.0
+    }
+
+    /// The column index, from 0.
+    pub fn index(self) -> usize {
+        self.0 - 1
+    }
+}
+
+/// A square of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellPos {
+    pub row: Row,
+    pub col: Col,
+}
+
+impl CellPos {
+    /// The square in row `row` and column `col`, both counting from 1.
+    pub fn new(row: usize, col: usize) -> Result<CellPos, SudokuError> {
+        Ok(CellPos {
+            row: Row::new(row)?,
+            col: Col::new(col)?,
+        })
+    }
+
+    /// The square at row index `row` and column index `col`, both counting
+    /// from 0.
+    pub fn from_zero_based(row: usize, col: usize) -> Result<CellPos, SudokuError> {
+        Ok(CellPos {
+            row: Row::from_zero_based(row)?,
+            col: Col::from_zero_based(col)?,
+        })
+    }
+
+    /// The square at `index` in a board read left to right and top to
+    /// bottom, as `print_board` writes it and `HouseId::cells` lists it.
+    pub fn from_index(index: usize) -> Result<CellPos, SudokuError> {
+        if index >= 81 {
+            return Err(SudokuError::InvalidRange);
+        }
+        CellPos::from_zero_based(index / 9, index % 9)
+    }
+
+    /// The index of the square in a board read left to right and top to
+    /// bottom.
+    pub fn index(self) -> usize {
+        self.row.index() * 9 + self.col.index()
+    }
+}
+
+impl From<(Row, Col)> for CellPos {
+    fn from((row, col): (Row, Col)) -> CellPos {
+        CellPos { row, col }
+    }
+}
+
+/// The square in row `row` and column `col`, for tests naming squares by
+/// number.
+#[cfg(test)]
+pub(crate) fn at(row: usize, col: usize) -> CellPos {
+    CellPos::new(row, col).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{at, CellPos, Col, Row};
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_cell_pos() {
+        assert!(Row::new(0).is_err());
+        assert!(Row::new(10).is_err());
+        assert_eq!(Row::new(9).unwrap().index(), 8);
+        assert_eq!(Col::from_zero_based(0).unwrap().get(), 1);
+        assert!(Col::from_zero_based(9).is_err());
+        assert!(Col::from_zero_based(usize::MAX).is_err());
+
+        let pos = CellPos::new(3, 7).unwrap();
+        assert_eq!(pos, CellPos::from_zero_based(2, 6).unwrap());
+        assert_eq!(pos.index(), 24);
+        assert_eq!(CellPos::from_index(24).unwrap(), pos);
+        assert!(CellPos::from_index(81).is_err());
+        assert!(CellPos::new(0, 1).is_err());
+        let row = Row::new(3).unwrap();
+        let col = Col::new(7).unwrap();
+        assert_eq!(CellPos::from((row, col)), pos);
+    }
+
+    #[test]
+    fn test_board_by_pos() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut board = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(board.get(at(1, 1)), Some(4));
+        assert_eq!(board.get(at(9, 9)), None);
+        assert!(board.is_given(at(1, 6)));
+        assert_eq!(
+            board.candidates(at(1, 2)).collect::<Vec<u8>>(),
+            vec![1, 2, 3, 5, 6, 7, 9]
+        );
+        assert_eq!(board.conflict(at(2, 2), 4).unwrap(), Some(at(1, 1)));
+        assert!(!board.is_legal(at(2, 2), 4));
+        assert!(board.is_legal(at(2, 2), 5));
+
+        board.set(at(2, 2), 5).unwrap();
+        assert_eq!(board.get(at(2, 2)), Some(5));
+        assert!(!board.is_given(at(2, 2)));
+        board.eliminate(at(9, 9), 1).unwrap();
+        assert!(!board.candidates(at(9, 9)).any(|d| d == 1));
+        board.clear(at(2, 2)).unwrap();
+        assert_eq!(board.get(at(2, 2)), None);
+    }
+}
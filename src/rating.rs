@@ -0,0 +1,126 @@
+use crate::{HouseId, Step, SudokuBoard, Technique};
+use std::fmt;
+
+/// The techniques in the order Sudoku Explainer prefers them, its easiest
+/// first.  It takes hidden singles before naked ones, and X-Wings before
+/// hidden pairs.
+const SE_ORDER: [Technique; 8] = [
+    Technique::HiddenSingle,
+    Technique::NakedSingle,
+    Technique::LockedCandidates,
+    Technique::NakedPair,
+    Technique::XWing,
+    Technique::HiddenPair,
+    Technique::NakedTriple,
+    Technique::HiddenTriple,
+];
+
+/// The rating Sudoku Explainer gives the easiest technique this solver
+/// does not know, the XY-Wing.
+const BEYOND_TECHNIQUES: f32 = 4.2;
+
+/// An approximate Sudoku Explainer rating, the numeric scale from about 1.0
+/// to over 9.0 that puzzle hardness is usually compared on.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct SeRating {
+    // The rating of the hardest step taken
+    pub value: f32,
+    // The techniques got stuck, so the puzzle rates at least `value`
+    pub lower_bound: bool,
+}
+
+/// The rating with one decimal, followed by `+` for a lower bound, e.g.
+/// "2.6" or "4.2+".
+impl fmt::Display for SeRating {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.1}", self.value)?;
+        if self.lower_bound {
+            write!(f, "+")?;
+        }
+        Ok(())
+    }
+}
+
+/// The weight Sudoku Explainer gives a step.  Hidden singles in a box and
+/// locked candidates pointing out of a box are a little easier than those
+/// in a row or column.
+fn step_rating(step: &Step) -> f32 {
+    let in_box = matches!(step.houses.first(), Some(HouseId::Box(_)));
+    match step.technique {
+        Technique::HiddenSingle if in_box => 1.2,
+        Technique::HiddenSingle => 1.5,
+        Technique::NakedSingle => 2.3,
+        Technique::LockedCandidates if in_box => 2.6,
+        Technique::LockedCandidates => 2.8,
+        Technique::NakedPair => 3.0,
+        Technique::XWing => 3.2,
+        Technique::HiddenPair => 3.4,
+        Technique::NakedTriple => 3.6,
+        Technique::HiddenTriple => 4.0,
+    }
+}
+
+impl SudokuBoard {
+    /// Rate the board on the Sudoku Explainer scale: solve it logically,
+    /// always taking the step that scale rates easiest, and rate it by its
+    /// hardest step.  The solver knows fewer techniques and looks for them
+    /// in its own order, so this is an approximation.  A board the
+    /// techniques cannot finish rates at least 4.2, where the techniques
+    /// it lacks begin.  A board that needs no steps rates 0.0.
+    pub fn se_rating(&self) -> SeRating {
+        let mut board = self.clone();
+        let mut value: f32 = 0.0;
+        while let Some(step) = board.next_step_with(&SE_ORDER) {
+            value = value.max(step_rating(&step));
+            if board.apply_step(&step).is_err() {
+                break;
+            }
+        }
+        let lower_bound = !board.is_solved();
+        if lower_bound {
+            value = value.max(BEYOND_TECHNIQUES);
+        }
+        SeRating { value, lower_bound }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_se_rating() {
+        let easy = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        let rating = easy.se_rating();
+        assert!(!rating.lower_bound);
+        assert!((1.2..=2.3).contains(&rating.value), "{}", rating);
+
+        // locked candidates
+        let s = "4-9---3---5-----8-----2---6--2-1-6--98------7-----2-98-7-93------5------3--571-4-";
+        let mut busy = SudokuBoard::fill_board(s).unwrap();
+        let rating = busy.se_rating();
+        assert!(rating.value == 2.6 || rating.value == 2.8, "{}", rating);
+        busy.solve_first().unwrap();
+        assert_eq!(busy.se_rating().to_string(), "0.0");
+
+        // an X-Wing, or harder
+        let s = "100000569492056108056109240009640801064010000218035604040500016905061402621000005";
+        let x_wing = SudokuBoard::fill_board(s).unwrap().se_rating();
+        assert!(x_wing.value >= 3.2 && !x_wing.lower_bound, "{}", x_wing);
+
+        let empty = SudokuBoard::new().se_rating();
+        assert!(empty.lower_bound);
+        assert_eq!(empty.to_string(), "4.2+");
+    }
+}
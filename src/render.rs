@@ -0,0 +1,182 @@
+use crate::{char_from32, BoxValue, Node, SudokuBoard};
+use std::fmt;
+
+/// Build a horizontal rule of the pretty printed grid, with squares `width`
+/// characters wide.  `light` joins squares inside a 3x3 square and `heavy`
+/// joins the 3x3 squares.
+fn rule(width: usize, left: char, fill: char, light: char, heavy: char, right: char) -> String {
+    let mut line = String::new();
+    line.push(left);
+    for col in 0..9 {
+        line.extend(std::iter::repeat_n(fill, width));
+        line.push(match col {
+            8 => right,
+            2 | 5 => heavy,
+            _ => light,
+        });
+    }
+    line
+}
+
+impl SudokuBoard {
+    /// Draw the board framed with Unicode box-drawing characters.  The 3x3
+    /// squares are separated by heavy lines and unknown squares are blank.
+    pub fn pretty_print(&self) -> String {
+        self.pretty_print_with(|node| match node.value {
+            BoxValue::Known(v) => char_from32(v).unwrap_or('?').to_string(),
+            BoxValue::Unknown(_) => " ".to_string(),
+        })
+    }
+
+    /// Draw the framed board, `cell` gives the text shown in each square.
+    pub(crate) fn pretty_print_with(&self, cell: impl Fn(&Node) -> String) -> String {
+        self.framed(1, 1, |node, _| cell(node))
+    }
+
+    /// Draw every square as a 3x3 grid of the candidates it has left, with
+    /// 1 2 3 on the top line and 7 8 9 on the bottom one.  A known square
+    /// shows only its digit, in the middle.  This is the usual way to look
+    /// at a position where solving got stuck.
+    pub fn candidate_grid(&self) -> String {
+        self.framed(3, 5, |node, line| {
+            let digits = (line as i32 * 3 + 1)..=(line as i32 * 3 + 3);
+            let shown: Vec<char> = match &node.value {
+                BoxValue::Known(v) if line == 1 => vec![' ', char_from32(*v).unwrap_or('?'), ' '],
+                BoxValue::Known(_) => vec![' '; 3],
+                BoxValue::Unknown(set) => digits
+                    .map(|d| {
+                        if set.contains(&d) {
+                            char_from32(d).unwrap_or('?')
+                        } else {
+                            ' '
+                        }
+                    })
+                    .collect(),
+            };
+            shown
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+    }
+
+    /// Draw the framed board with squares `height` lines high and `width`
+    /// characters wide, not counting a space on either side.  `cell` gives
+    /// the text of each line of a square.
+    fn framed(&self, height: usize, width: usize, cell: impl Fn(&Node, usize) -> String) -> String {
+        let width = width + 2;
+        let mut lines = vec![rule(width, '┏', '━', '┯', '┳', '┓')];
+        for (r, row) in self.board.iter().enumerate() {
+            for k in 0..height {
+                let mut line = String::new();
+                line.push('┃');
+                for (c, node) in row.iter().enumerate() {
+                    line.push(' ');
+                    line.push_str(&cell(node, k));
+                    line.push(' ');
+                    line.push(if c % 3 == 2 { '┃' } else { '│' });
+                }
+                lines.push(line);
+            }
+            lines.push(match r {
+                8 => rule(width, '┗', '━', '┷', '┻', '┛'),
+                2 | 5 => rule(width, '┣', '━', '┿', '╋', '┫'),
+                _ => rule(width, '┠', '─', '┼', '╂', '┨'),
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+/// Show the board as 9 lines of 9 squares, unknown squares as `-`.  The
+/// alternate form `{:#}` shows `pretty_print` instead.
+impl fmt::Display for SudokuBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.pretty_print());
+        }
+        let board = self.print_board();
+        for r in 0..9 {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", &board[r * 9..(r + 1) * 9])?;
+        }
+        Ok(())
+    }
+}
+
+/// The board on one line, as `print_board` writes it, so boards in failed
+/// assertions are easy to paste back.
+impl fmt::Debug for SudokuBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SudokuBoard")
+            .field(&self.print_board())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_display() {
+        let board = SudokuBoard::new();
+        assert_eq!(format!("{}", board), ["---------"; 9].join("\n"));
+        assert_eq!(
+            format!("{:?}", board),
+            format!("SudokuBoard({:?})", "-".repeat(81))
+        );
+    }
+
+    #[test]
+    fn test_pretty_print() {
+        let s = concat!(
+            "53--7----",
+            "6--195---",
+            "-98----6-",
+            "8---6---3",
+            "4--8-3--1",
+            "7---2---6",
+            "-6----28-",
+            "---419--5",
+            "----8--79"
+        );
+        let board = SudokuBoard::parse_strict(s).unwrap();
+        let pretty = format!("{:#}", board);
+        let lines: Vec<&str> = pretty.lines().collect();
+        assert_eq!(lines.len(), 19);
+        assert_eq!(lines[0], "┏━━━┯━━━┯━━━┳━━━┯━━━┯━━━┳━━━┯━━━┯━━━┓");
+        assert_eq!(lines[1], "┃ 5 │ 3 │   ┃   │ 7 │   ┃   │   │   ┃");
+        assert_eq!(lines[2], "┠───┼───┼───╂───┼───┼───╂───┼───┼───┨");
+        assert_eq!(lines[6], "┣━━━┿━━━┿━━━╋━━━┿━━━┿━━━╋━━━┿━━━┿━━━┫");
+        assert_eq!(lines[18], "┗━━━┷━━━┷━━━┻━━━┷━━━┷━━━┻━━━┷━━━┷━━━┛");
+        assert_eq!(pretty, board.pretty_print());
+    }
+
+    #[test]
+    fn test_candidate_grid() {
+        let s = "53--7----6--195----98----6-".to_string() + &"-".repeat(54);
+        let board = SudokuBoard::fill_board(&s).unwrap();
+        let grid = board.candidate_grid();
+        let lines: Vec<&str> = grid.lines().collect();
+        assert_eq!(lines.len(), 37);
+        assert_eq!(lines[0].chars().count(), 73);
+        assert!(lines[0].starts_with("┏━━━━━━━┯━━━━━━━┯━━━━━━━┳"));
+        // r1c3 can only be 1, 2, or 4
+        assert!(lines[1].starts_with("┃       │       │ 1 2   ┃"));
+        assert!(lines[2].starts_with("┃   5   │   3   │ 4     ┃"));
+        assert!(lines[3].starts_with("┃       │       │       ┃"));
+        assert!(lines[4].starts_with("┠───────┼"));
+        assert_eq!(
+            lines[36],
+            lines[0]
+                .replace('┏', "┗")
+                .replace('┯', "┷")
+                .replace('┳', "┻")
+                .replace('┓', "┛")
+        );
+    }
+}
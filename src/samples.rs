@@ -0,0 +1,110 @@
+use crate::{Difficulty, SudokuBoard};
+
+/// A puzzle from the sample library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub name: &'static str,
+    // The grade `SudokuBoard::grade` gives the puzzle
+    pub difficulty: Difficulty,
+    // The 81 squares row by row, `-` for an unknown one
+    pub puzzle: &'static str,
+}
+
+impl Sample {
+    /// The puzzle as a new board.
+    pub fn board(&self) -> SudokuBoard {
+        SudokuBoard::fill_board(self.puzzle).expect("sample puzzles are valid")
+    }
+}
+
+const SAMPLES: &[Sample] = &[
+    Sample {
+        name: "Classic",
+        difficulty: Difficulty::Easy,
+        puzzle: "53--7----6--195----98----6-8---6---34--8-3--17---2---6-6----28----419--5----8--79",
+    },
+    Sample {
+        name: "Easy 1",
+        difficulty: Difficulty::Easy,
+        puzzle: "6-2-4----8---6-4-51--8-3-6---6-----9---3-4---3-----8---3-6-7--44-9-1---3----3-7-1",
+    },
+    Sample {
+        name: "Easy 2",
+        difficulty: Difficulty::Easy,
+        puzzle: "5-9---7--7-1--3--6---59--142---3-----9-----6-----8---592--76---1--3--6-2--6---8-1",
+    },
+    Sample {
+        name: "Medium 1",
+        difficulty: Difficulty::Medium,
+        puzzle: "6-2-4-1--8---6-4-5---8-3-6---6-----9---3-4---3-----8---3-6-7---4-9-1---3--8-3-7-1",
+    },
+    Sample {
+        name: "Medium 2",
+        difficulty: Difficulty::Medium,
+        puzzle: "17---3-----48------2--9----6--17-2--7---5---9--2-34--6----2--3------65-----4---82",
+    },
+    Sample {
+        name: "Hard 1",
+        difficulty: Difficulty::Hard,
+        puzzle: "6--1--4---2---9-6---927---8-----4--7--1---2--9--5-----7---428---1-8---3---6--5--4",
+    },
+    Sample {
+        name: "Hard 2",
+        difficulty: Difficulty::Hard,
+        puzzle: "--97---------5631------8-599-4-8---5--3---4--8---4-6-338-1------7689---------72--",
+    },
+    Sample {
+        name: "Expert 1",
+        difficulty: Difficulty::Expert,
+        puzzle: "-831-45--4-5--82-----5------3----4-6-14-5-39-8-9----7------5-----82--9-1--13-678-",
+    },
+    Sample {
+        name: "Expert 2",
+        difficulty: Difficulty::Expert,
+        puzzle: "-5--2--7-8--9------3--68-2-2-----7----54-71----1-----3-7-84--3------5--7-9--3--5-",
+    },
+    Sample {
+        name: "Diabolical 1",
+        difficulty: Difficulty::Diabolical,
+        puzzle: "-92------6----1---8---9-6---5---381-7---2---5-398---6---6-7---2---2----1------43-",
+    },
+    Sample {
+        name: "Diabolical 2",
+        difficulty: Difficulty::Diabolical,
+        puzzle: "-2--9-6--8----1---149----7-4-1-75-------1-------83-5-9-8----947---3----8--5-4--6-",
+    },
+    Sample {
+        name: "Arto Inkala",
+        difficulty: Difficulty::Diabolical,
+        puzzle: "8----------36------7--9-2---5---7-------457-----1---3---1----68--85---1--9----4--",
+    },
+    Sample {
+        name: "Easter Monster",
+        difficulty: Difficulty::Diabolical,
+        puzzle: "1-------2-9-4---5---6---7---5-9-3-------7-------85--4-7-----6---3---9-8---2-----1",
+    },
+];
+
+/// The puzzles of the sample library, easiest first, ending with two
+/// well known hard ones.  Each has exactly one solution.
+pub fn samples() -> &'static [Sample] {
+    SAMPLES
+}
+
+#[cfg(test)]
+mod tests {
+    use super::samples;
+
+    #[test]
+    fn test_samples() {
+        let samples = samples();
+        assert!(samples
+            .windows(2)
+            .all(|w| w[0].difficulty <= w[1].difficulty));
+        for sample in samples {
+            let board = sample.board();
+            assert_eq!(board.count_solutions(2), 1, "{}", sample.name);
+            assert_eq!(board.grade(), sample.difficulty, "{}", sample.name);
+        }
+    }
+}
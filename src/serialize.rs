@@ -0,0 +1,135 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+use crate::{Solution, SudokuBoard};
+
+/// A board is written as its `to_code` string in human readable formats and
+/// as its `to_bytes` encoding in binary ones, both with the candidates, so
+/// the givens, the digits placed since, and the pencil marks all come back.
+impl Serialize for SudokuBoard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_code(true))
+        } else {
+            serializer.serialize_bytes(&self.to_bytes(true))
+        }
+    }
+}
+
+struct BoardVisitor;
+
+impl<'de> Visitor<'de> for BoardVisitor {
+    type Value = SudokuBoard;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a board encoded by to_code or to_bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<SudokuBoard, E> {
+        SudokuBoard::from_code(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<SudokuBoard, E> {
+        SudokuBoard::from_bytes(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<SudokuBoard, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for SudokuBoard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<SudokuBoard, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BoardVisitor)
+        } else {
+            deserializer.deserialize_bytes(BoardVisitor)
+        }
+    }
+}
+
+/// A solution is written as its 81 digits on one line, like `to_line`.
+impl Serialize for Solution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_line())
+    }
+}
+
+struct SolutionVisitor;
+
+impl Visitor<'_> for SolutionVisitor {
+    type Value = Solution;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "81 digits of a solved board")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Solution, E> {
+        let board = SudokuBoard::parse_grid(v).map_err(E::custom)?;
+        board.to_solution().map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Solution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Solution, D::Error> {
+        deserializer.deserialize_str(SolutionVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::{Solution, SolveOutcome, SudokuBoard};
+    use serde::de::value::{BytesDeserializer, Error, StrDeserializer};
+    use serde::Deserialize;
+
+    const PUZZLE: &str = concat!(
+        "4----8---",
+        "----91-8-",
+        "-865-2-3-",
+        "-2-4--9--",
+        "-1-2----6",
+        "367-59---",
+        "-----5---",
+        "7--8---24",
+        "2--93--7-"
+    );
+
+    fn solution() -> Solution {
+        match SudokuBoard::fill_board(PUZZLE).unwrap().solve_outcome() {
+            SolveOutcome::Unique(solution) => solution,
+            outcome => panic!("{:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn test_serde_board() {
+        let mut board = SudokuBoard::fill_board(PUZZLE).unwrap();
+        board.set(at(1, 2), solution().get(at(1, 2))).unwrap();
+
+        let code = board.to_code(true);
+        let read = SudokuBoard::deserialize(StrDeserializer::<Error>::new(&code)).unwrap();
+        assert_eq!(read.print_given_mask(), board.print_given_mask());
+        assert!(!read.is_given(at(1, 2)));
+
+        let bytes = board.to_bytes(true);
+        let read = SudokuBoard::deserialize(BytesDeserializer::<Error>::new(&bytes)).unwrap();
+        assert_eq!(read.print_board(), board.print_board());
+
+        assert!(SudokuBoard::deserialize(StrDeserializer::<Error>::new("!")).is_err());
+    }
+
+    #[test]
+    fn test_serde_solution() {
+        let line = solution().to_line();
+        let read = Solution::deserialize(StrDeserializer::<Error>::new(&line)).unwrap();
+        assert_eq!(read, solution());
+
+        assert!(Solution::deserialize(StrDeserializer::<Error>::new(PUZZLE)).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! A small HTTP service around the solver.
+//!
+//! ```text
+//! sudoku_server [ADDRESS]        listen on ADDRESS, 127.0.0.1:8080 by default
+//!
+//! POST /solve         the body is one puzzle, as `sudoku_bin solve` reads it
+//! POST /solve/batch   the body is one puzzle per line
+//! ```
+//!
+//! Each puzzle is answered with a JSON object holding the solution, the
+//! difficulty the logical techniques give it, and how much guessing the
+//! solve needed, or an `error` when it could not be solved.  The batch
+//! endpoint answers with an array of them, in the order of the lines.
+
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use sudoku::{json_string, SolveOutcome, SudokuBoard, SudokuError};
+
+/// The largest request body read, enough for a few thousand puzzles.
+const MAX_BODY: usize = 1 << 20;
+
+/// How long a client may stall while sending its request or reading the
+/// answer before the connection is dropped.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most connections served at once.  Any more are answered with
+/// `503 Service Unavailable` straight away.
+const MAX_CONNECTIONS: usize = 64;
+
+/// One of the `MAX_CONNECTIONS` connections being served, given back when
+/// it is dropped.
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    /// Take a slot, or None when every one is taken.
+    fn take(active: &Arc<AtomicUsize>) -> Option<Slot> {
+        let slot = Slot(active.clone());
+        (active.fetch_add(1, Ordering::SeqCst) < MAX_CONNECTIONS).then_some(slot)
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A request as far as the service needs it.
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+/// An HTTP status line and a JSON body.
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn error(status: &'static str, message: &str) -> Response {
+        Response {
+            status,
+            body: format!("{{\"error\":{}}}", json_string(message)),
+        }
+    }
+}
+
+/// Solve one puzzle.  The error is the message to send back.
+fn solve(puzzle: &str) -> Result<String, String> {
+    let board = SudokuBoard::parse_grid(puzzle).map_err(|e| e.to_string())?;
+    let difficulty = board.grade();
+    let (outcome, stats) = board.solve_outcome_first_with_stats();
+    let solution = match outcome {
+        SolveOutcome::Found(solution)
+        | SolveOutcome::Unique(solution)
+        | SolveOutcome::Multiple(solution, _) => solution,
+        SolveOutcome::Unsolvable => return Err(SudokuError::NotSolvable(None).to_string()),
+        SolveOutcome::Incomplete(e) => return Err(e.to_string()),
+    };
+    Ok(format!(
+        "{{\"puzzle\":{},\"solution\":{},\"difficulty\":{},\"guesses\":{},\"backtracks\":{}}}",
+        json_string(&board.print_board()),
+        json_string(&solution.to_line()),
+        json_string(difficulty.name()),
+        stats.guesses,
+        stats.backtracks
+    ))
+}
+
+fn handle(request: &Request) -> Response {
+    let single = match request.path.as_str() {
+        "/solve" => true,
+        "/solve/batch" => false,
+        _ => return Response::error("404 Not Found", "no such endpoint"),
+    };
+    if request.method != "POST" {
+        return Response::error("405 Method Not Allowed", "use POST");
+    }
+    if single {
+        return match solve(&request.body) {
+            Ok(body) => Response {
+                status: "200 OK",
+                body,
+            },
+            Err(e) => Response::error("422 Unprocessable Entity", &e),
+        };
+    }
+    let answers: Vec<String> = request
+        .body
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            solve(line).unwrap_or_else(|e| {
+                format!(
+                    "{{\"puzzle\":{},\"error\":{}}}",
+                    json_string(line.trim()),
+                    json_string(&e)
+                )
+            })
+        })
+        .collect();
+    Response {
+        status: "200 OK",
+        body: format!("[{}]", answers.join(",")),
+    }
+}
+
+/// Read the request line, the headers, and the body the `Content-Length`
+/// header announces.  The error is the response to send instead.
+fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let bad = |message| Response::error("400 Bad Request", message);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|_| bad("unreadable request"))?;
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(bad("malformed request line")),
+    };
+
+    let mut length = 0;
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => return Err(bad("unexpected end of headers")),
+            Ok(_) => (),
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad("invalid Content-Length"))?;
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(Response::error("413 Payload Too Large", "body too large"));
+    }
+
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad("body shorter than Content-Length"))?;
+    let body = String::from_utf8(body).map_err(|_| bad("body is not UTF-8"))?;
+    Ok(Request { method, path, body })
+}
+
+fn write_response(out: &mut impl Write, response: &Response) -> io::Result<()> {
+    write!(
+        out,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.body.len(),
+        response.body
+    )?;
+    out.flush()
+}
+
+fn serve(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request(&mut reader) {
+        Ok(request) => handle(&request),
+        Err(response) => response,
+    };
+    write_response(&mut &stream, &response)
+}
+
+pub fn main() {
+    let address = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("error: cannot listen on {}: {}", address, e);
+            process::exit(1);
+        }
+    };
+    eprintln!("listening on {}", address);
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming().flatten() {
+        let Some(slot) = Slot::take(&active) else {
+            let busy = Response::error("503 Service Unavailable", "too many connections");
+            let _ = stream.set_write_timeout(Some(TIMEOUT));
+            let _ = write_response(&mut &stream, &busy);
+            continue;
+        };
+        thread::spawn(move || {
+            if let Err(e) = serve(stream) {
+                eprintln!("error: {}", e);
+            }
+            drop(slot);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{handle, read_request, write_response, Request, Slot, MAX_CONNECTIONS};
+    use std::io::BufReader;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const PUZZLE: &str = concat!(
+        "4----8---",
+        "----91-8-",
+        "-865-2-3-",
+        "-2-4--9--",
+        "-1-2----6",
+        "367-59---",
+        "-----5---",
+        "7--8---24",
+        "2--93--7-"
+    );
+
+    fn post(path: &str, body: &str) -> Request {
+        Request {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = format!(
+            "POST /solve HTTP/1.1\r\nHost: x\r\ncontent-length: {}\r\n\r\n{}",
+            PUZZLE.len(),
+            PUZZLE
+        );
+        let request = read_request(&mut BufReader::new(raw.as_bytes()))
+            .ok()
+            .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/solve");
+        assert_eq!(request.body, PUZZLE);
+
+        let raw = "POST /solve HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort";
+        let response = read_request(&mut BufReader::new(raw.as_bytes()))
+            .err()
+            .unwrap();
+        assert_eq!(response.status, "400 Bad Request");
+    }
+
+    #[test]
+    fn test_handle() {
+        let response = handle(&post("/solve", PUZZLE));
+        assert_eq!(response.status, "200 OK");
+        assert!(response.body.starts_with("{\"puzzle\":\"4----8---"));
+        assert!(response.body.contains("\"solution\":\"4"));
+        assert!(response.body.contains("\"difficulty\":"));
+        assert!(response.body.ends_with("\"backtracks\":0}"));
+
+        let response = handle(&post("/solve", "123"));
+        assert_eq!(response.status, "422 Unprocessable Entity");
+
+        let batch = format!("{}\n\n11-------\n{}\n", PUZZLE, PUZZLE);
+        let response = handle(&post("/solve/batch", &batch));
+        assert_eq!(response.status, "200 OK");
+        assert!(response.body.starts_with("[{\"puzzle\""));
+        assert_eq!(response.body.matches("\"solution\"").count(), 2);
+        assert_eq!(response.body.matches("\"error\"").count(), 1);
+
+        let mut get = post("/solve", PUZZLE);
+        get.method = "GET".to_string();
+        assert_eq!(handle(&get).status, "405 Method Not Allowed");
+        assert_eq!(handle(&post("/", "")).status, "404 Not Found");
+
+        let mut out = Vec::new();
+        write_response(&mut out, &handle(&post("/", ""))).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(out.ends_with("\r\n\r\n{\"error\":\"no such endpoint\"}"));
+    }
+
+    #[test]
+    fn test_slots() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<Slot> = (0..MAX_CONNECTIONS)
+            .map(|_| Slot::take(&active).unwrap())
+            .collect();
+        assert!(Slot::take(&active).is_none());
+        assert_eq!(active.load(Ordering::SeqCst), MAX_CONNECTIONS);
+        drop(slots);
+        assert_eq!(active.load(Ordering::SeqCst), 0);
+        assert!(Slot::take(&active).is_some());
+    }
+}
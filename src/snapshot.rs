@@ -0,0 +1,71 @@
+use crate::{BoxValue, SudokuBoard};
+
+/// Set in a packed square when its value is known.  The low 4 bits then
+/// hold the digit.
+const KNOWN: u16 = 1 << 15;
+/// Set in a packed known square that was one of the givens.
+const GIVEN: u16 = 1 << 14;
+
+/// A save point of a board made by `SudokuBoard::snapshot`.  Every square
+/// is packed into 16 bits: the digit of a known square, or the candidates
+/// of an unknown one with bit 0 meaning the digit 1.  Copying it is cheap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoardState {
+    cells: [u16; 81],
+}
+
+impl SudokuBoard {
+    /// Save the values, givens, and candidates of the board.
+    pub fn snapshot(&self) -> BoardState {
+        let mut cells = [0; 81];
+        for (cell, node) in cells.iter_mut().zip(self.board.iter().flatten()) {
+            *cell = match &node.value {
+                BoxValue::Known(v) if node.given => KNOWN | GIVEN | *v as u16,
+                BoxValue::Known(v) => KNOWN | *v as u16,
+                BoxValue::Unknown(set) => set.iter().fold(0, |bits, v| bits | 1 << (v - 1)),
+            };
+        }
+        BoardState { cells }
+    }
+
+    /// Put the board back the way it was when `state` was saved.
+    pub fn restore(&mut self, state: &BoardState) {
+        let mut unknown = 0;
+        for (node, cell) in self.board.iter_mut().flatten().zip(state.cells) {
+            node.given = cell & GIVEN != 0;
+            node.value = if cell & KNOWN != 0 {
+                BoxValue::Known((cell & 0xf) as i32)
+            } else {
+                unknown += 1;
+                BoxValue::Unknown((1..=9).filter(|v| cell & 1 << (v - 1) != 0).collect())
+            };
+        }
+        self.unknown_values = unknown;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_snapshot() {
+        let s = "4----8---".to_string() + &"-".repeat(72);
+        let mut sboard = SudokuBoard::fill_board(&s).unwrap();
+        sboard.eliminate(at(9, 9), 5).unwrap();
+        let state = sboard.snapshot();
+        let before = (sboard.print_possibility(), sboard.print_given_mask());
+
+        sboard.solve_first().unwrap();
+        assert!(sboard.is_solved());
+        sboard.restore(&state);
+        assert_eq!(
+            (sboard.print_possibility(), sboard.print_given_mask()),
+            before
+        );
+        assert_eq!(sboard.print_board(), s);
+        assert_eq!(sboard.unsolved_cells().count(), 79);
+        assert_eq!(sboard.snapshot(), state);
+    }
+}
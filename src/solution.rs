@@ -0,0 +1,307 @@
+use crate::{BoxValue, CellPos, SolveStats, SudokuBoard, SudokuError, SudokuResult};
+use std::fmt;
+
+/// The digits of a solved board, row by row.  Unlike a `SudokuBoard` it has
+/// no alternatives to keep, and it cannot be changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Solution {
+    digits: [u8; 81],
+}
+
+impl Solution {
+    /// The digit of a square.
+    pub fn get(&self, pos: CellPos) -> u8 {
+        self.digits[pos.index()]
+    }
+
+    /// All 81 digits, row by row.
+    pub fn digits(&self) -> &[u8; 81] {
+        &self.digits
+    }
+
+    /// The digits of each row, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.digits.chunks(9)
+    }
+
+    /// The 81 digits on one line, as `print_board` writes a board.
+    pub fn to_line(&self) -> String {
+        self.digits.iter().map(|d| char::from(b'0' + d)).collect()
+    }
+
+    /// A board holding the solution.  None of its squares are givens.
+    pub fn to_board(&self) -> SudokuBoard {
+        let mut board = SudokuBoard::new();
+        for (node, digit) in board.board.iter_mut().flatten().zip(self.digits) {
+            node.value = BoxValue::Known(digit as i32);
+        }
+        board.unknown_values = 0;
+        board
+    }
+}
+
+/// 9 lines of 9 digits, the way a solved board is shown.
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let line = self.to_line();
+        for r in 0..9 {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", &line[r * 9..(r + 1) * 9])?;
+        }
+        Ok(())
+    }
+}
+
+/// What solving a board found, without folding "no solution" and "more
+/// than one" into errors.  This is what the `solve_outcome` methods
+/// return, in place of the older `solve` that fails with `NotSolvable`.
+#[derive(Debug)]
+pub enum SolveOutcome {
+    // The board has exactly one solution
+    Unique(Solution),
+    // The board has more than one solution.  Holds one of them, and how
+    // many were found before counting stopped, which is at least 2
+    Multiple(Solution, usize),
+    // A solution was found and the search stopped there, so whether it is
+    // the only one is not known.  Only the `solve_outcome_first` methods
+    // give it
+    Found(Solution),
+    // The board has no solution
+    Unsolvable,
+    // The solver stopped before it could tell, for the reason held
+    Incomplete(SudokuError),
+}
+
+/// How many solutions `solve_outcome` counts before it stops.
+const COUNT_LIMIT: usize = 2;
+
+impl SudokuBoard {
+    /// Solve a copy of the board and tell whether the solution is unique.
+    /// The board itself is left as it is, `fill_solution` copies the
+    /// solution into it.
+    ///
+    /// The search is deterministic: when it has to guess, it picks the
+    /// unknown square with the fewest alternatives, the topmost then
+    /// leftmost on a tie, and tries its alternatives smallest first.  The
+    /// solution held by `Multiple` is the first one this order comes to, so
+    /// a puzzle with several solutions always gives the same one, on every
+    /// run and platform.
+    pub fn solve_outcome(&self) -> SolveOutcome {
+        self.outcome(usize::MAX, &mut SolveStats::default())
+    }
+
+    /// Like `solve_outcome`, counting how much guessing the search needed.
+    /// It goes on after the first solution to look for a second, so the
+    /// counts cover that too.
+    pub fn solve_outcome_with_stats(&self) -> (SolveOutcome, SolveStats) {
+        let mut stats = SolveStats::default();
+        let outcome = self.outcome(usize::MAX, &mut stats);
+        (outcome, stats)
+    }
+
+    /// Like `solve_outcome`, but never nest more than `max_depth` guesses.
+    /// `Incomplete` holds the `DepthLimit` error when the limit stopped the
+    /// search before it could tell, even if one solution was found.
+    pub fn solve_outcome_within(&self, max_depth: usize) -> SolveOutcome {
+        self.outcome(max_depth, &mut SolveStats::default())
+    }
+
+    /// Solve a copy of the board, stopping at the first solution instead of
+    /// looking for a second.  A solution comes back as `Found`, the same one
+    /// `solve_outcome` holds, found by the same deterministic search.  This
+    /// is the cheaper call when uniqueness does not matter.
+    pub fn solve_outcome_first(&self) -> SolveOutcome {
+        self.solve_outcome_first_with_stats().0
+    }
+
+    /// Like `solve_outcome_first`, counting how much guessing the search
+    /// needed to come to the solution.
+    pub fn solve_outcome_first_with_stats(&self) -> (SolveOutcome, SolveStats) {
+        let mut stats = SolveStats::default();
+        let mut board = self.clone();
+        let outcome = match board.search(&mut stats, None, None) {
+            Ok(()) => match board.to_solution() {
+                Ok(solution) => SolveOutcome::Found(solution),
+                Err(e) => SolveOutcome::Incomplete(e),
+            },
+            Err(SudokuError::NotSolvable(_)) => SolveOutcome::Unsolvable,
+            Err(e) => SolveOutcome::Incomplete(e),
+        };
+        (outcome, stats)
+    }
+
+    /// Fill the unknown squares of the board from a solution of it.  Which
+    /// squares are givens stays as it was.  The board is left unchanged
+    /// when the solution does not fit it.
+    pub fn fill_solution(&mut self, solution: &Solution) -> SudokuResult {
+        let mut board = self.clone();
+        for (i, &digit) in solution.digits.iter().enumerate() {
+            let pos = CellPos::from_index(i)?;
+            match board.cell(pos).value {
+                BoxValue::Known(v) if v == digit as i32 => (),
+                BoxValue::Known(_) => {
+                    return Err(SudokuError::AlreadyKnown {
+                        row: pos.row.get(),
+                        col: pos.col.get(),
+                    })
+                }
+                BoxValue::Unknown(_) => board.set(pos, digit)?,
+            }
+        }
+        *self = board;
+        Ok(())
+    }
+
+    /// Count the solutions up to `COUNT_LIMIT` in one search, keeping the
+    /// first one found.
+    fn outcome(&self, max_depth: usize, stats: &mut SolveStats) -> SolveOutcome {
+        let (mut count, mut first, mut cut) = (0, None, false);
+        self.clone().count_into(
+            COUNT_LIMIT,
+            max_depth,
+            &mut count,
+            &mut first,
+            &mut cut,
+            stats,
+        );
+        match first.as_ref().map(SudokuBoard::to_solution) {
+            Some(Err(e)) => SolveOutcome::Incomplete(e),
+            Some(Ok(solution)) if count >= COUNT_LIMIT => SolveOutcome::Multiple(solution, count),
+            Some(Ok(solution)) if !cut => SolveOutcome::Unique(solution),
+            None if !cut => SolveOutcome::Unsolvable,
+            _ => {
+                // the search only got as far as guessing, so the singles
+                // cannot fail here
+                let mut board = self.clone();
+                let _ = board.fill_singles(None);
+                SolveOutcome::Incomplete(SudokuError::DepthLimit {
+                    depth: max_depth,
+                    board: Box::new(board),
+                })
+            }
+        }
+    }
+
+    pub(crate) fn to_solution(&self) -> Result<Solution, SudokuError> {
+        let mut digits = [0; 81];
+        for (digit, node) in digits.iter_mut().zip(self.board.iter().flatten()) {
+            match node.value {
+                BoxValue::Known(v) => *digit = v as u8,
+                BoxValue::Unknown(_) => return Err(SudokuError::NoFullySolved),
+            }
+        }
+        Ok(Solution { digits })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SolveOutcome;
+    use crate::pos::at;
+    use crate::{SudokuBoard, SudokuError};
+
+    #[test]
+    fn test_solve_outcome_first() {
+        let s = concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        let (outcome, stats) = puzzle.solve_outcome_first_with_stats();
+        let solution = match outcome {
+            SolveOutcome::Found(solution) => solution,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(puzzle.print_board(), s);
+
+        let mut expected = puzzle.clone();
+        assert_eq!(expected.solve_first().unwrap(), stats);
+        assert_eq!(solution.to_line(), expected.print_board());
+        assert_eq!(solution.to_string(), expected.to_string());
+        assert!(solution.to_board() == expected);
+        assert!(solution.to_board().is_solved());
+        assert_eq!(solution.get(at(1, 1)), 4);
+        assert_eq!(solution.rows().next().unwrap(), &solution.digits()[..9]);
+
+        // the first solution is the one `Multiple` holds
+        match (
+            SudokuBoard::new().solve_outcome_first(),
+            SudokuBoard::new().solve_outcome(),
+        ) {
+            (SolveOutcome::Found(first), SolveOutcome::Multiple(held, _)) => {
+                assert_eq!(first, held)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let broken = SudokuBoard::fill_board(&("---123456--9".to_string() + &"-".repeat(69)));
+        assert!(matches!(
+            broken.unwrap().solve_outcome_first(),
+            SolveOutcome::Unsolvable
+        ));
+    }
+
+    #[test]
+    fn test_solve_outcome() {
+        let s = concat!(
+            "---------",
+            "-----3-85",
+            "--1-2----",
+            "---5-7---",
+            "--4---1--",
+            "-9-------",
+            "5------73",
+            "--2-1----",
+            "----4---9"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        match puzzle.solve_outcome() {
+            SolveOutcome::Unique(solution) => {
+                let mut expected = puzzle.clone();
+                expected.solve_first().unwrap();
+                assert_eq!(solution.to_line(), expected.print_board());
+
+                let mut filled = puzzle.clone();
+                filled.fill_solution(&solution).unwrap();
+                assert!(filled == expected);
+                assert!(filled.is_given(at(2, 6)) && !filled.is_given(at(1, 1)));
+                let mut other = SudokuBoard::new();
+                other.set(at(1, 1), solution.get(at(1, 1)) % 9 + 1).unwrap();
+                assert!(other.fill_solution(&solution).is_err());
+                assert!(other.get(at(2, 2)).is_none());
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let (outcome, stats) = puzzle.solve_outcome_with_stats();
+        assert!(matches!(outcome, SolveOutcome::Unique(_)));
+        assert!(stats.guesses > 0 && stats.backtracks > 0);
+        assert!(matches!(
+            puzzle.solve_outcome_within(1),
+            SolveOutcome::Incomplete(SudokuError::DepthLimit { depth: 1, .. })
+        ));
+        assert!(matches!(
+            puzzle.solve_outcome_within(81),
+            SolveOutcome::Unique(_)
+        ));
+
+        let empty = SudokuBoard::new();
+        assert!(matches!(
+            empty.solve_outcome(),
+            SolveOutcome::Multiple(_, 2)
+        ));
+        let broken = SudokuBoard::fill_board(&("---123456--9".to_string() + &"-".repeat(69)));
+        assert!(matches!(
+            broken.unwrap().solve_outcome(),
+            SolveOutcome::Unsolvable
+        ));
+    }
+}
@@ -0,0 +1,352 @@
+use crate::random::Rng;
+use crate::trace;
+use crate::{BoxValue, Cause, Event, SolveStats, SudokuBoard, SudokuError, SudokuResult};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// Seed of the random keys, fixed so hashes are the same on every run.
+const KEY_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// Random keys of the Zobrist hash, one for each digit known in a square
+/// and one for each digit missing from a square for a reason other than a
+/// known peer.
+struct Keys {
+    known: [[u64; 10]; 81],
+    missing: [[u64; 10]; 81],
+}
+
+fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+    KEYS.get_or_init(|| {
+        let mut rng = Rng::new(KEY_SEED);
+        let mut keys = Keys {
+            known: [[0; 10]; 81],
+            missing: [[0; 10]; 81],
+        };
+        for table in [&mut keys.known, &mut keys.missing] {
+            for key in table.iter_mut().flatten() {
+                *key = rng.next_u64();
+            }
+        }
+        keys
+    })
+}
+
+/// The key to XOR into the hash when `digit` becomes known in the square.
+fn known_key(row: usize, col: usize, digit: u8) -> u64 {
+    keys().known[(row - 1) * 9 + col - 1][digit as usize]
+}
+
+/// Options of the backtracking search run by a `Solver`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverOptions {
+    // Remember states of the board found to have no solution, and skip them
+    // when the search meets them again
+    pub transpositions: bool,
+    // Most states remembered, the table is emptied when it is full
+    pub table_size: usize,
+}
+
+impl Default for SolverOptions {
+    fn default() -> SolverOptions {
+        SolverOptions {
+            transpositions: true,
+            table_size: 1 << 20,
+        }
+    }
+}
+
+/// Solves boards with the same search as `SudokuBoard::solve`, keeping the
+/// hashes of the states it proved dead from one board to the next.
+///
+/// The guesses of a single solve never lead to the same state twice, so
+/// the table pays off when many related boards are solved or counted, such
+/// as a puzzle with one given removed after another: a state that had no
+/// solution before still has none.
+pub struct Solver {
+    options: SolverOptions,
+    dead: HashSet<u64>,
+    skipped: usize,
+}
+
+impl Solver {
+    pub fn new(options: SolverOptions) -> Solver {
+        Solver {
+            options,
+            dead: HashSet::new(),
+            skipped: 0,
+        }
+    }
+
+    /// Fill in the solution `solve_outcome_first` finds.  Guesses skipped
+    /// because their state was known to be dead are not counted.
+    pub fn solve(&mut self, board: &mut SudokuBoard) -> Result<SolveStats, SudokuError> {
+        let mut stats = SolveStats::default();
+        let mut solved = board.clone();
+        let hash = solved.fill_singles_hashed(board.zobrist_hash())?;
+        self.search(&mut solved, hash, &mut stats)?;
+        *board = solved;
+        Ok(stats)
+    }
+
+    /// Count the solutions of the board like `count_solutions`, stopping
+    /// once `limit` are found.
+    pub fn count_solutions(&mut self, board: &SudokuBoard, limit: usize) -> usize {
+        let mut count = 0;
+        let mut board = board.clone();
+        if limit > 0 {
+            if let Ok(hash) = board.fill_singles_hashed(board.zobrist_hash()) {
+                self.count_into(&mut board, hash, limit, &mut count);
+            }
+        }
+        count
+    }
+
+    /// How many times a dead state was met again and skipped.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Forget the dead states.
+    pub fn clear(&mut self) {
+        self.dead.clear();
+    }
+
+    pub(crate) fn is_dead(&mut self, hash: u64) -> bool {
+        let dead = self.options.transpositions && self.dead.contains(&hash);
+        if dead {
+            self.skipped += 1;
+        }
+        dead
+    }
+
+    pub(crate) fn remember_dead(&mut self, hash: u64) {
+        if !self.options.transpositions {
+            return;
+        }
+        if self.dead.len() >= self.options.table_size {
+            self.dead.clear();
+        }
+        self.dead.insert(hash);
+    }
+
+    /// The state after placing the guess and filling in the singles, with
+    /// its hash, or None on a contradiction.
+    pub(crate) fn guess(
+        board: &SudokuBoard,
+        hash: u64,
+        row: usize,
+        col: usize,
+        digit: i32,
+    ) -> Option<(SudokuBoard, u64)> {
+        let mut alt_board = board.clone();
+        let mut alt_hash = hash;
+        alt_board
+            .place(
+                row,
+                col,
+                digit,
+                Cause::Guess,
+                Some(&mut |event: &Event| {
+                    if let Event::Known {
+                        row, col, digit, ..
+                    } = *event
+                    {
+                        alt_hash ^= known_key(row, col, digit);
+                    }
+                }),
+            )
+            .ok()?;
+        let alt_hash = alt_board.fill_singles_hashed(alt_hash).ok()?;
+        Some((alt_board, alt_hash))
+    }
+
+    fn search(
+        &mut self,
+        board: &mut SudokuBoard,
+        hash: u64,
+        stats: &mut SolveStats,
+    ) -> SudokuResult {
+        let (row, col, alternatives) = match board.guess_square() {
+            Some(guess) => guess,
+            None => return Ok(()),
+        };
+        if self.is_dead(hash) {
+            return Err(SudokuError::NotSolvable(None));
+        }
+        for digit in alternatives {
+            stats.guesses += 1;
+            let _span = trace::branch_span(row, col, digit);
+            if let Some((mut alt_board, alt_hash)) = Solver::guess(board, hash, row, col, digit) {
+                if self.search(&mut alt_board, alt_hash, stats).is_ok() {
+                    *board = alt_board;
+                    return Ok(());
+                }
+            }
+            stats.backtracks += 1;
+        }
+        self.remember_dead(hash);
+        Err(SudokuError::NotSolvable(None))
+    }
+
+    fn count_into(&mut self, board: &mut SudokuBoard, hash: u64, limit: usize, count: &mut usize) {
+        let (row, col, alternatives) = match board.guess_square() {
+            Some(guess) => guess,
+            None => {
+                *count += 1;
+                return;
+            }
+        };
+        if self.is_dead(hash) {
+            return;
+        }
+        let before = *count;
+        for digit in alternatives {
+            if *count >= limit {
+                // cut short, so not proved dead
+                return;
+            }
+            if let Some((mut alt_board, alt_hash)) = Solver::guess(board, hash, row, col, digit) {
+                self.count_into(&mut alt_board, alt_hash, limit, count);
+            }
+        }
+        if *count == before {
+            self.remember_dead(hash);
+        }
+    }
+}
+
+impl SudokuBoard {
+    /// A Zobrist hash of the state of the board: the digits known in each
+    /// square, and the alternatives of the unknown squares.  Placing a
+    /// digit changes the hash by XOR with a key of the square and digit, so
+    /// the search keeps it up to date as it goes.  Boards that differ only
+    /// in which values were given hash the same.
+    pub fn zobrist_hash(&self) -> u64 {
+        let keys = keys();
+        let mut hash = 0;
+        for (i, node) in self.board.iter().flatten().enumerate() {
+            match &node.value {
+                BoxValue::Known(v) => hash ^= keys.known[i][*v as usize],
+                BoxValue::Unknown(set) => {
+                    // digits removed by a known peer follow from the known
+                    // squares, only the others need keys of their own
+                    for digit in 1..=9 {
+                        if !set.contains(&digit)
+                            && !self.peers(node.row, node.col).iter().any(|&(r, c)| {
+                                matches!(self.board[r - 1][c - 1].value, BoxValue::Known(v) if v == digit)
+                            })
+                        {
+                            hash ^= keys.missing[i][digit as usize];
+                        }
+                    }
+                }
+            }
+        }
+        hash
+    }
+
+    /// `fill_singles`, updating `hash` for each square filled in.
+    pub(crate) fn fill_singles_hashed(&mut self, hash: u64) -> Result<u64, SudokuError> {
+        let mut hash = hash;
+        self.fill_singles(Some(&mut |event: &Event| {
+            if let Event::Known {
+                row, col, digit, ..
+            } = *event
+            {
+                hash ^= known_key(row, col, digit);
+            }
+        }))?;
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Solver, SolverOptions};
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    // a hard puzzle, where the search backtracks over many guesses
+    const PUZZLE: &str = concat!(
+        "100007090",
+        "030020008",
+        "009600500",
+        "005300900",
+        "010080002",
+        "600004000",
+        "300000010",
+        "040000007",
+        "007000300"
+    );
+
+    #[test]
+    fn test_zobrist_hash() {
+        let board = SudokuBoard::fill_board(PUZZLE).unwrap();
+        let mut placed = board.clone();
+        let digit = board.candidates(at(1, 2)).next().unwrap();
+        placed.set(at(1, 2), digit).unwrap();
+        assert_ne!(board.zobrist_hash(), placed.zobrist_hash());
+        // the same squares known in another order hash the same
+        let mut other = SudokuBoard::new();
+        let squares: Vec<(usize, char)> = PUZZLE.chars().enumerate().collect();
+        for &(i, c) in squares.iter().rev() {
+            if c != '0' {
+                other
+                    .set(at(i / 9 + 1, i % 9 + 1), c.to_digit(10).unwrap() as u8)
+                    .unwrap();
+            }
+        }
+        assert_eq!(board.zobrist_hash(), other.zobrist_hash());
+
+        // as does a board where the search filled in the squares
+        let mut solved = board.clone();
+        solved.solve_first().unwrap();
+        let mut given = SudokuBoard::fill_board(&solved.print_board()).unwrap();
+        assert_eq!(solved.zobrist_hash(), given.zobrist_hash());
+        given.clear(at(1, 1)).unwrap();
+        assert_ne!(solved.zobrist_hash(), given.zobrist_hash());
+    }
+
+    #[test]
+    fn test_solver() {
+        let board = SudokuBoard::fill_board(PUZZLE).unwrap();
+        let mut expected = board.clone();
+        let expected_stats = expected.solve_first().unwrap();
+
+        let mut solver = Solver::new(SolverOptions::default());
+        let mut solved = board.clone();
+        let stats = solver.solve(&mut solved).unwrap();
+        assert_eq!(solved.print_board(), expected.print_board());
+        assert_eq!(stats, expected_stats);
+        // solving again skips the dead ends of the first time
+        let mut again = board.clone();
+        let stats = solver.solve(&mut again).unwrap();
+        assert_eq!(again.print_board(), expected.print_board());
+        assert!(stats.backtracks < expected_stats.backtracks);
+        assert!(solver.skipped() > 0);
+
+        // counting the same with and without the table, as givens are removed
+        let mut without = Solver::new(SolverOptions {
+            transpositions: false,
+            ..SolverOptions::default()
+        });
+        let mut puzzle = board.clone();
+        for (i, c) in PUZZLE.chars().enumerate() {
+            if c != '0' {
+                puzzle.clear(at(i / 9 + 1, i % 9 + 1)).unwrap();
+                let count = puzzle.count_solutions(3);
+                assert_eq!(solver.count_solutions(&puzzle, 3), count);
+                assert_eq!(without.count_solutions(&puzzle, 3), count);
+                if count == 3 {
+                    break;
+                }
+            }
+        }
+        assert!(solver.skipped() > 0);
+        assert_eq!(without.skipped(), 0);
+
+        let mut empty = SudokuBoard::new();
+        assert!(solver.solve(&mut empty).is_ok() && empty.is_solved());
+    }
+}
@@ -0,0 +1,88 @@
+//! `proptest` strategies making boards, for property tests of code that
+//! uses the crate.
+//!
+//! ```ignore
+//! proptest! {
+//!     #[test]
+//!     fn solves(puzzle in sudoku::strategy::solvable_puzzles()) {
+//!         prop_assert!(puzzle.clone().solve_first().is_ok());
+//!     }
+//! }
+//! ```
+
+use crate::SudokuBoard;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Place the givens that fit, skipping a digit that clashes with the ones
+/// already placed or leaves a square without alternatives.  `cells` holds
+/// 81 digits, 0 for an unknown square.
+fn place_givens(cells: &[u8]) -> SudokuBoard {
+    let mut board = SudokuBoard::new();
+    for (i, digit) in cells.iter().enumerate().filter(|(_, d)| **d != 0) {
+        let mut next = board.clone();
+        if next
+            .mark_as_given(i / 9 + 1, i % 9 + 1, *digit as i32)
+            .is_ok()
+        {
+            board = next;
+        }
+    }
+    board
+}
+
+/// Solved boards.  They shrink towards the board the solver finds for an
+/// empty one.
+pub fn complete_grids() -> impl Strategy<Value = SudokuBoard> {
+    any::<u64>().prop_map(|seed| {
+        let mut board = SudokuBoard::new();
+        board.solve_seeded(seed).unwrap();
+        board
+    })
+}
+
+/// Puzzles with at least one solution: a complete grid with some squares
+/// left out.  They may have more than one solution.  They shrink towards
+/// fewer givens.
+pub fn solvable_puzzles() -> impl Strategy<Value = SudokuBoard> {
+    (complete_grids(), vec(any::<bool>(), 81)).prop_map(|(grid, keep)| {
+        let cells: Vec<u8> = grid
+            .print_board()
+            .chars()
+            .zip(keep)
+            .map(|(c, keep)| if keep { c as u8 - b'0' } else { 0 })
+            .collect();
+        place_givens(&cells)
+    })
+}
+
+/// Boards with givens that never clash, but that may have no solution.
+/// They shrink towards fewer and smaller givens.
+pub fn partial_boards() -> impl Strategy<Value = SudokuBoard> {
+    vec(0..=9u8, 81).prop_map(|cells| place_givens(&cells))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{complete_grids, partial_boards, solvable_puzzles};
+    use proptest::prelude::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_complete_grids(grid in complete_grids()) {
+            prop_assert!(grid.is_solved() && grid.is_valid());
+        }
+
+        #[test]
+        fn test_solvable_puzzles(puzzle in solvable_puzzles()) {
+            prop_assert!(puzzle.count_solutions(1) == 1);
+        }
+
+        #[test]
+        fn test_partial_boards(board in partial_boards()) {
+            prop_assert!(board.is_valid() && board.is_consistent());
+        }
+    }
+}
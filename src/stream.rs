@@ -0,0 +1,246 @@
+use crate::detect::{parse_json, parse_sdk};
+use crate::json::json_string;
+use crate::parse::is_separator;
+use crate::{InputFormat, SudokuBoard, SudokuError};
+use std::io::{self, BufRead, Write};
+
+/// How many puzzles a `solve_stream` solved, and how many it could not.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    pub solved: usize,
+    // Puzzles that could not be read or have no solution
+    pub failed: usize,
+}
+
+/// Where the solutions go, in the format the puzzles came in.
+struct Output<W> {
+    writer: W,
+    format: InputFormat,
+    stats: StreamStats,
+}
+
+impl<W: Write> Output<W> {
+    /// Solve one puzzle, read from `line` of the input, and write its
+    /// solution, or why it has none.
+    fn puzzle(
+        &mut self,
+        line: usize,
+        name: Option<&str>,
+        puzzle: Result<SudokuBoard, SudokuError>,
+    ) -> io::Result<()> {
+        let solved = puzzle.and_then(|mut board| board.solve_first().map(|_| board));
+        if solved.is_ok() {
+            self.stats.solved += 1;
+        } else {
+            self.stats.failed += 1;
+        }
+        let out = &mut self.writer;
+        if self.format == InputFormat::Json {
+            return match solved {
+                Ok(board) => writeln!(
+                    out,
+                    "{{\"line\":{},\"solution\":\"{}\",\"error\":null}}",
+                    line,
+                    board.print_board()
+                ),
+                Err(e) => writeln!(
+                    out,
+                    "{{\"line\":{},\"solution\":null,\"error\":{}}}",
+                    line,
+                    json_string(&e.to_string())
+                ),
+            };
+        }
+        if let Some(name) = name {
+            writeln!(out, "{}", name)?;
+        }
+        match solved {
+            Ok(board) if matches!(self.format, InputFormat::Line | InputFormat::Sdm) => {
+                writeln!(out, "{}", board.print_board())
+            }
+            Ok(board) => writeln!(out, "{}", board),
+            Err(e) => writeln!(out, "# line {}: {}", line, e),
+        }
+    }
+}
+
+/// The grid being read: its header, the line it starts on, and its rows.
+#[derive(Default)]
+struct Record {
+    name: Option<String>,
+    start: usize,
+    rows: String,
+    count: usize,
+}
+
+impl Record {
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.count == 0
+    }
+
+    /// Solve the grid read so far, which fails when it is cut short, and
+    /// start over.
+    fn finish<W: Write>(&mut self, out: &mut Output<W>) -> io::Result<()> {
+        let record = std::mem::take(self);
+        let board = SudokuBoard::parse_grid(&record.rows);
+        out.puzzle(record.start, record.name.as_deref(), board)
+    }
+}
+
+/// Solve the puzzles read from `reader` one at a time, each to the solution
+/// `solve_outcome_first` finds, writing each solution to `writer` as soon as
+/// it is found, so a collection of any size is solved in the memory of a
+/// single puzzle.
+///
+/// `format` tells how the puzzles are written, and the solutions are
+/// written the same way:
+///
+/// - `Line` and `Sdm`: a puzzle per line, answered by its solution on a
+///   line.
+/// - `Grid`: puzzles of 9 rows, framed or not, each optionally after a
+///   line containing `Grid` that names it.  The name is written before the
+///   solution's 9 rows.
+/// - `Sdk`: the whole input is a single puzzle.
+/// - `Json`: JSON lines, each an object or array `parse_any` reads, answered
+///   with an object per puzzle holding the `line` it came from, its
+///   `solution`, and an `error`, one of them null.
+///
+/// Blank lines are skipped.  A puzzle that cannot be read or has no
+/// solution does not stop the stream: outside JSON it is answered with a
+/// line `# line N: reason`.  Only reading or writing fails the call.
+pub fn solve_stream(
+    reader: impl BufRead,
+    writer: impl Write,
+    format: InputFormat,
+) -> io::Result<StreamStats> {
+    let mut out = Output {
+        writer,
+        format,
+        stats: StreamStats::default(),
+    };
+    let mut record = Record::default();
+    let mut sdk = String::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match format {
+            InputFormat::Line | InputFormat::Sdm => {
+                out.puzzle(i + 1, None, SudokuBoard::parse_grid(line))?;
+            }
+            InputFormat::Json => match parse_json(line) {
+                Ok(boards) => {
+                    for board in boards {
+                        out.puzzle(i + 1, None, Ok(board))?;
+                    }
+                }
+                Err(e) => out.puzzle(i + 1, None, Err(e))?,
+            },
+            InputFormat::Sdk => {
+                sdk.push_str(line);
+                sdk.push('\n');
+            }
+            InputFormat::Grid if line.contains("Grid") => {
+                if !record.is_empty() {
+                    record.finish(&mut out)?;
+                }
+                record.name = Some(line.to_string());
+                record.start = i + 1;
+            }
+            InputFormat::Grid => {
+                if record.is_empty() {
+                    record.start = i + 1;
+                }
+                record.rows.push_str(line);
+                record.rows.push('\n');
+                // frame lines between the rows of boxes are not rows
+                if !is_separator(line) {
+                    record.count += 1;
+                    if record.count == 9 {
+                        record.finish(&mut out)?;
+                    }
+                }
+            }
+        }
+    }
+    if !record.is_empty() {
+        record.finish(&mut out)?;
+    }
+    if !sdk.is_empty() {
+        out.puzzle(1, None, parse_sdk(&sdk))?;
+    }
+    out.writer.flush()?;
+    Ok(out.stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_stream, StreamStats};
+    use crate::{InputFormat, SudokuBoard};
+
+    const PUZZLE: &str =
+        "4....8.......91.8..865.2.3..2.4..9...1.2....6367.59........5...7..8...242..93..7.";
+
+    fn stream(input: &str, format: InputFormat) -> (String, StreamStats) {
+        let mut output = Vec::new();
+        let stats = solve_stream(input.as_bytes(), &mut output, format).unwrap();
+        (String::from_utf8(output).unwrap(), stats)
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        let mut solved = SudokuBoard::parse_grid(PUZZLE).unwrap();
+        solved.solve_first().unwrap();
+        let solution = solved.print_board();
+
+        let (output, stats) = stream(
+            &format!("{}\r\n\r\n{}\n123\n", PUZZLE, PUZZLE),
+            InputFormat::Sdm,
+        );
+        assert_eq!(
+            output,
+            format!(
+                "{0}\n{0}\n# line 4: expected 81 squares, found 3\n",
+                solution
+            )
+        );
+        assert_eq!(
+            stats,
+            StreamStats {
+                solved: 2,
+                failed: 1
+            }
+        );
+
+        let rows: Vec<&str> = (0..9).map(|r| &PUZZLE[r * 9..r * 9 + 9]).collect();
+        let solution_rows: Vec<&str> = (0..9).map(|r| &solution[r * 9..r * 9 + 9]).collect();
+        let input = format!(
+            "Grid 01\n{}\nGrid 02\n{}\n",
+            rows.join("\n"),
+            rows[..4].join("\n")
+        );
+        let (output, stats) = stream(&input, InputFormat::Grid);
+        assert_eq!(
+            output,
+            format!(
+                "Grid 01\n{}\nGrid 02\n# line 11: expected 81 squares, found 36\n",
+                solution_rows.join("\n")
+            )
+        );
+        assert_eq!(stats.failed, 1);
+
+        let input = format!("{{\"board\":\"{}\"}}\n{{\"board\":\"1\"}}\n", PUZZLE);
+        let (output, _) = stream(&input, InputFormat::Json);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!(
+                "{{\"line\":1,\"solution\":\"{}\",\"error\":null}}",
+                solution
+            )
+        );
+        assert!(lines[1].starts_with("{\"line\":2,\"solution\":null,\"error\":\""));
+    }
+}
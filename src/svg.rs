@@ -0,0 +1,134 @@
+use crate::html::escape;
+use crate::{BoxValue, SudokuBoard};
+
+/// How `to_svg` draws a board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    // Width and height of a square, in pixels
+    pub cell_size: u32,
+    // CSS font family of the digits
+    pub font_family: String,
+    // Show the candidates of unknown squares as small digits
+    pub candidates: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            cell_size: 40,
+            font_family: "sans-serif".to_string(),
+            candidates: false,
+        }
+    }
+}
+
+/// Room around the grid so the thick outer lines are not cut off.
+const MARGIN: u32 = 2;
+
+impl SudokuBoard {
+    /// Draw the board as an SVG image.  Givens are bold and squares filled
+    /// in since are blue, as in `to_html`, and the 3x3 squares are framed
+    /// with thicker lines.  The image is `9 * cell_size` pixels square plus
+    /// a small margin.
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let cell = options.cell_size;
+        let size = 9 * cell + 2 * MARGIN;
+        let mut lines = vec![
+            format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" viewBox=\"0 0 {0} {0}\">",
+                size
+            ),
+            format!("<rect width=\"{0}\" height=\"{0}\" fill=\"#fff\"/>", size),
+            format!(
+                "<g font-family=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">",
+                escape(&options.font_family)
+            ),
+        ];
+        for node in self.board.iter().flatten() {
+            let x = MARGIN + (node.col as u32 - 1) * cell;
+            let y = MARGIN + (node.row as u32 - 1) * cell;
+            match &node.value {
+                BoxValue::Known(v) => {
+                    let style = if node.given {
+                        "font-weight=\"bold\" fill=\"#000\""
+                    } else {
+                        "fill=\"#1a5fb4\""
+                    };
+                    lines.push(format!(
+                        "<text x=\"{}\" y=\"{}\" font-size=\"{}\" {}>{}</text>",
+                        x + cell / 2,
+                        y + cell / 2,
+                        cell * 3 / 5,
+                        style,
+                        v
+                    ));
+                }
+                BoxValue::Unknown(set) if options.candidates => {
+                    for d in set {
+                        let (r, c) = ((*d as u32 - 1) / 3, (*d as u32 - 1) % 3);
+                        lines.push(format!(
+                            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"#666\">{}</text>",
+                            x + cell * (2 * c + 1) / 6,
+                            y + cell * (2 * r + 1) / 6,
+                            cell / 4,
+                            d
+                        ));
+                    }
+                }
+                BoxValue::Unknown(_) => (),
+            }
+        }
+        lines.push("</g>".to_string());
+        for i in 0..=9 {
+            let at = MARGIN + i * cell;
+            let (end, width) = (MARGIN + 9 * cell, if i % 3 == 0 { 3 } else { 1 });
+            lines.push(format!(
+                "<line x1=\"{0}\" y1=\"{1}\" x2=\"{0}\" y2=\"{2}\" stroke=\"#000\" stroke-width=\"{3}\"/>",
+                at, MARGIN, end, width
+            ));
+            lines.push(format!(
+                "<line x1=\"{1}\" y1=\"{0}\" x2=\"{2}\" y2=\"{0}\" stroke=\"#000\" stroke-width=\"{3}\"/>",
+                at, MARGIN, end, width
+            ));
+        }
+        lines.push("</svg>".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SvgOptions;
+    use crate::pos::at;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_to_svg() {
+        let s = "53--7----6--195----98----6-".to_string() + &"-".repeat(54);
+        let mut board = SudokuBoard::fill_board(&s).unwrap();
+        board.set(at(1, 3), 4).unwrap();
+        let svg = board.to_svg(&SvgOptions::default());
+        assert!(svg
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"364\" height=\"364\""));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(
+            "<text x=\"22\" y=\"22\" font-size=\"24\" font-weight=\"bold\" fill=\"#000\">5</text>"
+        ));
+        assert!(svg.contains("<text x=\"102\" y=\"22\" font-size=\"24\" fill=\"#1a5fb4\">4</text>"));
+        assert_eq!(svg.matches("<text").count(), 11);
+        assert_eq!(svg.matches("<line").count(), 20);
+        assert_eq!(svg.matches("stroke-width=\"3\"").count(), 8);
+
+        // r1c4 can be 2, 6, or 8, drawn small in their places
+        let options = SvgOptions {
+            cell_size: 60,
+            font_family: "\"Fira Sans\", serif".to_string(),
+            candidates: true,
+        };
+        let svg = board.to_svg(&options);
+        assert!(svg.contains("width=\"544\""));
+        assert!(svg.contains("font-family=\"&quot;Fira Sans&quot;, serif\""));
+        assert!(svg.contains("<text x=\"212\" y=\"12\" font-size=\"15\" fill=\"#666\">2</text>"));
+        assert!(svg.contains("<text x=\"232\" y=\"32\" font-size=\"15\" fill=\"#666\">6</text>"));
+    }
+}
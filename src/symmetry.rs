@@ -0,0 +1,130 @@
+use crate::SudokuBoard;
+
+/// A symmetry of the pattern of givens.  Squares that map onto each other
+/// are either all givens or all unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    // No symmetry
+    None,
+    // Turning the board half a turn gives the same pattern
+    Rotational,
+    // Turning the board a quarter turn gives the same pattern
+    QuarterTurn,
+    // Mirrored left to right
+    Horizontal,
+    // Mirrored top to bottom
+    Vertical,
+    // Mirrored along the diagonal from the top left to the bottom right
+    Diagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 6] = [
+        Symmetry::None,
+        Symmetry::Rotational,
+        Symmetry::QuarterTurn,
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Diagonal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Rotational => "rotational",
+            Symmetry::QuarterTurn => "quarter-turn",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::Diagonal => "diagonal",
+        }
+    }
+
+    /// The squares (as 0 based index row * 9 + col) that `index` maps onto,
+    /// including itself.
+    pub(crate) fn orbit(&self, index: usize) -> Vec<usize> {
+        let (r, c) = (index / 9, index % 9);
+        let mut orbit = match self {
+            Symmetry::None => vec![(r, c)],
+            Symmetry::Rotational => vec![(r, c), (8 - r, 8 - c)],
+            Symmetry::QuarterTurn => vec![(r, c), (c, 8 - r), (8 - r, 8 - c), (8 - c, r)],
+            Symmetry::Horizontal => vec![(r, c), (r, 8 - c)],
+            Symmetry::Vertical => vec![(r, c), (8 - r, c)],
+            Symmetry::Diagonal => vec![(r, c), (c, r)],
+        }
+        .into_iter()
+        .map(|(r, c)| r * 9 + c)
+        .collect::<Vec<usize>>();
+        orbit.sort();
+        orbit.dedup();
+        orbit
+    }
+}
+
+impl SudokuBoard {
+    /// The symmetries the pattern of givens has, other than `None`, in the
+    /// order of `Symmetry::ALL`.  Only which squares are givens matters,
+    /// not their digits.
+    pub fn clue_symmetries(&self) -> Vec<Symmetry> {
+        let given: Vec<bool> = self.board.iter().flatten().map(|node| node.given).collect();
+        Symmetry::ALL
+            .into_iter()
+            .filter(|s| *s != Symmetry::None)
+            .filter(|s| (0..81).all(|i| s.orbit(i).into_iter().all(|j| given[j] == given[i])))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Symmetry;
+    use crate::SudokuBoard;
+
+    #[test]
+    fn test_orbit() {
+        assert_eq!(Symmetry::None.orbit(10), vec![10]);
+        assert_eq!(Symmetry::Rotational.orbit(0), vec![0, 80]);
+        assert_eq!(Symmetry::Rotational.orbit(40), vec![40]);
+        assert_eq!(Symmetry::QuarterTurn.orbit(0), vec![0, 8, 72, 80]);
+        assert_eq!(Symmetry::Horizontal.orbit(9), vec![9, 17]);
+        assert_eq!(Symmetry::Vertical.orbit(9), vec![9, 63]);
+        assert_eq!(Symmetry::Diagonal.orbit(1), vec![1, 9]);
+    }
+
+    #[test]
+    fn test_clue_symmetries() {
+        // givens in the four corners and the middle
+        let mut s = "-".repeat(81);
+        for (i, digit) in [(0, "1"), (8, "2"), (40, "5"), (72, "3"), (80, "4")] {
+            s.replace_range(i..=i, digit);
+        }
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(sboard.clue_symmetries(), Symmetry::ALL[1..].to_vec());
+
+        s.replace_range(1..=1, "5");
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(sboard.clue_symmetries(), vec![]);
+        s.replace_range(79..=79, "6");
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(sboard.clue_symmetries(), vec![Symmetry::Rotational]);
+        s.replace_range(7..=7, "7");
+        s.replace_range(73..=73, "8");
+        let sboard = SudokuBoard::fill_board(&s).unwrap();
+        assert_eq!(
+            sboard.clue_symmetries(),
+            vec![
+                Symmetry::Rotational,
+                Symmetry::Horizontal,
+                Symmetry::Vertical
+            ]
+        );
+
+        // squares filled in do not count
+        let mut solved = sboard.clone();
+        solved.solve_first().unwrap();
+        assert_eq!(solved.clue_symmetries(), sboard.clue_symmetries());
+        assert_eq!(
+            SudokuBoard::new().clue_symmetries(),
+            Symmetry::ALL[1..].to_vec()
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{SolveStats, SudokuBoard, SudokuError};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// How many guesses `solve_async` makes between giving the executor a
+/// chance to run other tasks.
+const YIELD_EVERY: usize = 64;
+
+impl SudokuBoard {
+    /// Fill in the solution `solve_outcome_first` finds, handing control
+    /// back to the executor every few guesses so a long search does not
+    /// block it.  Any executor can drive the future.  Dropping it part way
+    /// cancels the solve and leaves the board as it was.
+    pub async fn solve_async(&mut self) -> Result<SolveStats, SudokuError> {
+        let mut stats = SolveStats::default();
+        let mut board = self.clone();
+        board.fill_singles(None)?;
+        // each guess still being tried: the board before the guess, the
+        // square, and the alternatives left to try there
+        let mut guesses = Vec::new();
+        loop {
+            let (row, col, alternatives) = match board.guess_square() {
+                Some(guess) => guess,
+                None => {
+                    *self = board;
+                    return Ok(stats);
+                }
+            };
+            guesses.push((board, row, col, alternatives.into_iter()));
+            board = loop {
+                let (before, row, col, alternatives) = match guesses.last_mut() {
+                    Some(guess) => guess,
+                    None => return Err(SudokuError::NotSolvable(None)),
+                };
+                let digit = match alternatives.next() {
+                    Some(digit) => digit,
+                    None => {
+                        // every alternative failed, so the guess before
+                        // this one was wrong
+                        guesses.pop();
+                        if !guesses.is_empty() {
+                            stats.backtracks += 1;
+                        }
+                        continue;
+                    }
+                };
+                stats.guesses += 1;
+                let mut next = before.clone();
+                let (row, col) = (*row, *col);
+                if stats.guesses % YIELD_EVERY == 0 {
+                    YieldNow(false).await;
+                }
+                match next
+                    .mark_as_known(row, col, digit)
+                    .and_then(|_| next.fill_singles(None))
+                {
+                    Ok(()) => break next,
+                    Err(_) => stats.backtracks += 1,
+                }
+            };
+        }
+    }
+}
+
+/// A future that is pending once, waking its task straight away, so the
+/// executor can poll other tasks before this one carries on.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SudokuBoard;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Poll a future until it is ready, counting the polls.
+    fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        let mut polls = 1;
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return (output, polls),
+                Poll::Pending => polls += 1,
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_async() {
+        let s = concat!(
+            "---------",
+            "-----3-85",
+            "--1-2----",
+            "---5-7---",
+            "--4---1--",
+            "-9-------",
+            "5------73",
+            "--2-1----",
+            "----4---9"
+        );
+        let puzzle = SudokuBoard::fill_board(s).unwrap();
+        let mut expected = puzzle.clone();
+        let expected_stats = expected.solve_first().unwrap();
+
+        let mut sboard = puzzle.clone();
+        let (stats, polls) = block_on(sboard.solve_async());
+        let stats = stats.unwrap();
+        assert!(sboard == expected);
+        assert_eq!(stats.guesses, expected_stats.guesses);
+        assert_eq!(stats.backtracks, expected_stats.backtracks);
+        assert!(polls > 1);
+
+        // dropping the future part way leaves the board alone
+        let mut sboard = puzzle.clone();
+        {
+            let waker = Waker::from(Arc::new(Noop));
+            let mut future = pin!(sboard.solve_async());
+            let poll = future.as_mut().poll(&mut Context::from_waker(&waker));
+            assert!(poll.is_pending());
+        }
+        assert!(sboard == puzzle);
+
+        // r1c8 and r1c9 can both only be 9
+        let mut broken = SudokuBoard::fill_board(concat!(
+            "1234567--",
+            "---------",
+            "---------",
+            "-------8-",
+            "---------",
+            "---------",
+            "--------8",
+            "---------",
+            "---------"
+        ))
+        .unwrap();
+        assert!(block_on(broken.solve_async()).0.is_err());
+    }
+}
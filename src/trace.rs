@@ -0,0 +1,199 @@
+//! Instrumentation with the `tracing` crate.  Without the `tracing`
+//! feature every function here does nothing, so the solver calls them
+//! unconditionally.
+//!
+//! Phases are spans at the `DEBUG` level: `parse`, `propagate`, `strategy`
+//! with the technique tried, and `branch` with the guess being searched.
+//! Changes to squares are events at the `TRACE` level: `place` and
+//! `eliminate`, with the square, the digit, and the cause.
+
+#[cfg(feature = "tracing")]
+pub(crate) use enabled::*;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use disabled::*;
+
+#[cfg(feature = "tracing")]
+mod enabled {
+    use crate::{BoxValue, Cause, SudokuBoard, Technique};
+    use tracing::span::EnteredSpan;
+    use tracing::{debug_span, trace, Level};
+
+    pub(crate) type Guard = EnteredSpan;
+
+    pub(crate) fn parse_span(format: &'static str) -> Guard {
+        debug_span!("parse", format).entered()
+    }
+
+    pub(crate) fn propagate_span() -> Guard {
+        debug_span!("propagate").entered()
+    }
+
+    pub(crate) fn strategy_span(technique: Technique) -> Guard {
+        debug_span!("strategy", technique = ?technique).entered()
+    }
+
+    pub(crate) fn branch_span(row: usize, col: usize, digit: i32) -> Guard {
+        debug_span!("branch", row, col, digit).entered()
+    }
+
+    /// Report the digit placed in the square, and the alternatives it is
+    /// about to remove from the peers.
+    pub(crate) fn placed(board: &SudokuBoard, row: usize, col: usize, digit: i32, cause: Cause) {
+        if !tracing::enabled!(Level::TRACE) {
+            return;
+        }
+        trace!(row, col, digit, cause = ?cause, "place");
+        for (r, c) in board.peers(row, col) {
+            if matches!(&board.board[r - 1][c - 1].value, BoxValue::Unknown(v) if v.contains(&digit))
+            {
+                eliminated(r, c, digit, Cause::Peer { row, col });
+            }
+        }
+    }
+
+    pub(crate) fn eliminated(row: usize, col: usize, digit: i32, cause: Cause) {
+        trace!(row, col, digit, cause = ?cause, "eliminate");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+mod disabled {
+    use crate::{Cause, SudokuBoard, Technique};
+
+    pub(crate) struct Guard;
+
+    pub(crate) fn parse_span(_format: &'static str) -> Guard {
+        Guard
+    }
+
+    pub(crate) fn propagate_span() -> Guard {
+        Guard
+    }
+
+    pub(crate) fn strategy_span(_technique: Technique) -> Guard {
+        Guard
+    }
+
+    pub(crate) fn branch_span(_row: usize, _col: usize, _digit: i32) -> Guard {
+        Guard
+    }
+
+    pub(crate) fn placed(
+        _board: &SudokuBoard,
+        _row: usize,
+        _col: usize,
+        _digit: i32,
+        _cause: Cause,
+    ) {
+    }
+
+    pub(crate) fn eliminated(_row: usize, _col: usize, _digit: i32, _cause: Cause) {}
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use crate::{Event, SudokuBoard};
+    use std::collections::BTreeMap;
+    use std::fmt;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Metadata, Subscriber};
+
+    type Counts = Arc<Mutex<BTreeMap<String, usize>>>;
+
+    /// Counts the spans by name and the events by message.
+    struct Counter {
+        counts: Counts,
+        next_id: AtomicU64,
+    }
+
+    impl Counter {
+        fn count(&self, name: String) {
+            *self.counts.lock().unwrap().entry(name).or_default() += 1;
+        }
+    }
+
+    struct Message(String);
+
+    impl Visit for Message {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for Counter {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.count(span.metadata().name().to_string());
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut message = Message(String::new());
+            event.record(&mut message);
+            self.count(message.0);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_tracing() {
+        let counts = Counts::default();
+        let counter = Counter {
+            counts: counts.clone(),
+            next_id: AtomicU64::new(0),
+        };
+        let mut events = Vec::new();
+        let stats = tracing::subscriber::with_default(counter, || {
+            let mut board = SudokuBoard::fill_board(concat!(
+                "100007090",
+                "030020008",
+                "009600500",
+                "005300900",
+                "010080002",
+                "600004000",
+                "300000010",
+                "040000007",
+                "007000300"
+            ))
+            .unwrap();
+            let step = board.next_step().unwrap();
+            board
+                .apply_step_with_observer(&step, |e| events.push(*e))
+                .unwrap();
+            board.solve_with_observer(|e| events.push(*e)).unwrap()
+        });
+
+        let counts = counts.lock().unwrap();
+        let count = |name: &str| counts.get(name).copied().unwrap_or(0);
+        assert_eq!(count("parse"), 1);
+        assert!(count("propagate") > 0 && count("strategy") > 0);
+        assert_eq!(count("branch"), stats.guesses);
+        // the events tell the same story as an observer
+        let known = events
+            .iter()
+            .filter(|e| matches!(e, Event::Known { .. }))
+            .count();
+        let eliminated = events
+            .iter()
+            .filter(|e| matches!(e, Event::Eliminated { .. }))
+            .count();
+        assert_eq!(count("place"), known);
+        assert_eq!(count("eliminate"), eliminated);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{peer_table, CellPos, SudokuBoard, SudokuError};
+use std::collections::BTreeSet;
+
+/// Most other fillings looked at for each group of cleared squares, so the
+/// larger groups stay quick.
+const MAX_FILLINGS: usize = 512;
+
+/// Fill the cleared squares of `grid` every way the rules allow, up to
+/// `MAX_FILLINGS` ways, collecting each full grid.
+fn fillings(
+    grid: &mut [u8; 81],
+    cleared: &[usize],
+    peers: &[Vec<usize>],
+    found: &mut Vec<[u8; 81]>,
+) {
+    let Some((&i, rest)) = cleared.split_first() else {
+        found.push(*grid);
+        return;
+    };
+    for digit in 1..=9 {
+        if found.len() >= MAX_FILLINGS {
+            return;
+        }
+        if peers[i].iter().all(|&j| grid[j] != digit) {
+            grid[i] = digit;
+            fillings(grid, rest, peers, found);
+            grid[i] = 0;
+        }
+    }
+}
+
+impl SudokuBoard {
+    /// Find small unavoidable sets of a solved board: squares that could
+    /// swap digits among themselves and still give a valid grid, so every
+    /// puzzle with this solution needs a given in each of them.  Only sets
+    /// of at most `max_size` squares that hold no smaller set found are
+    /// returned, smallest first.
+    ///
+    /// The sets are found by clearing the squares of two digits, and of
+    /// three digits in a band or stack, and filling them in again every
+    /// other way.  This finds the usual small sets but not necessarily all
+    /// of them.  Fails with `NoFullySolved` unless the board is solved.
+    pub fn unavoidable_sets(&self, max_size: usize) -> Result<Vec<Vec<CellPos>>, SudokuError> {
+        if !self.is_solved() {
+            return Err(SudokuError::NoFullySolved);
+        }
+        let solution = self.as_bytes().map(|b| b - b'0');
+        let peers = peer_table();
+
+        // the bands, then the stacks
+        let regions: Vec<Vec<usize>> = (0..3)
+            .map(|band| (0..81).filter(|i| i / 27 == band).collect())
+            .chain((0..3).map(|stack| (0..81).filter(|i| i % 9 / 3 == stack).collect()))
+            .collect();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for a in 1..=9 {
+            for b in a + 1..=9 {
+                groups.push((0..81).filter(|&i| [a, b].contains(&solution[i])).collect());
+                for c in b + 1..=9 {
+                    for region in &regions {
+                        let digits = [a, b, c];
+                        groups.push(
+                            region
+                                .iter()
+                                .copied()
+                                .filter(|&i| digits.contains(&solution[i]))
+                                .collect(),
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut sets = BTreeSet::new();
+        for cleared in groups {
+            let mut grid = solution;
+            for &i in &cleared {
+                grid[i] = 0;
+            }
+            let mut found = Vec::new();
+            fillings(&mut grid, &cleared, &peers, &mut found);
+            for filling in found {
+                let changed: Vec<usize> = (0..81).filter(|&i| filling[i] != solution[i]).collect();
+                if !changed.is_empty() && changed.len() <= max_size {
+                    sets.insert(changed);
+                }
+            }
+        }
+
+        // a set holding a smaller one needs no given of its own
+        let mut sets: Vec<Vec<usize>> = sets.into_iter().collect();
+        sets.sort_by_key(|set| set.len());
+        let mut minimal: Vec<Vec<usize>> = Vec::new();
+        for set in sets {
+            if !minimal.iter().any(|m| m.iter().all(|i| set.contains(i))) {
+                minimal.push(set);
+            }
+        }
+        Ok(minimal
+            .into_iter()
+            .map(|set| {
+                set.into_iter()
+                    .map(|i| CellPos::from_index(i).unwrap())
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos::at;
+    use crate::{SudokuBoard, SudokuError};
+
+    #[test]
+    fn test_unavoidable_sets() {
+        let mut grid = SudokuBoard::fill_board(concat!(
+            "4----8---",
+            "----91-8-",
+            "-865-2-3-",
+            "-2-4--9--",
+            "-1-2----6",
+            "367-59---",
+            "-----5---",
+            "7--8---24",
+            "2--93--7-"
+        ))
+        .unwrap();
+        assert!(matches!(
+            grid.unavoidable_sets(6),
+            Err(SudokuError::NoFullySolved)
+        ));
+        grid.solve_first().unwrap();
+        let sets = grid.unavoidable_sets(6).unwrap();
+        assert!(!sets.is_empty());
+        assert!(sets.windows(2).all(|w| w[0].len() <= w[1].len()));
+        for set in &sets {
+            assert!(set.len() >= 4 && set.len() <= 6);
+            // without givens in the set the puzzle has another solution
+            let mut puzzle = grid.clone();
+            for pos in set {
+                puzzle.clear(at(pos.row.get(), pos.col.get())).unwrap();
+            }
+            assert_eq!(puzzle.count_solutions(2), 2);
+        }
+        for (i, a) in sets.iter().enumerate() {
+            for b in &sets[i + 1..] {
+                assert!(!a.iter().all(|pos| b.contains(pos)));
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{SolveOutcome, SudokuBoard};
+
+/// Read the one puzzle in `puzzle` with `parse_any`.
+fn read(puzzle: &str) -> Result<SudokuBoard, String> {
+    let (_, mut boards) = SudokuBoard::parse_any(puzzle).map_err(|e| e.to_string())?;
+    match boards.len() {
+        1 => Ok(boards.remove(0)),
+        n => Err(format!("expected one puzzle, found {}", n)),
+    }
+}
+
+/// Solve a puzzle given as `parse_any` reads it, answering with the 81
+/// digits of its solution.  A puzzle that cannot be read, has no solution,
+/// or has more than one fails with the reason.
+#[wasm_bindgen]
+pub fn solve(puzzle: &str) -> Result<String, String> {
+    match read(puzzle)?.solve_outcome() {
+        SolveOutcome::Unique(solution) => Ok(solution.to_line()),
+        SolveOutcome::Multiple(_, _) => Err("the puzzle has more than one solution".to_string()),
+        SolveOutcome::Unsolvable => Err("the puzzle has no solution".to_string()),
+        // `solve_outcome` always looks for a second solution
+        SolveOutcome::Found(solution) => Ok(solution.to_line()),
+        SolveOutcome::Incomplete(e) => Err(e.to_string()),
+    }
+}
+
+/// Count the solutions of a puzzle given as `parse_any` reads it, stopping
+/// at `limit`.
+#[wasm_bindgen]
+pub fn count_solutions(puzzle: &str, limit: usize) -> Result<usize, String> {
+    Ok(read(puzzle)?.count_solutions(limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_solutions, solve};
+
+    const PUZZLE: &str = concat!(
+        "4----8---",
+        "----91-8-",
+        "-865-2-3-",
+        "-2-4--9--",
+        "-1-2----6",
+        "367-59---",
+        "-----5---",
+        "7--8---24",
+        "2--93--7-"
+    );
+
+    #[test]
+    fn test_wasm_solve() {
+        let line = solve(PUZZLE).unwrap();
+        assert_eq!(line.len(), 81);
+        assert!(line.starts_with('4'));
+        assert!(solve(&"-".repeat(81)).is_err());
+        assert!(solve("12").is_err());
+        assert_eq!(count_solutions(PUZZLE, 2), Ok(1));
+        assert_eq!(count_solutions(&"-".repeat(81), 3), Ok(3));
+    }
+}